@@ -1,5 +1,5 @@
-use geo::{Distance, Euclidean, Geodesic, Haversine, Rhumb};
-use geo_traits::to_geo::ToGeoGeometry;
+use geo::{Distance, Euclidean, Haversine};
+use geo_traits::to_geo::ToGeoPoint;
 use geo_types::point;
 use geob::{Geob, SRID};
 fn main() -> Result<(), Box<dyn core::error::Error>> {
@@ -9,31 +9,25 @@ fn main() -> Result<(), Box<dyn core::error::Error>> {
     let lygten = point!(x:12.5378308, y: 55.7036352);
     // println!("{:?}", geob);
 
-    let rust = Geob::from_geo_type(&rust, SRID::WEB_MERCATOR.into());
-    let lygten = Geob::from_geo_type(&lygten, SRID::WEB_MERCATOR.into());
+    let rust = Geob::from_geo_type(&rust, SRID::WGS84);
+    let lygten = Geob::from_geo_type(&lygten, SRID::WGS84);
 
     println!(
         "Distance {}",
         Haversine.distance(
-            rust.geometry().to_geometry().into_point().unwrap(),
-            lygten.geometry().to_geometry().into_point().unwrap()
+            rust.as_point().unwrap().to_point(),
+            lygten.as_point().unwrap().to_point()
         )
     );
 
+    let rust = rust.project_into(SRID::WEB_MERCATOR.into());
+    let lygten = lygten.project_into(SRID::WEB_MERCATOR.into());
+
     println!(
         "Distance {}",
-        Haversine.distance(
-            rust.project_into(4096)
-                .geometry()
-                .to_geometry()
-                .into_point()
-                .unwrap(),
-            lygten
-                .project_into(4096)
-                .geometry()
-                .to_geometry()
-                .into_point()
-                .unwrap()
+        Euclidean.distance(
+            rust.as_point().unwrap().to_point(),
+            lygten.as_point().unwrap().to_point()
         )
     );
 
@@ -0,0 +1,16 @@
+#![no_main]
+
+use geob::{Geob, GeobRef};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(geo) = GeobRef::from_bytes(data) else {
+        return;
+    };
+
+    let text = geo.to_string();
+    let reparsed = Geob::from_text(&text).expect("reparse displayed geometry");
+    assert_eq!(reparsed.to_string(), text);
+    // Compared as geobs, NaN equals NaN
+    assert_eq!(reparsed.as_ref(), geo);
+});
@@ -0,0 +1,20 @@
+#![no_main]
+
+use geob::Geob;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(geo) = Geob::from_bytes(data) else {
+        return;
+    };
+
+    // Anything accepted must be safe to walk
+    let _ = geo.envelope();
+    let _ = geo.to_string();
+    assert_eq!(geo.to_geometry().kind(), geo.kind());
+    assert_eq!(geo.as_ref().encoded_len(), data.len());
+
+    // Toggling the envelope keeps the blob valid
+    assert!(Geob::from_bytes(geo.with_envelope().as_ref().bytes).is_ok());
+    assert!(Geob::from_bytes(geo.without_envelope().as_ref().bytes).is_ok());
+});
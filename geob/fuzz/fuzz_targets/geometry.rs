@@ -0,0 +1,26 @@
+#![no_main]
+
+use geob::{GeobRef, types::GeometryRef};
+use libfuzzer_sys::fuzz_target;
+
+fn walk(geo: GeometryRef<'_>) {
+    let _ = format!("{geo:?}");
+
+    if let GeometryRef::Collection(collection) = geo {
+        for idx in 0..=collection.len() {
+            if let Some(child) = collection.get(idx) {
+                walk(child);
+            }
+        }
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let geo = GeobRef { bytes: data };
+    let _ = geo.try_endian();
+    let _ = geo.try_kind();
+
+    if let Ok(root) = geo.try_geometry() {
+        walk(root);
+    }
+});
@@ -0,0 +1,30 @@
+#![no_main]
+
+use geob::{Geob, wkt};
+use libfuzzer_sys::fuzz_target;
+use udled::bytes::Endian;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&first, rest)) = data.split_first() else {
+        return;
+    };
+    let Ok(input) = core::str::from_utf8(rest) else {
+        return;
+    };
+
+    let endian = if first & 1 == 0 {
+        Endian::Big
+    } else {
+        Endian::Lt
+    };
+
+    let Ok(geo) = wkt::parse(input, endian) else {
+        return;
+    };
+
+    // The parser must only produce valid blobs that survive a text round trip
+    assert!(Geob::from_bytes(geo.as_ref().bytes).is_ok());
+    let text = geo.to_string();
+    let reparsed = wkt::parse(&text, endian).expect("reparse displayed geometry");
+    assert_eq!(reparsed.to_string(), text);
+});
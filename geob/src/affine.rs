@@ -0,0 +1,288 @@
+use alloc::vec::Vec;
+use libm::{cos, round, sin};
+
+use crate::{Coord, Geob, Geometry, builder::GeometryWriter};
+
+/// 2D affine transform, mapping `(x, y)` to
+/// `(a * x + b * y + xoff, d * x + e * y + yoff)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Affine {
+    pub a: f64,
+    pub b: f64,
+    pub d: f64,
+    pub e: f64,
+    pub xoff: f64,
+    pub yoff: f64,
+}
+
+impl Affine {
+    pub const IDENTITY: Affine = Affine::new(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+
+    pub const fn new(a: f64, b: f64, d: f64, e: f64, xoff: f64, yoff: f64) -> Affine {
+        Affine {
+            a,
+            b,
+            d,
+            e,
+            xoff,
+            yoff,
+        }
+    }
+
+    pub const fn translate(dx: f64, dy: f64) -> Affine {
+        Affine::new(1.0, 0.0, 0.0, 1.0, dx, dy)
+    }
+
+    pub const fn scale(sx: f64, sy: f64) -> Affine {
+        Affine::new(sx, 0.0, 0.0, sy, 0.0, 0.0)
+    }
+
+    /// Counter clockwise rotation by `angle` radians around the origin
+    pub fn rotate(angle: f64) -> Affine {
+        let (sin, cos) = (sin(angle), cos(angle));
+        Affine::new(cos, -sin, sin, cos, 0.0, 0.0)
+    }
+
+    /// Counter clockwise rotation by `angle` radians around `(x, y)`
+    pub fn rotate_around(angle: f64, x: f64, y: f64) -> Affine {
+        Affine::translate(-x, -y)
+            .then(&Affine::rotate(angle))
+            .then(&Affine::translate(x, y))
+    }
+
+    /// Transform applying `self` first and `next` second
+    pub fn then(&self, next: &Affine) -> Affine {
+        Affine::new(
+            next.a * self.a + next.b * self.d,
+            next.a * self.b + next.b * self.e,
+            next.d * self.a + next.e * self.d,
+            next.d * self.b + next.e * self.e,
+            next.a * self.xoff + next.b * self.yoff + next.xoff,
+            next.d * self.xoff + next.e * self.yoff + next.yoff,
+        )
+    }
+
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.a * x + self.b * y + self.xoff,
+            self.d * x + self.e * y + self.yoff,
+        )
+    }
+}
+
+impl Default for Affine {
+    fn default() -> Affine {
+        Affine::IDENTITY
+    }
+}
+
+impl Geob {
+    /// Apply an affine transform to every coordinate in place
+    pub fn affine(&mut self, transform: &Affine) {
+        self.map_coords(|x, y| transform.apply(x, y));
+    }
+
+    /// Swap x and y of every coordinate, for geometries written in the wrong
+    /// axis order
+    pub fn flip_coordinates(&mut self) {
+        self.map_coords(|x, y| (y, x));
+    }
+
+    /// Copy with every coordinate rounded to the nearest point of a grid
+    /// anchored at `origin`. An axis with a size of zero is left as is.
+    ///
+    /// Like PostGIS, repeated consecutive coordinates are dropped, then
+    /// linestrings with fewer than two coordinates, rings with fewer than four
+    /// and polygons without an exterior ring. Collapsed parts are left out of
+    /// multi geometries and collections, a collapsed root geometry is empty.
+    pub fn snap_to_grid(&self, origin: (f64, f64), size: (f64, f64)) -> Geob {
+        let grid = Grid { origin, size };
+        let geometry = grid.geometry(self.to_geometry());
+
+        let (srid, endian) = (self.srid(), self.endian());
+        let mut writer = if self.has_envelope() {
+            GeometryWriter::with_envelope(Vec::new(), srid, endian)
+        } else {
+            GeometryWriter::new(Vec::new(), srid, endian)
+        }
+        .unwrap();
+
+        geometry.write(&mut writer).unwrap();
+        writer.build().unwrap()
+    }
+}
+
+struct Grid {
+    origin: (f64, f64),
+    size: (f64, f64),
+}
+
+impl Grid {
+    fn coord(&self, coord: Coord) -> Coord {
+        let snap = |n: f64, origin: f64, size: f64| {
+            if size == 0.0 {
+                n
+            } else {
+                origin + round((n - origin) / size) * size
+            }
+        };
+
+        Coord::from((
+            snap(coord.x, self.origin.0, self.size.0),
+            snap(coord.y, self.origin.1, self.size.1),
+        ))
+    }
+
+    fn coords(&self, coords: Vec<Coord>) -> Vec<Coord> {
+        let mut output: Vec<Coord> = Vec::with_capacity(coords.len());
+        for coord in coords {
+            let coord = self.coord(coord);
+            if output.last() != Some(&coord) {
+                output.push(coord);
+            }
+        }
+        output
+    }
+
+    fn line(&self, line: Vec<Coord>) -> Option<Vec<Coord>> {
+        let line = self.coords(line);
+        (line.len() >= 2).then_some(line)
+    }
+
+    fn polygon(&self, rings: Vec<Vec<Coord>>) -> Option<Vec<Vec<Coord>>> {
+        let mut rings = rings.into_iter().map(|ring| self.coords(ring));
+
+        let exterior = rings.next().filter(|ring| ring.len() >= 4)?;
+        Some(
+            core::iter::once(exterior)
+                .chain(rings.filter(|ring| ring.len() >= 4))
+                .collect(),
+        )
+    }
+
+    fn geometry(&self, geometry: Geometry) -> Geometry {
+        match geometry {
+            Geometry::Point(coord) => Geometry::Point(self.coord(coord)),
+            Geometry::LineString(line) => Geometry::LineString(self.line(line).unwrap_or_default()),
+            Geometry::Polygon(rings) => Geometry::Polygon(self.polygon(rings).unwrap_or_default()),
+            Geometry::MultiPoint(coords) => {
+                Geometry::MultiPoint(coords.into_iter().map(|c| self.coord(c)).collect())
+            }
+            Geometry::MultiLineString(lines) => Geometry::MultiLineString(
+                lines
+                    .into_iter()
+                    .filter_map(|line| self.line(line))
+                    .collect(),
+            ),
+            Geometry::MultiPolygon(polygons) => Geometry::MultiPolygon(
+                polygons
+                    .into_iter()
+                    .filter_map(|rings| self.polygon(rings))
+                    .collect(),
+            ),
+            Geometry::Collection(geometries) => Geometry::Collection(
+                geometries
+                    .into_iter()
+                    .map(|geo| self.geometry(geo))
+                    .filter(|geo| !collapsed(geo))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+fn collapsed(geometry: &Geometry) -> bool {
+    match geometry {
+        Geometry::Point(_) => false,
+        Geometry::LineString(coords) | Geometry::MultiPoint(coords) => coords.is_empty(),
+        Geometry::Polygon(rings) | Geometry::MultiLineString(rings) => rings.is_empty(),
+        Geometry::MultiPolygon(polygons) => polygons.is_empty(),
+        Geometry::Collection(geometries) => geometries.is_empty(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::ToString;
+    use core::f64::consts::FRAC_PI_2;
+    use udled::bytes::Endian;
+
+    use super::Affine;
+    use crate::Geob;
+
+    #[test]
+    fn affine() {
+        let transform = Affine::rotate_around(FRAC_PI_2, 1.0, 1.0).then(&Affine::scale(2.0, 1.0));
+        let (x, y) = transform.apply(2.0, 1.0);
+        assert!((x - 2.0).abs() < 1e-12 && (y - 2.0).abs() < 1e-12);
+
+        let mut geo = Geob::from_text("SRID=3857;LINESTRING(1.0 2.0, 3.0 4.0)").unwrap();
+        let original = geo.clone();
+        geo.affine(&Affine::translate(10.0, -2.0));
+        assert_eq!(geo.to_string(), "SRID=3857;LINESTRING(11 0, 13 2)");
+        assert_eq!(original.to_string(), "SRID=3857;LINESTRING(1 2, 3 4)");
+
+        geo.flip_coordinates();
+        assert_eq!(geo.to_string(), "SRID=3857;LINESTRING(0 11, 2 13)");
+
+        let snapped = geo.snap_to_grid((0.0, 1.0), (5.0, 0.0));
+        assert_eq!(snapped.to_string(), "SRID=3857;LINESTRING(0 11, 0 13)");
+    }
+
+    #[test]
+    fn snap_to_grid() {
+        let snap = |text: &str, size: f64| {
+            Geob::from_text(text)
+                .unwrap()
+                .snap_to_grid((0.0, 0.0), (size, size))
+                .to_string()
+        };
+
+        assert_eq!(
+            snap("SRID=0;LINESTRING(0 0, 0.1 0.2, 1.2 0.9, 1 1, 2 2)", 1.0),
+            "SRID=0;LINESTRING(0 0, 1 1, 2 2)"
+        );
+        assert_eq!(
+            snap("SRID=0;LINESTRING(0 0, 0.4 0.4)", 1.0),
+            "SRID=0;LINESTRING()"
+        );
+
+        // The hole collapses, the exterior ring is kept
+        assert_eq!(
+            snap(
+                "SRID=0;POLYGON((0 0, 10 0, 10 10, 0 10, 0 0), (2 2, 2.2 2, 2.2 2.2, 2 2))",
+                1.0
+            ),
+            "SRID=0;POLYGON((0 0, 10 0, 10 10, 0 10, 0 0))"
+        );
+        assert_eq!(
+            snap("SRID=0;POLYGON((0 0, 1 0, 1 1, 0 0))", 10.0),
+            "SRID=0;POLYGON()"
+        );
+
+        assert_eq!(
+            snap(
+                "SRID=0;MULTIPOLYGON(((0 0, 1 0, 1 1, 0 0)), ((0 0, 20 0, 20 20, 0 0)))",
+                10.0
+            ),
+            "SRID=0;MULTIPOLYGON(((0 0, 20 0, 20 20, 0 0)))"
+        );
+        assert_eq!(
+            snap(
+                "SRID=0;GEOMETRYCOLLECTION(POINT(1.4 1.6), LINESTRING(0 0, 0.1 0), \
+                 MULTILINESTRING((0 0, 0.2 0), (0 0, 3 3)))",
+                1.0
+            ),
+            "SRID=0;GEOMETRYCOLLECTION(POINT(1 2), MULTILINESTRING((0 0, 3 3)))"
+        );
+
+        let geo = Geob::from_text("SRID=0;POINT(1.4 1.6)")
+            .unwrap()
+            .to_endian(Endian::Big)
+            .with_envelope();
+        let snapped = geo.snap_to_grid((0.0, 0.0), (1.0, 1.0));
+        assert_eq!(snapped.endian(), Endian::Big);
+        assert!(snapped.has_envelope());
+        assert_eq!(snapped.envelope().unwrap().min_x, 1.0);
+    }
+}
@@ -0,0 +1,217 @@
+use core::f64::consts::PI;
+
+use libm::{asin, atan2, log, sin, sqrt, tan};
+
+use crate::{
+    algorithm::geodesic::{A, F, MEAN_RADIUS},
+    types::{CoordSeqRef, GeometryRef, PolygonRef},
+};
+
+// Eccentricity of the WGS84 ellipsoid
+const E2: f64 = F * (2.0 - F);
+
+impl<'a> GeometryRef<'a> {
+    /// Planar area. Counter clockwise exteriors are positive.
+    pub fn signed_area(&self) -> f64 {
+        self.fold_area(&|polygon| polygon_area(polygon, ring_signed_area))
+    }
+
+    /// Planar area
+    pub fn area(&self) -> f64 {
+        self.fold_area(&|polygon| polygon_area(polygon, ring_signed_area).abs())
+    }
+
+    /// Area in square meters of longitude/latitude coordinates on the WGS84
+    /// ellipsoid. Counter clockwise exteriors are positive.
+    ///
+    /// Vertices are mapped onto the authalic sphere, which has the same surface
+    /// area as the ellipsoid, and edges are treated as great circle arcs. This
+    /// approximates the area bounded by geodesics, off by about 0.2 ppm for a
+    /// 1° square, 4 ppm at 5° and 50 ppm at 20°. Use geo's `GeodesicArea` for
+    /// Karney's exact ellipsoidal area.
+    pub fn geodesic_area_signed(&self) -> f64 {
+        let radius = authalic_radius();
+        self.fold_area(&|polygon| {
+            polygon_area(polygon, |ring| {
+                ring_spherical_excess(ring, authalic_latitude)
+            })
+        }) * radius
+            * radius
+    }
+
+    /// Area in square meters of longitude/latitude coordinates on the WGS84
+    /// ellipsoid, approximated on the authalic sphere, see
+    /// [`GeometryRef::geodesic_area_signed`]
+    pub fn geodesic_area(&self) -> f64 {
+        let radius = authalic_radius();
+        self.fold_area(&|polygon| {
+            polygon_area(polygon, |ring| {
+                ring_spherical_excess(ring, authalic_latitude)
+            })
+            .abs()
+        }) * radius
+            * radius
+    }
+
+    /// Area in square meters of longitude/latitude coordinates on a sphere with
+    /// the mean radius of WGS84. Counter clockwise exteriors are positive.
+    pub fn spherical_area_signed(&self) -> f64 {
+        self.fold_area(&|polygon| {
+            polygon_area(polygon, |ring| ring_spherical_excess(ring, |lat| lat))
+        }) * MEAN_RADIUS
+            * MEAN_RADIUS
+    }
+
+    fn fold_area(&self, func: &dyn Fn(PolygonRef<'a>) -> f64) -> f64 {
+        match self {
+            GeometryRef::Polygon(polygon) => func(*polygon),
+            GeometryRef::MultiPolygon(polygons) => polygons.iter().map(func).sum(),
+            GeometryRef::Collection(collection) => {
+                collection.iter().map(|geo| geo.fold_area(func)).sum()
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+/// Area of the exterior minus the holes, with the sign of the exterior
+fn polygon_area<F>(polygon: PolygonRef<'_>, ring_area: F) -> f64
+where
+    F: Fn(CoordSeqRef<'_>) -> f64,
+{
+    let mut rings = polygon.iter();
+
+    let Some(exterior) = rings.next() else {
+        return 0.0;
+    };
+
+    let exterior = ring_area(exterior);
+    let holes = rings.map(|ring| ring_area(ring).abs()).sum::<f64>();
+
+    exterior.signum() * (exterior.abs() - holes)
+}
+
+/// Shoelace formula, relative to the first coordinate to limit precision loss
+pub(crate) fn ring_signed_area(ring: CoordSeqRef<'_>) -> f64 {
+    let mut iter = ring.iter();
+
+    let Some(first) = iter.next() else {
+        return 0.0;
+    };
+
+    let (x0, y0) = (first.x(), first.y());
+    let (mut px, mut py) = (0.0, 0.0);
+    let mut sum = 0.0;
+
+    for coord in iter {
+        let (x, y) = (coord.x() - x0, coord.y() - y0);
+        sum += px * y - x * py;
+        (px, py) = (x, y);
+    }
+
+    sum / 2.0
+}
+
+/// Signed spherical excess in steradians of a ring of longitude/latitude
+/// coordinates, with `lat` mapping geodetic latitude (radians) onto the sphere
+fn ring_spherical_excess<L>(ring: CoordSeqRef<'_>, lat: L) -> f64
+where
+    L: Fn(f64) -> f64,
+{
+    let mut iter = ring.iter();
+
+    let Some(first) = iter.next() else {
+        return 0.0;
+    };
+
+    let mut prev_lon = first.x().to_radians();
+    let mut prev_tan = tan(lat(first.y().to_radians()) / 2.0);
+    let mut sum = 0.0;
+
+    for coord in iter {
+        let lon = coord.x().to_radians();
+        let tan_lat = tan(lat(coord.y().to_radians()) / 2.0);
+
+        let mut d_lon = lon - prev_lon;
+        if d_lon > PI {
+            d_lon -= 2.0 * PI;
+        } else if d_lon < -PI {
+            d_lon += 2.0 * PI;
+        }
+
+        sum += 2.0
+            * atan2(
+                tan(d_lon / 2.0) * (prev_tan + tan_lat),
+                1.0 + prev_tan * tan_lat,
+            );
+
+        prev_lon = lon;
+        prev_tan = tan_lat;
+    }
+
+    -sum
+}
+
+fn q(lat: f64) -> f64 {
+    let e = sqrt(E2);
+    let sin_lat = sin(lat);
+
+    (1.0 - E2)
+        * (sin_lat / (1.0 - E2 * sin_lat * sin_lat)
+            - 1.0 / (2.0 * e) * log((1.0 - e * sin_lat) / (1.0 + e * sin_lat)))
+}
+
+fn authalic_radius() -> f64 {
+    A * sqrt(q(PI / 2.0) / 2.0)
+}
+
+fn authalic_latitude(lat: f64) -> f64 {
+    asin((q(lat) / q(PI / 2.0)).clamp(-1.0, 1.0))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Geob;
+
+    #[test]
+    fn area() {
+        let geo = Geob::from_text(
+            "SRID=0;POLYGON((0.0 0.0, 10.0 0.0, 10.0 10.0, 0.0 10.0, 0.0 0.0), (2.0 2.0, 2.0 4.0, 4.0 4.0, 4.0 2.0, 2.0 2.0))",
+        )
+        .unwrap();
+
+        assert_eq!(geo.geometry().signed_area(), 96.0);
+        assert_eq!(geo.geometry().area(), 96.0);
+
+        let cw =
+            Geob::from_text("SRID=0;POLYGON((0.0 0.0, 0.0 10.0, 10.0 10.0, 10.0 0.0, 0.0 0.0))")
+                .unwrap();
+        assert_eq!(cw.geometry().signed_area(), -100.0);
+        assert_eq!(cw.geometry().area(), 100.0);
+    }
+
+    #[test]
+    fn geodesic_area() {
+        // Geodesic polygon spanning one degree at the equator, 12 308 778 361 m²
+        let geo =
+            Geob::from_text("SRID=4326;POLYGON((0.0 0.0, 1.0 0.0, 1.0 1.0, 0.0 1.0, 0.0 0.0))")
+                .unwrap();
+
+        let area = geo.geometry().geodesic_area_signed();
+        assert!((area - 12_308_778_361.0).abs() / area < 1e-6, "{area}");
+
+        let spherical = geo.geometry().spherical_area_signed();
+        assert!((spherical - area).abs() / area < 0.01, "{spherical}");
+
+        // Clockwise, bulging slightly north of the 60°N - 61°N cell of 6 123 140 879 m²
+        let geo = Geob::from_text(
+            "SRID=4326;POLYGON((0.0 60.0, 0.0 61.0, 1.0 61.0, 1.0 60.0, 0.0 60.0))",
+        )
+        .unwrap();
+
+        let area = geo.geometry().geodesic_area_signed();
+        assert!(area < 0.0);
+        assert_eq!(geo.geometry().geodesic_area(), -area);
+        assert!((-area - 6_123_140_879.0).abs() / -area < 1e-3, "{area}");
+    }
+}
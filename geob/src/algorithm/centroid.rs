@@ -0,0 +1,172 @@
+use libm::hypot;
+
+use crate::types::{CoordSeqRef, GeometryRef, PolygonRef};
+
+#[derive(Default)]
+struct Centroid {
+    // Area weighted
+    area: f64,
+    area_x: f64,
+    area_y: f64,
+    // Length weighted
+    length: f64,
+    length_x: f64,
+    length_y: f64,
+    // Point count weighted
+    points: f64,
+    points_x: f64,
+    points_y: f64,
+}
+
+impl Centroid {
+    fn add(&mut self, geo: &GeometryRef<'_>) {
+        match geo {
+            GeometryRef::Point(point) => self.add_point(point.x(), point.y()),
+            GeometryRef::MultiPoint(points) => {
+                for coord in points.iter() {
+                    self.add_point(coord.x(), coord.y());
+                }
+            }
+            GeometryRef::LineString(line) => self.add_line(line.0),
+            GeometryRef::MultiLineString(lines) => {
+                for line in lines.iter() {
+                    self.add_line(line);
+                }
+            }
+            GeometryRef::Polygon(polygon) => self.add_polygon(*polygon),
+            GeometryRef::MultiPolygon(polygons) => {
+                for polygon in polygons.iter() {
+                    self.add_polygon(polygon);
+                }
+            }
+            GeometryRef::Collection(collection) => {
+                for geo in collection.iter() {
+                    self.add(&geo);
+                }
+            }
+        }
+    }
+
+    fn add_point(&mut self, x: f64, y: f64) {
+        self.points += 1.0;
+        self.points_x += x;
+        self.points_y += y;
+    }
+
+    /// Segment midpoints weighted by length. Vertices are tracked as well for
+    /// lines of zero length.
+    fn add_line(&mut self, line: CoordSeqRef<'_>) {
+        let mut prev: Option<(f64, f64)> = None;
+
+        for coord in line.iter() {
+            let (x, y) = (coord.x(), coord.y());
+
+            if let Some((px, py)) = prev {
+                let length = hypot(x - px, y - py);
+                self.length += length;
+                self.length_x += length * (x + px) / 2.0;
+                self.length_y += length * (y + py) / 2.0;
+            }
+
+            self.add_point(x, y);
+            prev = Some((x, y));
+        }
+    }
+
+    /// Ring centroids weighted by area, holes subtracted. Rings are tracked as
+    /// lines for polygons of zero area.
+    fn add_polygon(&mut self, polygon: PolygonRef<'_>) {
+        for (idx, ring) in polygon.iter().enumerate() {
+            let Some((area, x, y)) = ring_centroid(ring) else {
+                continue;
+            };
+
+            let weight = if idx == 0 { area.abs() } else { -area.abs() };
+
+            self.area += weight;
+            self.area_x += weight * x;
+            self.area_y += weight * y;
+
+            self.add_line(ring);
+        }
+    }
+
+    fn finish(&self) -> Option<(f64, f64)> {
+        if self.area != 0.0 {
+            Some((self.area_x / self.area, self.area_y / self.area))
+        } else if self.length != 0.0 {
+            Some((self.length_x / self.length, self.length_y / self.length))
+        } else if self.points != 0.0 {
+            Some((self.points_x / self.points, self.points_y / self.points))
+        } else {
+            None
+        }
+    }
+}
+
+/// Signed area and centroid of a ring, relative to the first coordinate to
+/// limit precision loss
+fn ring_centroid(ring: CoordSeqRef<'_>) -> Option<(f64, f64, f64)> {
+    let mut iter = ring.iter();
+    let first = iter.next()?;
+
+    let (x0, y0) = (first.x(), first.y());
+    let (mut px, mut py) = (0.0, 0.0);
+    let (mut area, mut cx, mut cy) = (0.0, 0.0, 0.0);
+
+    for coord in iter {
+        let (x, y) = (coord.x() - x0, coord.y() - y0);
+        let cross = px * y - x * py;
+
+        area += cross;
+        cx += (px + x) * cross;
+        cy += (py + y) * cross;
+
+        (px, py) = (x, y);
+    }
+
+    if area == 0.0 {
+        return Some((0.0, x0, y0));
+    }
+
+    Some((area / 2.0, x0 + cx / (3.0 * area), y0 + cy / (3.0 * area)))
+}
+
+impl<'a> GeometryRef<'a> {
+    /// Planar centroid of the highest dimension components, `None` if the
+    /// geometry has no coordinates
+    pub fn centroid(&self) -> Option<(f64, f64)> {
+        let mut centroid = Centroid::default();
+        centroid.add(self);
+        centroid.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Geob;
+
+    #[test]
+    fn centroid() {
+        let centroid = |text: &str| Geob::from_text(text).unwrap().geometry().centroid();
+
+        assert_eq!(
+            centroid("SRID=0;POLYGON((0.0 0.0, 4.0 0.0, 4.0 2.0, 0.0 2.0, 0.0 0.0))"),
+            Some((2.0, 1.0))
+        );
+        assert_eq!(
+            centroid("SRID=0;LINESTRING(0.0 0.0, 2.0 0.0, 2.0 2.0)"),
+            Some((1.5, 0.5))
+        );
+        assert_eq!(
+            centroid(
+                "SRID=0;GEOMETRYCOLLECTION(POINT(100.0 100.0), POLYGON((0.0 0.0, 2.0 0.0, 2.0 2.0, 0.0 2.0, 0.0 0.0)))"
+            ),
+            Some((1.0, 1.0))
+        );
+        assert_eq!(
+            centroid("SRID=0;POLYGON((1.0 1.0, 3.0 3.0, 1.0 1.0))"),
+            Some((2.0, 2.0))
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use core::f64::consts::TAU;
+
+use libm::{atan, atan2, cos, sin, sqrt, tan};
+
+// WGS84 ellipsoid
+pub const A: f64 = 6_378_137.0;
+pub const F: f64 = 1.0 / 298.257_223_563;
+pub const B: f64 = A * (1.0 - F);
+
+/// Mean radius of the WGS84 ellipsoid, (2a + b) / 3
+pub const MEAN_RADIUS: f64 = 6_371_008.8;
+
+const MAX_ITERATIONS: usize = 200;
+
+/// Great circle distance in meters on a sphere with [`MEAN_RADIUS`]
+pub fn haversine(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = (lon2 - lon1).to_radians();
+
+    let a = sin(d_lat / 2.0) * sin(d_lat / 2.0)
+        + cos(lat1) * cos(lat2) * sin(d_lon / 2.0) * sin(d_lon / 2.0);
+
+    2.0 * MEAN_RADIUS * atan2(sqrt(a), sqrt(1.0 - a))
+}
+
+/// Distance in meters on the WGS84 ellipsoid using Vincenty's inverse formula.
+///
+/// Returns `None` if the iteration does not converge, which happens for nearly
+/// antipodal points.
+pub fn vincenty(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> Option<f64> {
+    inverse(lon1, lat1, lon2, lat2).map(|(distance, _)| distance)
+}
+
+/// Distance and initial azimuth in radians, clockwise from north
+fn inverse(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> Option<(f64, f64)> {
+    let l = (lon2 - lon1).to_radians();
+    let u1 = atan((1.0 - F) * tan(lat1.to_radians()));
+    let u2 = atan((1.0 - F) * tan(lat2.to_radians()));
+
+    let (sin_u1, cos_u1) = (sin(u1), cos(u1));
+    let (sin_u2, cos_u2) = (sin(u2), cos(u2));
+
+    let mut lambda = l;
+
+    for _ in 0..MAX_ITERATIONS {
+        let (sin_lambda, cos_lambda) = (sin(lambda), cos(lambda));
+
+        let sin_sigma = sqrt(
+            (cos_u2 * sin_lambda) * (cos_u2 * sin_lambda)
+                + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda)
+                    * (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda),
+        );
+
+        if sin_sigma == 0.0 {
+            return Some((0.0, 0.0));
+        }
+
+        let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        let sigma = atan2(sin_sigma, cos_sigma);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+        let cos_2sigma_m = if cos2_alpha != 0.0 {
+            cos_sigma - 2.0 * sin_u1 * sin_u2 / cos2_alpha
+        } else {
+            // Equatorial line
+            0.0
+        };
+
+        let c = F / 16.0 * cos2_alpha * (4.0 + F * (4.0 - 3.0 * cos2_alpha));
+        let prev = lambda;
+        lambda = l
+            + (1.0 - c)
+                * F
+                * sin_alpha
+                * (sigma
+                    + c * sin_sigma
+                        * (cos_2sigma_m
+                            + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)));
+
+        if (lambda - prev).abs() < 1e-12 {
+            let u_sq = cos2_alpha * (A * A - B * B) / (B * B);
+            let a =
+                1.0 + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+            let b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+            let delta_sigma = b
+                * sin_sigma
+                * (cos_2sigma_m
+                    + b / 4.0
+                        * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)
+                            - b / 6.0
+                                * cos_2sigma_m
+                                * (-3.0 + 4.0 * sin_sigma * sin_sigma)
+                                * (-3.0 + 4.0 * cos_2sigma_m * cos_2sigma_m)));
+
+            let azimuth = atan2(
+                cos_u2 * sin_lambda,
+                cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda,
+            );
+
+            return Some((B * a * (sigma - delta_sigma), azimuth));
+        }
+    }
+
+    None
+}
+
+/// Distance in meters on the WGS84 ellipsoid, falling back to [`haversine`]
+/// where Vincenty's formula does not converge
+pub fn distance(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    vincenty(lon1, lat1, lon2, lat2).unwrap_or_else(|| haversine(lon1, lat1, lon2, lat2))
+}
+
+/// Initial azimuth in radians from the first point to the second, clockwise
+/// from north in `0..2π`. Falls back to the great circle bearing where
+/// Vincenty's formula does not converge. `None` if the points coincide.
+pub fn azimuth(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> Option<f64> {
+    let azimuth = match inverse(lon1, lat1, lon2, lat2) {
+        Some((0.0, _)) => return None,
+        Some((_, azimuth)) => azimuth,
+        None => {
+            let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+            let d_lon = (lon2 - lon1).to_radians();
+            atan2(
+                sin(d_lon) * cos(lat2),
+                cos(lat1) * sin(lat2) - sin(lat1) * cos(lat2) * cos(d_lon),
+            )
+        }
+    };
+
+    Some(azimuth.rem_euclid(TAU))
+}
+
+/// Point at `distance` meters from `(lon, lat)` along `azimuth` in radians,
+/// clockwise from north, using Vincenty's direct formula
+pub fn destination(lon: f64, lat: f64, distance: f64, azimuth: f64) -> (f64, f64) {
+    let (sin_alpha1, cos_alpha1) = (sin(azimuth), cos(azimuth));
+
+    let tan_u1 = (1.0 - F) * tan(lat.to_radians());
+    let cos_u1 = 1.0 / sqrt(1.0 + tan_u1 * tan_u1);
+    let sin_u1 = tan_u1 * cos_u1;
+
+    let sigma1 = atan2(tan_u1, cos_alpha1);
+    let sin_alpha = cos_u1 * sin_alpha1;
+    let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+
+    let u_sq = cos2_alpha * (A * A - B * B) / (B * B);
+    let a = 1.0 + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+    let b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+
+    let mut sigma = distance / (B * a);
+    let (mut sin_sigma, mut cos_sigma, mut cos_2sigma_m);
+
+    let mut iterations = 0;
+    loop {
+        cos_2sigma_m = cos(2.0 * sigma1 + sigma);
+        (sin_sigma, cos_sigma) = (sin(sigma), cos(sigma));
+
+        let delta_sigma = b
+            * sin_sigma
+            * (cos_2sigma_m
+                + b / 4.0
+                    * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)
+                        - b / 6.0
+                            * cos_2sigma_m
+                            * (-3.0 + 4.0 * sin_sigma * sin_sigma)
+                            * (-3.0 + 4.0 * cos_2sigma_m * cos_2sigma_m)));
+
+        let prev = sigma;
+        sigma = distance / (B * a) + delta_sigma;
+
+        iterations += 1;
+        if (sigma - prev).abs() < 1e-12 || iterations == MAX_ITERATIONS {
+            break;
+        }
+    }
+
+    let tmp = sin_u1 * sin_sigma - cos_u1 * cos_sigma * cos_alpha1;
+    let lat2 = atan2(
+        sin_u1 * cos_sigma + cos_u1 * sin_sigma * cos_alpha1,
+        (1.0 - F) * sqrt(sin_alpha * sin_alpha + tmp * tmp),
+    );
+
+    let lambda = atan2(
+        sin_sigma * sin_alpha1,
+        cos_u1 * cos_sigma - sin_u1 * sin_sigma * cos_alpha1,
+    );
+    let c = F / 16.0 * cos2_alpha * (4.0 + F * (4.0 - 3.0 * cos2_alpha));
+    let l = lambda
+        - (1.0 - c)
+            * F
+            * sin_alpha
+            * (sigma
+                + c * sin_sigma
+                    * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)));
+
+    let lon2 = (lon + l.to_degrees() + 180.0).rem_euclid(360.0) - 180.0;
+
+    (lon2, lat2.to_degrees())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{azimuth, destination, distance, haversine, vincenty};
+
+    #[test]
+    fn distances() {
+        // Flinders Peak to Buninyong, Vincenty (1975)
+        let d = vincenty(
+            144.424_867_89,
+            -37.951_033_42,
+            143.926_495_53,
+            -37.652_821_14,
+        )
+        .unwrap();
+        assert!((d - 54_972.271).abs() < 0.001, "{d}");
+
+        assert_eq!(distance(12.0, 55.0, 12.0, 55.0), 0.0);
+
+        // One degree along the equator
+        let d = distance(0.0, 0.0, 1.0, 0.0);
+        assert!((d - 111_319.491).abs() < 0.001, "{d}");
+
+        let h = haversine(0.0, 0.0, 1.0, 0.0);
+        assert!((h - d).abs() / d < 0.005);
+
+        // Nearly antipodal points do not converge
+        assert!(vincenty(0.0, 0.0, 179.7, 0.5).is_none());
+        assert!(distance(0.0, 0.0, 179.7, 0.5) > 19_000_000.0);
+    }
+
+    #[test]
+    fn directions() {
+        // Flinders Peak to Buninyong, Vincenty (1975)
+        let (lon1, lat1) = (144.424_867_89, -37.951_033_42);
+        let (lon2, lat2) = (143.926_495_53, -37.652_821_14);
+
+        let forward = azimuth(lon1, lat1, lon2, lat2).unwrap();
+        assert!(
+            (forward.to_degrees() - 306.868_159).abs() < 1e-5,
+            "{forward}"
+        );
+
+        let (lon, lat) = destination(lon1, lat1, 54_972.271, forward);
+        assert!((lon - lon2).abs() < 1e-8 && (lat - lat2).abs() < 1e-8);
+
+        assert_eq!(azimuth(0.0, 0.0, 0.0, 1.0), Some(0.0));
+        assert_eq!(azimuth(12.0, 55.0, 12.0, 55.0), None);
+
+        let (lon, lat) = destination(179.5, 0.0, 111_319.491, core::f64::consts::FRAC_PI_2);
+        assert!((lon + 179.5).abs() < 1e-6 && lat.abs() < 1e-9);
+    }
+}
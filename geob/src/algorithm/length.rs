@@ -0,0 +1,80 @@
+use libm::hypot;
+
+use crate::{
+    algorithm::geodesic,
+    types::{CoordSeqRef, GeometryRef},
+};
+
+impl<'a> GeometryRef<'a> {
+    /// Planar length of the linear components
+    pub fn length(&self) -> f64 {
+        self.fold_lines(&|line| line_length(line, hypot_distance))
+    }
+
+    /// Length in meters of the linear components of longitude/latitude
+    /// coordinates on the WGS84 ellipsoid
+    pub fn geodesic_length(&self) -> f64 {
+        self.fold_lines(&|line| line_length(line, geodesic::distance))
+    }
+
+    /// Planar length of the polygon rings
+    pub fn perimeter(&self) -> f64 {
+        self.fold_rings(&|ring| line_length(ring, hypot_distance))
+    }
+
+    /// Length in meters of the polygon rings of longitude/latitude coordinates on
+    /// the WGS84 ellipsoid
+    pub fn geodesic_perimeter(&self) -> f64 {
+        self.fold_rings(&|ring| line_length(ring, geodesic::distance))
+    }
+
+    fn fold_lines(&self, func: &dyn Fn(CoordSeqRef<'a>) -> f64) -> f64 {
+        match self {
+            GeometryRef::LineString(line) => func(line.0),
+            GeometryRef::MultiLineString(lines) => lines.iter().map(func).sum(),
+            GeometryRef::Collection(collection) => {
+                collection.iter().map(|geo| geo.fold_lines(func)).sum()
+            }
+            _ => 0.0,
+        }
+    }
+
+    fn fold_rings(&self, func: &dyn Fn(CoordSeqRef<'a>) -> f64) -> f64 {
+        match self {
+            GeometryRef::Polygon(polygon) => polygon.iter().map(func).sum(),
+            GeometryRef::MultiPolygon(polygons) => polygons
+                .iter()
+                .map(|polygon| polygon.iter().map(func).sum::<f64>())
+                .sum(),
+            GeometryRef::Collection(collection) => {
+                collection.iter().map(|geo| geo.fold_rings(func)).sum()
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+fn hypot_distance(x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
+    hypot(x2 - x1, y2 - y1)
+}
+
+pub(crate) fn line_length<D>(line: CoordSeqRef<'_>, distance: D) -> f64
+where
+    D: Fn(f64, f64, f64, f64) -> f64,
+{
+    let mut iter = line.iter();
+
+    let Some(first) = iter.next() else {
+        return 0.0;
+    };
+
+    let (mut px, mut py) = (first.x(), first.y());
+
+    iter.map(|coord| {
+        let (x, y) = (coord.x(), coord.y());
+        let d = distance(px, py, x, y);
+        (px, py) = (x, y);
+        d
+    })
+    .sum()
+}
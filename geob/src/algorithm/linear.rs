@@ -0,0 +1,493 @@
+use alloc::{vec, vec::Vec};
+use core::f64::consts::TAU;
+use libm::{acos, asin, atan2, cos, hypot, sin};
+
+use crate::{
+    SRID,
+    algorithm::geodesic,
+    types::{CoordRef, CoordSeqRef, GeometryRef, LineStringRef, PolygonRef},
+};
+
+type Coord = (f64, f64);
+
+/// How distances are measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// Euclidean distance in the units of the coordinates
+    Planar,
+    /// Meters on the WGS84 ellipsoid, for longitude/latitude coordinates
+    Geodesic,
+}
+
+impl Metric {
+    pub fn for_srid(srid: SRID) -> Metric {
+        if srid.is_geodetic() {
+            Metric::Geodesic
+        } else {
+            Metric::Planar
+        }
+    }
+
+    pub fn distance(&self, (x1, y1): Coord, (x2, y2): Coord) -> f64 {
+        match self {
+            Metric::Planar => hypot(x2 - x1, y2 - y1),
+            Metric::Geodesic => geodesic::distance(x1, y1, x2, y2),
+        }
+    }
+
+    /// Azimuth from `a` to `b` in radians, clockwise from north or the y axis,
+    /// in `0..2π`. `None` if the points coincide.
+    pub fn azimuth(&self, a: Coord, b: Coord) -> Option<f64> {
+        match self {
+            Metric::Planar if a == b => None,
+            Metric::Planar => Some(atan2(b.0 - a.0, b.1 - a.1).rem_euclid(TAU)),
+            Metric::Geodesic => geodesic::azimuth(a.0, a.1, b.0, b.1),
+        }
+    }
+
+    /// Point at `distance` from `origin` along `azimuth` in radians, clockwise
+    /// from north or the y axis
+    pub fn destination(&self, origin: Coord, distance: f64, azimuth: f64) -> Coord {
+        match self {
+            Metric::Planar => (
+                origin.0 + distance * sin(azimuth),
+                origin.1 + distance * cos(azimuth),
+            ),
+            Metric::Geodesic => geodesic::destination(origin.0, origin.1, distance, azimuth),
+        }
+    }
+
+    /// Point `fraction` of the way from `a` to `b`, along the great circle for
+    /// geodesic coordinates
+    pub(super) fn interpolate(&self, a: Coord, b: Coord, fraction: f64) -> Coord {
+        let planar = (a.0 + (b.0 - a.0) * fraction, a.1 + (b.1 - a.1) * fraction);
+
+        if *self == Metric::Planar {
+            return planar;
+        }
+
+        let (a, b) = (unit(a), unit(b));
+        let dot = (a[0] * b[0] + a[1] * b[1] + a[2] * b[2]).clamp(-1.0, 1.0);
+        let angle = acos(dot);
+        if angle < 1e-12 {
+            return planar;
+        }
+
+        let s = sin(angle);
+        let (wa, wb) = (sin((1.0 - fraction) * angle) / s, sin(fraction * angle) / s);
+        let v = [
+            wa * a[0] + wb * b[0],
+            wa * a[1] + wb * b[1],
+            wa * a[2] + wb * b[2],
+        ];
+
+        (
+            atan2(v[1], v[0]).to_degrees(),
+            asin(v[2].clamp(-1.0, 1.0)).to_degrees(),
+        )
+    }
+
+    /// Fraction along `a`-`b` of the point closest to `p`. Geodesic
+    /// coordinates are projected onto a plane tangent at `p`.
+    fn project(&self, a: Coord, b: Coord, p: Coord) -> f64 {
+        let (a, b) = match self {
+            Metric::Planar => ((a.0 - p.0, a.1 - p.1), (b.0 - p.0, b.1 - p.1)),
+            Metric::Geodesic => (local(a, p), local(b, p)),
+        };
+
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let len = dx * dx + dy * dy;
+        if len == 0.0 {
+            return 0.0;
+        }
+
+        (-(a.0 * dx + a.1 * dy) / len).clamp(0.0, 1.0)
+    }
+}
+
+fn unit((lon, lat): Coord) -> [f64; 3] {
+    let (lon, lat) = (lon.to_radians(), lat.to_radians());
+    [cos(lat) * cos(lon), cos(lat) * sin(lon), sin(lat)]
+}
+
+/// Equirectangular projection of `c` around `origin`, in degrees of latitude
+fn local(c: Coord, origin: Coord) -> Coord {
+    let mut dx = c.0 - origin.0;
+    if dx > 180.0 {
+        dx -= 360.0;
+    } else if dx < -180.0 {
+        dx += 360.0;
+    }
+
+    (dx * cos(origin.1.to_radians()), c.1 - origin.1)
+}
+
+impl<'a> LineStringRef<'a> {
+    /// Point at `fraction` of the length of the line, `None` if the line is
+    /// empty or the fraction is outside `0..=1`
+    pub fn interpolate_point(&self, fraction: f64, metric: Metric) -> Option<Coord> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return None;
+        }
+
+        let total = length(self.0, metric);
+        point_at(self.0, fraction * total, metric)
+    }
+
+    /// Fraction of the length of the line at the point closest to `(x, y)`,
+    /// `None` if the line is empty
+    pub fn locate_point(&self, x: f64, y: f64, metric: Metric) -> Option<f64> {
+        if self.0.is_empty() {
+            return None;
+        }
+
+        let (mut total, mut best) = (0.0, (f64::INFINITY, 0.0));
+        for (a, b) in segments(self.0) {
+            let t = metric.project(a, b, (x, y));
+            let d = metric.distance(metric.interpolate(a, b, t), (x, y));
+            let len = metric.distance(a, b);
+
+            if d < best.0 {
+                best = (d, total + t * len);
+            }
+            total += len;
+        }
+
+        if total == 0.0 {
+            return Some(0.0);
+        }
+
+        Some(best.1 / total)
+    }
+
+    /// Coordinates between the fractions `start` and `end` of the length of
+    /// the line. A single coordinate when they are equal, `None` if the line is
+    /// empty or the fractions are out of order or outside `0..=1`.
+    pub fn substring(&self, start: f64, end: f64, metric: Metric) -> Option<Vec<Coord>> {
+        if !(0.0..=1.0).contains(&start) || !(start..=1.0).contains(&end) {
+            return None;
+        }
+
+        let total = length(self.0, metric);
+        let (from, to) = (start * total, end * total);
+        let first = point_at(self.0, from, metric)?;
+
+        if start == end {
+            return Some(vec![first]);
+        }
+
+        let mut output = vec![first];
+        let mut along = 0.0;
+        let mut prev = None;
+        for coord in self.0.iter().map(|c| (c.x(), c.y())) {
+            if let Some(prev) = prev {
+                along += metric.distance(prev, coord);
+            }
+            prev = Some(coord);
+
+            if along > from && along < to && output.last() != Some(&coord) {
+                output.push(coord);
+            }
+        }
+
+        let last = point_at(self.0, to, metric)?;
+        if output.len() == 1 || output.last() != Some(&last) {
+            output.push(last);
+        }
+
+        Some(output)
+    }
+}
+
+impl<'a> GeometryRef<'a> {
+    /// The closest pair of points, the first on `self` and the second on
+    /// `other`. Both are the same point where the geometries intersect.
+    /// Geodesic coordinates are compared on a plane scaled to their mean
+    /// latitude. `None` if either geometry is empty.
+    pub fn closest_points(
+        &self,
+        other: &GeometryRef<'_>,
+        metric: Metric,
+    ) -> Option<(Coord, Coord)> {
+        let scale = match metric {
+            Metric::Planar => 1.0,
+            Metric::Geodesic => {
+                let (a, b) = (self.bounding_rect()?, other.bounding_rect()?);
+                cos(((a.min_y + a.max_y + b.min_y + b.max_y) / 4.0).to_radians()).max(1e-6)
+            }
+        };
+
+        let a = Parts::new(self, scale);
+        let b = Parts::new(other, scale);
+
+        if a.segments.is_empty() || b.segments.is_empty() {
+            return None;
+        }
+
+        let unscale = |(x, y): Coord| (x / scale, y);
+
+        // A vertex inside the other geometry's area
+        for (segments, parts) in [(&b.segments, &a), (&a.segments, &b)] {
+            for (p, _) in segments.iter() {
+                if parts
+                    .polygons
+                    .iter()
+                    .any(|polygon| parts.contains(*polygon, *p))
+                {
+                    let p = unscale(*p);
+                    return Some((p, p));
+                }
+            }
+        }
+
+        let mut best = (f64::INFINITY, ((0.0, 0.0), (0.0, 0.0)));
+        for sa in &a.segments {
+            for sb in &b.segments {
+                let (pa, pb) = closest_segments(*sa, *sb);
+                let d = hypot(pb.0 - pa.0, pb.1 - pa.1);
+                if d < best.0 {
+                    best = (d, (pa, pb));
+                }
+            }
+        }
+
+        let (pa, pb) = best.1;
+        Some((unscale(pa), unscale(pb)))
+    }
+}
+
+/// Consecutive coordinate pairs of a sequence
+fn segments(seq: CoordSeqRef<'_>) -> impl Iterator<Item = (Coord, Coord)> + '_ {
+    let coord = |c: CoordRef<'_>| (c.x(), c.y());
+    seq.iter().map(coord).zip(seq.iter().skip(1).map(coord))
+}
+
+fn length(seq: CoordSeqRef<'_>, metric: Metric) -> f64 {
+    segments(seq).map(|(a, b)| metric.distance(a, b)).sum()
+}
+
+/// Point at `along` from the start of the line, `None` if the line is empty
+fn point_at(seq: CoordSeqRef<'_>, along: f64, metric: Metric) -> Option<Coord> {
+    let first = seq.get(0)?;
+    let mut last = (first.x(), first.y());
+
+    if along <= 0.0 {
+        return Some(last);
+    }
+
+    let mut total = 0.0;
+    for (a, b) in segments(seq) {
+        let segment = metric.distance(a, b);
+
+        if total + segment >= along {
+            if segment == 0.0 {
+                return Some(b);
+            }
+            return Some(metric.interpolate(a, b, (along - total) / segment));
+        }
+
+        total += segment;
+        last = b;
+    }
+
+    Some(last)
+}
+
+/// Segments and polygons of a geometry, with x scaled. Points are segments
+/// of zero length.
+struct Parts<'a> {
+    scale: f64,
+    segments: Vec<(Coord, Coord)>,
+    polygons: Vec<PolygonRef<'a>>,
+}
+
+impl<'a> Parts<'a> {
+    fn new(geo: &GeometryRef<'a>, scale: f64) -> Parts<'a> {
+        let mut parts = Parts {
+            scale,
+            segments: Vec::new(),
+            polygons: Vec::new(),
+        };
+        parts.add(geo);
+        parts
+    }
+
+    fn add(&mut self, geo: &GeometryRef<'a>) {
+        match geo {
+            GeometryRef::Point(point) => self.add_point(point.coord()),
+            GeometryRef::MultiPoint(points) => {
+                for c in points.iter() {
+                    self.add_point(c);
+                }
+            }
+            GeometryRef::LineString(line) => self.add_line(line.0),
+            GeometryRef::MultiLineString(lines) => {
+                for line in lines.iter() {
+                    self.add_line(line);
+                }
+            }
+            GeometryRef::Polygon(polygon) => self.add_polygon(*polygon),
+            GeometryRef::MultiPolygon(polygons) => {
+                for polygon in polygons.iter() {
+                    self.add_polygon(polygon);
+                }
+            }
+            GeometryRef::Collection(collection) => {
+                for geo in collection.iter() {
+                    self.add(&geo);
+                }
+            }
+        }
+    }
+
+    fn add_polygon(&mut self, polygon: PolygonRef<'a>) {
+        for ring in polygon.iter() {
+            self.add_line(ring);
+        }
+        self.polygons.push(polygon);
+    }
+
+    fn add_point(&mut self, c: CoordRef<'_>) {
+        let p = (c.x() * self.scale, c.y());
+        self.segments.push((p, p));
+    }
+
+    fn add_line(&mut self, line: CoordSeqRef<'_>) {
+        if line.len() == 1 {
+            return self.add_point(line.get(0).unwrap());
+        }
+
+        let scale = self.scale;
+        self.segments.extend(
+            segments(line).map(|((x1, y1), (x2, y2))| ((x1 * scale, y1), (x2 * scale, y2))),
+        );
+    }
+
+    /// Even-odd test of a scaled point against all rings of a polygon
+    fn contains(&self, polygon: PolygonRef<'_>, (x, y): Coord) -> bool {
+        let mut inside = false;
+
+        for ring in polygon.iter() {
+            for ((x1, y1), (x2, y2)) in segments(ring) {
+                let (x1, x2) = (x1 * self.scale, x2 * self.scale);
+                if (y1 > y) != (y2 > y) && x < x1 + (y - y1) * (x2 - x1) / (y2 - y1) {
+                    inside = !inside;
+                }
+            }
+        }
+
+        inside
+    }
+}
+
+fn orient(a: Coord, b: Coord, c: Coord) -> f64 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+fn closest_on_segment((a, b): (Coord, Coord), p: Coord) -> Coord {
+    let t = Metric::Planar.project(a, b, p);
+    Metric::Planar.interpolate(a, b, t)
+}
+
+fn closest_segments(a: (Coord, Coord), b: (Coord, Coord)) -> (Coord, Coord) {
+    let (d1, d2) = (orient(b.0, b.1, a.0), orient(b.0, b.1, a.1));
+    let (d3, d4) = (orient(a.0, a.1, b.0), orient(a.0, a.1, b.1));
+
+    // Proper crossing
+    if d1 * d2 < 0.0 && d3 * d4 < 0.0 {
+        let t = d1 / (d1 - d2);
+        let p = Metric::Planar.interpolate(a.0, a.1, t);
+        return (p, p);
+    }
+
+    let candidates = [
+        (a.0, closest_on_segment(b, a.0)),
+        (a.1, closest_on_segment(b, a.1)),
+        (closest_on_segment(a, b.0), b.0),
+        (closest_on_segment(a, b.1), b.1),
+    ];
+
+    candidates
+        .into_iter()
+        .min_by(|(p1, q1), (p2, q2)| {
+            hypot(q1.0 - p1.0, q1.1 - p1.1).total_cmp(&hypot(q2.0 - p2.0, q2.1 - p2.1))
+        })
+        .unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec;
+
+    use super::Metric;
+    use crate::{Geob, types::GeometryRef};
+
+    #[test]
+    fn linear_referencing() {
+        let geo = Geob::from_text("SRID=0;LINESTRING(0.0 0.0, 10.0 0.0, 10.0 10.0)").unwrap();
+        let GeometryRef::LineString(line) = geo.geometry() else {
+            panic!("expected a linestring");
+        };
+
+        assert_eq!(
+            line.interpolate_point(0.25, Metric::Planar),
+            Some((5.0, 0.0))
+        );
+        assert_eq!(
+            line.interpolate_point(0.75, Metric::Planar),
+            Some((10.0, 5.0))
+        );
+        assert_eq!(line.interpolate_point(1.5, Metric::Planar), None);
+
+        let geo = Geob::from_text("SRID=0;LINESTRING(1 1, 1 1)").unwrap();
+        let GeometryRef::LineString(point) = geo.geometry() else {
+            panic!("expected a linestring");
+        };
+        assert_eq!(point.locate_point(5.0, 5.0, Metric::Planar), Some(0.0));
+        assert_eq!(
+            point.interpolate_point(0.5, Metric::Planar),
+            Some((1.0, 1.0))
+        );
+
+        assert_eq!(line.locate_point(12.0, 5.0, Metric::Planar), Some(0.75));
+        assert_eq!(line.locate_point(-1.0, -1.0, Metric::Planar), Some(0.0));
+
+        assert_eq!(
+            line.substring(0.25, 0.75, Metric::Planar),
+            Some(vec![(5.0, 0.0), (10.0, 0.0), (10.0, 5.0)])
+        );
+        assert_eq!(
+            line.substring(0.5, 0.5, Metric::Planar),
+            Some(vec![(10.0, 0.0)])
+        );
+
+        // Along the equator the great circle is the parallel
+        let geo = Geob::from_text("SRID=4326;LINESTRING(0.0 0.0, 2.0 0.0)").unwrap();
+        let GeometryRef::LineString(line) = geo.geometry() else {
+            panic!("expected a linestring");
+        };
+        let (x, y) = line.interpolate_point(0.5, Metric::Geodesic).unwrap();
+        assert!((x - 1.0).abs() < 1e-9 && y.abs() < 1e-9);
+        let fraction = line.locate_point(0.5, 1.0, Metric::Geodesic).unwrap();
+        assert!((fraction - 0.25).abs() < 1e-6);
+
+        let square =
+            Geob::from_text("SRID=0;POLYGON((0.0 0.0, 4.0 0.0, 4.0 4.0, 0.0 4.0, 0.0 0.0))")
+                .unwrap();
+        let line = Geob::from_text("SRID=0;LINESTRING(6.0 1.0, 6.0 3.0)").unwrap();
+        assert_eq!(
+            square
+                .geometry()
+                .closest_points(&line.geometry(), Metric::Planar),
+            Some(((4.0, 1.0), (6.0, 1.0)))
+        );
+
+        let inner = Geob::from_text("SRID=0;LINESTRING(1.0 1.0, 2.0 2.0)").unwrap();
+        assert_eq!(
+            square
+                .geometry()
+                .closest_points(&inner.geometry(), Metric::Planar),
+            Some(((1.0, 1.0), (1.0, 1.0)))
+        );
+    }
+}
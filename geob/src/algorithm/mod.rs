@@ -0,0 +1,77 @@
+//! Measurements computed directly over the encoded coordinates, without
+//! decoding into an intermediate geometry.
+
+mod area;
+mod centroid;
+pub mod geodesic;
+mod length;
+mod linear;
+mod segmentize;
+mod triangulate;
+
+pub use self::{
+    linear::Metric,
+    segmentize::{MAX_SEGMENTIZE_POINTS, SegmentizeError},
+};
+
+use crate::{Envelope, types::GeometryRef};
+
+impl<'a> GeometryRef<'a> {
+    /// Number of coordinates, including the closing coordinate of rings
+    pub fn num_points(&self) -> usize {
+        match self {
+            GeometryRef::Point(_) => 1,
+            GeometryRef::LineString(line) => line.len(),
+            GeometryRef::MultiPoint(points) => points.len(),
+            GeometryRef::MultiLineString(lines) => lines.iter().map(|line| line.len()).sum(),
+            GeometryRef::Polygon(polygon) => polygon.iter().map(|ring| ring.len()).sum(),
+            GeometryRef::MultiPolygon(polygons) => polygons
+                .iter()
+                .flat_map(|polygon| polygon.iter())
+                .map(|ring| ring.len())
+                .sum(),
+            GeometryRef::Collection(collection) => {
+                collection.iter().map(|geo| geo.num_points()).sum()
+            }
+        }
+    }
+
+    /// Call `func` for every coordinate in order
+    pub fn for_each_coord<F: FnMut(f64, f64)>(&self, func: &mut F) {
+        match self {
+            GeometryRef::Point(point) => func(point.x(), point.y()),
+            GeometryRef::LineString(line) => line.iter().for_each(|c| func(c.x(), c.y())),
+            GeometryRef::MultiPoint(points) => points.iter().for_each(|c| func(c.x(), c.y())),
+            GeometryRef::MultiLineString(lines) => lines
+                .iter()
+                .flat_map(|line| line.iter())
+                .for_each(|c| func(c.x(), c.y())),
+            GeometryRef::Polygon(polygon) => polygon
+                .iter()
+                .flat_map(|ring| ring.iter())
+                .for_each(|c| func(c.x(), c.y())),
+            GeometryRef::MultiPolygon(polygons) => polygons
+                .iter()
+                .flat_map(|polygon| polygon.iter())
+                .flat_map(|ring| ring.iter())
+                .for_each(|c| func(c.x(), c.y())),
+            GeometryRef::Collection(collection) => {
+                for geo in collection.iter() {
+                    geo.for_each_coord(func);
+                }
+            }
+        }
+    }
+
+    /// Bounding rectangle, `None` if the geometry has no coordinates
+    pub fn bounding_rect(&self) -> Option<Envelope> {
+        let mut envelope: Option<Envelope> = None;
+
+        self.for_each_coord(&mut |x, y| match &mut envelope {
+            Some(envelope) => envelope.expand(x, y),
+            None => envelope = Some(Envelope::from_coord(x, y)),
+        });
+
+        envelope
+    }
+}
@@ -0,0 +1,180 @@
+use core::fmt;
+
+use libm::ceil;
+
+use crate::{
+    Geob,
+    algorithm::Metric,
+    builder::GeobBuilder,
+    types::{CoordSeqRef, GeometryRef, PolygonRef},
+};
+
+/// Most coordinates [`Geob::segmentize`] will produce
+pub const MAX_SEGMENTIZE_POINTS: usize = 1 << 24;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SegmentizeError {
+    /// The segmentized geometry would have more than
+    /// [`MAX_SEGMENTIZE_POINTS`] coordinates
+    TooManyPoints,
+}
+
+impl fmt::Display for SegmentizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SegmentizeError::TooManyPoints => write!(
+                f,
+                "segmentizing would produce more than {MAX_SEGMENTIZE_POINTS} points"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for SegmentizeError {}
+
+impl Geob {
+    /// Copy of the geometry with vertices added so no segment is longer than
+    /// `max_length`, in meters along great circles for geodetic SRIDs.
+    ///
+    /// Fails if the copy would have more than [`MAX_SEGMENTIZE_POINTS`]
+    /// coordinates.
+    pub fn segmentize(&self, max_length: f64) -> Result<Geob, SegmentizeError> {
+        let mut segmentize = Segmentize {
+            builder: Geob::builder(self.srid()),
+            max_length,
+            metric: Metric::for_srid(self.srid()),
+            points: 0,
+        };
+
+        segmentize.geometry(&self.geometry())?;
+        Ok(segmentize.builder.build().unwrap())
+    }
+}
+
+struct Segmentize {
+    builder: GeobBuilder,
+    max_length: f64,
+    metric: Metric,
+    /// Coordinates written so far
+    points: usize,
+}
+
+impl Segmentize {
+    fn geometry(&mut self, geo: &GeometryRef<'_>) -> Result<(), SegmentizeError> {
+        match geo {
+            GeometryRef::Point(point) => self.builder.point(point.x(), point.y()).unwrap(),
+            GeometryRef::MultiPoint(_) => self.builder.geometry(geo).unwrap(),
+            GeometryRef::LineString(line) => {
+                self.builder.begin_line_string().unwrap();
+                self.coords(line.0)?;
+                self.builder.end().unwrap();
+            }
+            GeometryRef::MultiLineString(lines) => {
+                self.builder.begin_multi_line_string().unwrap();
+                for line in lines.iter() {
+                    self.builder.begin_line_string().unwrap();
+                    self.coords(line)?;
+                    self.builder.end().unwrap();
+                }
+                self.builder.end().unwrap();
+            }
+            GeometryRef::Polygon(rings) => self.polygon(rings)?,
+            GeometryRef::MultiPolygon(polygons) => {
+                self.builder.begin_multi_polygon().unwrap();
+                for rings in polygons.iter() {
+                    self.polygon(&rings)?;
+                }
+                self.builder.end().unwrap();
+            }
+            GeometryRef::Collection(collection) => {
+                self.builder.begin_collection().unwrap();
+                for geo in collection.iter() {
+                    self.geometry(&geo)?;
+                }
+                self.builder.end().unwrap();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn polygon(&mut self, polygon: &PolygonRef<'_>) -> Result<(), SegmentizeError> {
+        self.builder.begin_polygon().unwrap();
+        for ring in polygon.iter() {
+            self.builder.begin_ring().unwrap();
+            self.coords(ring)?;
+            self.builder.end().unwrap();
+        }
+        self.builder.end().unwrap();
+
+        Ok(())
+    }
+
+    fn coords(&mut self, seq: CoordSeqRef<'_>) -> Result<(), SegmentizeError> {
+        let mut prev = None;
+
+        for coord in seq.iter() {
+            let coord = (coord.x(), coord.y());
+            let mut count = 1.0;
+
+            if let Some(prev) = prev {
+                let segments = ceil(self.metric.distance(prev, coord) / self.max_length);
+                // Non finite coordinates or lengths are left as they are
+                if segments.is_finite() && segments > 1.0 {
+                    count = segments;
+                }
+            }
+
+            // Checked before writing, as count may be far beyond usize
+            if count > (MAX_SEGMENTIZE_POINTS - self.points) as f64 {
+                return Err(SegmentizeError::TooManyPoints);
+            }
+            self.points += count as usize;
+
+            if let Some(prev) = prev {
+                for idx in 1..count as usize {
+                    let (x, y) = self.metric.interpolate(prev, coord, idx as f64 / count);
+                    self.builder.coord(x, y).unwrap();
+                }
+            }
+
+            self.builder.coord(coord.0, coord.1).unwrap();
+            prev = Some(coord);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SegmentizeError;
+    use crate::{Geob, types::GeometryRef};
+
+    #[test]
+    fn segmentize() {
+        let geo =
+            Geob::from_text("SRID=3857;POLYGON((0.0 0.0, 10.0 0.0, 10.0 10.0, 0.0 0.0))").unwrap();
+
+        let dense = geo.segmentize(4.0).unwrap();
+        assert_eq!(dense.srid(), geo.srid());
+        // 3 + 3 + 4 segments
+        assert_eq!(dense.geometry().num_points(), 11);
+        assert_eq!(dense.geometry().signed_area(), geo.geometry().signed_area());
+
+        // One degree of longitude along the equator is about 111 km
+        let geo = Geob::from_text("SRID=4326;LINESTRING(0.0 0.0, 1.0 0.0)").unwrap();
+        let dense = geo.segmentize(50_000.0).unwrap();
+        let GeometryRef::LineString(line) = dense.geometry() else {
+            panic!("expected a linestring");
+        };
+        assert_eq!(line.len(), 4);
+        assert_eq!(
+            line.iter().nth(1).map(|c| (c.x(), c.y())),
+            Some((1.0 / 3.0, 0.0))
+        );
+
+        let geo = Geob::from_text("SRID=3857;LINESTRING(0 0, 1e15 0)").unwrap();
+        assert_eq!(geo.segmentize(1e-3), Err(SegmentizeError::TooManyPoints));
+    }
+}
@@ -0,0 +1,601 @@
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+use libm::atan2;
+
+use crate::{Envelope, Geob, mvt::clip_ring};
+
+type Coord = (f64, f64);
+
+/// Vertex at infinity, the apex of the triangles outside the convex hull
+const INFINITE: usize = usize::MAX;
+
+impl Geob {
+    /// Delaunay triangulation of the distinct vertices, as a collection of
+    /// counter clockwise triangles. Empty if the vertices are collinear.
+    pub fn delaunay_triangles(&self) -> Geob {
+        let sites = sites(self);
+        let frame = Frame::new(&sites);
+        let triangles = triangulate(&frame.normalize(&sites)).unwrap_or_default();
+
+        let mut builder = Geob::builder(self.srid());
+        builder.begin_collection().unwrap();
+
+        for [a, b, c] in triangles {
+            if c == INFINITE {
+                continue;
+            }
+
+            builder.begin_polygon().unwrap();
+            builder.begin_ring().unwrap();
+            for idx in [a, b, c, a] {
+                let (x, y) = sites[idx];
+                builder.coord(x, y).unwrap();
+            }
+            builder.end().unwrap();
+            builder.end().unwrap();
+        }
+
+        builder.end().unwrap();
+        builder.build().unwrap()
+    }
+
+    /// Voronoi cells of the distinct vertices, as a collection of polygons.
+    ///
+    /// Cells are clipped to the envelope of the vertices expanded by its larger
+    /// side, grown to cover `extend_to`. Empty with fewer than two vertices, or
+    /// when `extend_to` is too large to express relative to the vertices.
+    pub fn voronoi_polygons(&self, extend_to: Option<&Envelope>) -> Geob {
+        let sites = sites(self);
+
+        let mut builder = Geob::builder(self.srid());
+        builder.begin_collection().unwrap();
+
+        if sites.len() >= 2 {
+            let frame = Frame::new(&sites);
+            for cell in voronoi(
+                &frame.normalize(&sites),
+                extend_to.map(|e| frame.envelope(e)),
+            ) {
+                let Some(&(x0, y0)) = cell.first() else {
+                    continue;
+                };
+
+                builder.begin_polygon().unwrap();
+                builder.begin_ring().unwrap();
+                for &coord in cell.iter().chain([&(x0, y0)]) {
+                    let (x, y) = frame.denormalize(coord);
+                    builder.coord(x, y).unwrap();
+                }
+                builder.end().unwrap();
+                builder.end().unwrap();
+            }
+        }
+
+        builder.end().unwrap();
+        builder.build().unwrap()
+    }
+}
+
+/// Clipped Voronoi cells of at least two distinct sites, in the order of the
+/// sites. Empty if the clip envelope is not finite.
+fn voronoi(sites: &[Coord], extend_to: Option<Envelope>) -> Vec<Vec<Coord>> {
+    let count = sites.len();
+
+    let mut clip = bounds(sites);
+    let margin = clip.width().max(clip.height());
+    clip = Envelope::new(
+        clip.min_x - margin,
+        clip.min_y - margin,
+        clip.max_x + margin,
+        clip.max_y + margin,
+    );
+    if let Some(extend_to) = extend_to {
+        clip = clip.merge(&extend_to);
+    }
+
+    // Far away sites bound the cells of the hull vertices without reaching
+    // into the clip envelope
+    let (cx, cy) = clip.center();
+    let far = 10.0 * (clip.width() + clip.height());
+    if !far.is_finite() {
+        return Vec::new();
+    }
+
+    let mut sites = sites.to_vec();
+    sites.extend([
+        (cx - far, cy - far),
+        (cx + far, cy - far),
+        (cx + far, cy + far),
+        (cx - far, cy + far),
+    ]);
+
+    let Some(triangles) = triangulate(&sites) else {
+        return Vec::new();
+    };
+
+    let mut cells = vec![Vec::new(); count];
+    for triangle in triangles {
+        if triangle[2] == INFINITE {
+            continue;
+        }
+
+        let center = circumcenter(sites[triangle[0]], sites[triangle[1]], sites[triangle[2]]);
+        for idx in triangle {
+            if idx < count {
+                cells[idx].push(center);
+            }
+        }
+    }
+
+    cells
+        .into_iter()
+        .zip(&sites)
+        .map(|(mut cell, &(x, y))| {
+            cell.sort_by(|a, b| atan2(a.1 - y, a.0 - x).total_cmp(&atan2(b.1 - y, b.0 - x)));
+            clip_ring(&cell, &clip)
+        })
+        .collect()
+}
+
+/// Maps sites into `[-1, 1]` around the center of their envelope, so the
+/// predicates and the far away Voronoi sites stay within range however far
+/// apart the sites are
+struct Frame {
+    center: Coord,
+    scale: f64,
+}
+
+impl Frame {
+    fn new(sites: &[Coord]) -> Frame {
+        let Some(&first) = sites.first() else {
+            return Frame {
+                center: (0.0, 0.0),
+                scale: 1.0,
+            };
+        };
+
+        let envelope = bounds(sites);
+        // Halved first so the center and the span don't overflow
+        let center = (
+            envelope.min_x / 2.0 + envelope.max_x / 2.0,
+            envelope.min_y / 2.0 + envelope.max_y / 2.0,
+        );
+        let scale = (envelope.max_x / 2.0 - envelope.min_x / 2.0)
+            .max(envelope.max_y / 2.0 - envelope.min_y / 2.0);
+
+        if scale > 0.0 {
+            Frame { center, scale }
+        } else {
+            Frame {
+                center: first,
+                scale: 1.0,
+            }
+        }
+    }
+
+    fn normalize(&self, sites: &[Coord]) -> Vec<Coord> {
+        sites
+            .iter()
+            .map(|&(x, y)| {
+                (
+                    (x - self.center.0) / self.scale,
+                    (y - self.center.1) / self.scale,
+                )
+            })
+            .collect()
+    }
+
+    fn envelope(&self, envelope: &Envelope) -> Envelope {
+        Envelope::new(
+            (envelope.min_x - self.center.0) / self.scale,
+            (envelope.min_y - self.center.1) / self.scale,
+            (envelope.max_x - self.center.0) / self.scale,
+            (envelope.max_y - self.center.1) / self.scale,
+        )
+    }
+
+    fn denormalize(&self, (x, y): Coord) -> Coord {
+        (
+            self.center.0 + x * self.scale,
+            self.center.1 + y * self.scale,
+        )
+    }
+}
+
+/// Distinct finite vertices, sorted
+fn sites(geo: &Geob) -> Vec<Coord> {
+    let mut sites = Vec::with_capacity(geo.geometry().num_points());
+    geo.geometry().for_each_coord(&mut |x, y| {
+        if x.is_finite() && y.is_finite() {
+            sites.push((x, y));
+        }
+    });
+
+    sites.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    sites.dedup();
+    sites
+}
+
+fn bounds(sites: &[Coord]) -> Envelope {
+    let mut envelope = Envelope::from_coord(sites[0].0, sites[0].1);
+    for &(x, y) in &sites[1..] {
+        envelope.expand(x, y);
+    }
+    envelope
+}
+
+/// Twice the signed area of the triangle, positive if counter clockwise
+fn orient(a: Coord, b: Coord, c: Coord) -> f64 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+/// Positive if `p` lies inside the circumcircle of the counter clockwise
+/// triangle
+fn in_circle(a: Coord, b: Coord, c: Coord, p: Coord) -> f64 {
+    let (ax, ay) = (a.0 - p.0, a.1 - p.1);
+    let (bx, by) = (b.0 - p.0, b.1 - p.1);
+    let (cx, cy) = (c.0 - p.0, c.1 - p.1);
+
+    (ax * ax + ay * ay) * (bx * cy - cx * by) - (bx * bx + by * by) * (ax * cy - cx * ay)
+        + (cx * cx + cy * cy) * (ax * by - bx * ay)
+}
+
+fn circumcenter(a: Coord, b: Coord, c: Coord) -> Coord {
+    let (bx, by) = (b.0 - a.0, b.1 - a.1);
+    let (cx, cy) = (c.0 - a.0, c.1 - a.1);
+    let d = 2.0 * (bx * cy - by * cx);
+
+    let b2 = bx * bx + by * by;
+    let c2 = cx * cx + cy * cy;
+
+    (a.0 + (cy * b2 - by * c2) / d, a.1 + (bx * c2 - cx * b2) / d)
+}
+
+/// Bowyer-Watson triangulation of distinct sites. Triangles are counter
+/// clockwise, and those outside the hull have [`INFINITE`] as third vertex.
+///
+/// Sites are inserted in sorted order, each located by walking from the
+/// triangles of the previous one, so the conflict region is found by
+/// following neighbors instead of testing every triangle.
+///
+/// Empty if the sites are collinear, and `None` if rounding leaves a site
+/// without a triangle to insert it into.
+fn triangulate(sites: &[Coord]) -> Option<Vec<[usize; 3]>> {
+    // Seed with the first non collinear triple
+    let Some(c) = (2..sites.len()).find(|c| orient(sites[0], sites[1], sites[*c]) != 0.0) else {
+        return Some(Vec::new());
+    };
+
+    let (a, b) = if orient(sites[0], sites[1], sites[c]) > 0.0 {
+        (0, 1)
+    } else {
+        (1, 0)
+    };
+
+    let mut mesh = Mesh::default();
+    let seed: Vec<_> = [
+        [a, b, c],
+        [b, a, INFINITE],
+        [c, b, INFINITE],
+        [a, c, INFINITE],
+    ]
+    .into_iter()
+    .map(|triangle| mesh.add(triangle))
+    .collect();
+    mesh.link(&seed);
+
+    let mut last = seed[0];
+    for p in (2..sites.len()).filter(|p| *p != c) {
+        last = mesh.insert(sites, p, last)?;
+    }
+
+    Some(
+        mesh.triangles
+            .into_iter()
+            .zip(mesh.alive)
+            .filter_map(|(triangle, alive)| alive.then_some(triangle))
+            .collect(),
+    )
+}
+
+/// Triangles with the neighbor across the edge opposite each vertex
+#[derive(Default)]
+struct Mesh {
+    triangles: Vec<[usize; 3]>,
+    neighbors: Vec<[usize; 3]>,
+    alive: Vec<bool>,
+    free: Vec<usize>,
+}
+
+impl Mesh {
+    fn add(&mut self, triangle: [usize; 3]) -> usize {
+        match self.free.pop() {
+            Some(idx) => {
+                self.triangles[idx] = triangle;
+                self.neighbors[idx] = [INFINITE; 3];
+                self.alive[idx] = true;
+                idx
+            }
+            None => {
+                self.triangles.push(triangle);
+                self.neighbors.push([INFINITE; 3]);
+                self.alive.push(true);
+                self.triangles.len() - 1
+            }
+        }
+    }
+
+    /// Edge opposite vertex `idx` of a triangle
+    fn edge(&self, t: usize, idx: usize) -> (usize, usize) {
+        let triangle = self.triangles[t];
+        (triangle[(idx + 1) % 3], triangle[(idx + 2) % 3])
+    }
+
+    /// Connect the triangles sharing an edge among `new`
+    fn link(&mut self, new: &[usize]) {
+        let mut open = BTreeMap::new();
+        for &t in new {
+            for idx in 0..3 {
+                let (a, b) = self.edge(t, idx);
+                match open.remove(&(b, a)) {
+                    Some((other, other_idx)) => {
+                        self.neighbors[t][idx] = other;
+                        self.neighbors[other][other_idx] = t;
+                    }
+                    None => {
+                        open.insert((a, b), (t, idx));
+                    }
+                }
+            }
+        }
+    }
+
+    fn conflict(&self, sites: &[Coord], t: usize, point: Coord) -> bool {
+        let [a, b, c] = self.triangles[t];
+        if c == INFINITE {
+            // The circumcircle of an outer triangle is the half plane beyond
+            // its hull edge, including the open edge itself
+            let o = orient(sites[a], sites[b], point);
+            o > 0.0 || (o == 0.0 && between(sites[a], sites[b], point))
+        } else {
+            in_circle(sites[a], sites[b], sites[c], point) > 0.0
+        }
+    }
+
+    /// A triangle in conflict with `point`, walking towards it from `start`
+    fn locate(&self, sites: &[Coord], start: usize, point: Coord) -> Option<usize> {
+        let mut t = start;
+
+        'walk: for _ in 0..self.triangles.len() {
+            if self.conflict(sites, t, point) {
+                return Some(t);
+            }
+
+            if self.triangles[t][2] == INFINITE {
+                t = self.neighbors[t][2];
+                continue;
+            }
+
+            for idx in 0..3 {
+                let (a, b) = self.edge(t, idx);
+                if orient(sites[a], sites[b], point) < 0.0 {
+                    t = self.neighbors[t][idx];
+                    continue 'walk;
+                }
+            }
+
+            break;
+        }
+
+        // Rounding can stall the walk, fall back to a scan
+        (0..self.triangles.len()).find(|t| self.alive[*t] && self.conflict(sites, *t, point))
+    }
+
+    /// Insert site `p` and return one of the new triangles
+    fn insert(&mut self, sites: &[Coord], p: usize, start: usize) -> Option<usize> {
+        let point = sites[p];
+
+        // The conflict region is connected, flood it from the located triangle
+        let mut bad = vec![self.locate(sites, start, point)?];
+        self.alive[bad[0]] = false;
+        let mut idx = 0;
+        while let Some(&t) = bad.get(idx) {
+            for n in self.neighbors[t] {
+                if self.alive[n] && self.conflict(sites, n, point) {
+                    self.alive[n] = false;
+                    bad.push(n);
+                }
+            }
+            idx += 1;
+        }
+
+        // Connect `p` to the edges between the cavity and the rest
+        let mut boundary = Vec::new();
+        for &t in &bad {
+            for idx in 0..3 {
+                let outer = self.neighbors[t][idx];
+                if self.alive[outer] {
+                    let back = (0..3).find(|i| self.neighbors[outer][*i] == t).unwrap();
+                    boundary.push((self.edge(t, idx), outer, back));
+                }
+            }
+        }
+        self.free.extend_from_slice(&bad);
+
+        let mut new = Vec::with_capacity(boundary.len());
+        for ((a, b), outer, back) in boundary {
+            // Also the index of the edge `(a, b)` in the new triangle
+            let (triangle, own) = match (a, b) {
+                (INFINITE, b) => ([b, p, INFINITE], 1),
+                (a, INFINITE) => ([p, a, INFINITE], 0),
+                (a, b) => ([a, b, p], 2),
+            };
+
+            let t = self.add(triangle);
+            self.neighbors[t][own] = outer;
+            self.neighbors[outer][back] = t;
+            new.push(t);
+        }
+
+        self.link(&new);
+        new.first().copied()
+    }
+}
+
+/// Whether `p`, collinear with `a` and `b`, lies strictly between them
+fn between(a: Coord, b: Coord, p: Coord) -> bool {
+    let d = (b.0 - a.0) * (p.0 - a.0) + (b.1 - a.1) * (p.1 - a.1);
+    d > 0.0 && d < (b.0 - a.0) * (b.0 - a.0) + (b.1 - a.1) * (b.1 - a.1)
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{format, string::ToString, vec::Vec};
+
+    use super::{INFINITE, in_circle, orient, sites, triangulate as delaunay};
+    use crate::{Envelope, Geob, SRID, types::GeometryRef};
+
+    /// Check that the triangles cover the hull of the sites with empty
+    /// circumcircles
+    fn check(geo: &Geob) {
+        let sites = sites(geo);
+        let triangles = delaunay(&sites).unwrap();
+
+        let inner: Vec<_> = triangles.iter().filter(|t| t[2] != INFINITE).collect();
+        let hull = triangles.len() - inner.len();
+        // Euler's formula for a triangulated point set
+        assert_eq!(inner.len(), 2 * sites.len() - 2 - hull);
+
+        for &&[a, b, c] in &inner {
+            let (a, b, c) = (sites[a], sites[b], sites[c]);
+            assert!(orient(a, b, c) > 0.0);
+
+            let scale = (b.0 - a.0).hypot(b.1 - a.1) + (c.0 - a.0).hypot(c.1 - a.1);
+            for &p in &sites {
+                assert!(in_circle(a, b, c, p) <= 1e-9 * scale.powi(4));
+            }
+        }
+    }
+
+    #[test]
+    fn triangulate() {
+        let geo = Geob::from_text(
+            "SRID=3857;MULTILINESTRING((0.0 0.0, 4.0 0.0, 4.0 4.0, 0.0 4.0), (1.0 1.0, 3.0 2.0))",
+        )
+        .unwrap();
+
+        let triangles = geo.delaunay_triangles();
+        assert_eq!(triangles.srid(), geo.srid());
+
+        let GeometryRef::Collection(collection) = triangles.geometry() else {
+            panic!("expected a collection");
+        };
+        // 6 vertices with 4 on the hull
+        assert_eq!(collection.len(), 6);
+
+        let area: f64 = collection.iter().map(|t| t.signed_area()).sum();
+        assert!((area - 16.0).abs() < 1e-9);
+
+        let cells = geo.voronoi_polygons(Some(&Envelope::new(-100.0, 0.0, 0.0, 0.0)));
+        let GeometryRef::Collection(collection) = cells.geometry() else {
+            panic!("expected a collection");
+        };
+        assert_eq!(collection.len(), 6);
+
+        // Cells tile the clip envelope
+        let area: f64 = collection.iter().map(|t| t.signed_area()).sum();
+        assert!((area - 108.0 * 12.0).abs() < 1e-6);
+
+        let collinear = Geob::from_text("SRID=0;LINESTRING(0.0 0.0, 1.0 1.0, 2.0 2.0)").unwrap();
+        assert_eq!(
+            collinear.delaunay_triangles().to_string(),
+            "SRID=0;GEOMETRYCOLLECTION()"
+        );
+        assert_eq!(
+            collinear.voronoi_polygons(None).geometry().num_points(),
+            3 * 5
+        );
+    }
+
+    #[test]
+    fn empty_circumcircles() {
+        // Linear congruential generator, the sites are sorted anyway
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut random = || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        };
+
+        let mut builder = Geob::builder(SRID::UNKNOWN);
+        builder.begin_multi_point().unwrap();
+        for _ in 0..400 {
+            builder.coord(random() * 100.0, random() * 100.0).unwrap();
+        }
+        builder.end().unwrap();
+        check(&builder.build().unwrap());
+
+        // Cocircular and collinear sites
+        let mut builder = Geob::builder(SRID::UNKNOWN);
+        builder.begin_multi_point().unwrap();
+        for x in 0..12 {
+            for y in 0..12 {
+                builder.coord(x as f64, y as f64).unwrap();
+            }
+        }
+        builder.end().unwrap();
+        let grid = builder.build().unwrap();
+        check(&grid);
+
+        let triangles = grid.delaunay_triangles();
+        let GeometryRef::Collection(triangles) = triangles.geometry() else {
+            panic!("expected a collection");
+        };
+        assert_eq!(triangles.len(), 2 * 11 * 11);
+        let area: f64 = triangles.iter().map(|t| t.signed_area()).sum();
+        assert!((area - 121.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn far_apart_sites() {
+        for scale in [1e-300, 1e160, 1e307] {
+            let geo = Geob::from_text(&format!(
+                "SRID=0;MULTIPOINT(({scale} {scale}),({} {scale}),({scale} {}))",
+                -scale, -scale
+            ))
+            .unwrap();
+
+            let triangles = geo.delaunay_triangles();
+            let GeometryRef::Collection(triangles) = triangles.geometry() else {
+                panic!("expected a collection");
+            };
+            assert_eq!(triangles.len(), 1);
+
+            let cells = geo.voronoi_polygons(None);
+            let GeometryRef::Collection(collection) = cells.geometry() else {
+                panic!("expected a collection");
+            };
+            assert_eq!(collection.len(), 3, "{scale}");
+
+            let mut finite = true;
+            cells
+                .geometry()
+                .for_each_coord(&mut |x, y| finite &= x.is_finite() && y.is_finite());
+            assert!(finite, "{scale}");
+        }
+
+        let geo = Geob::from_text("SRID=0;MULTIPOINT((0 0),(1e307 0),(0 1e307))").unwrap();
+        let cells = geo.voronoi_polygons(None);
+        let GeometryRef::Collection(collection) = cells.geometry() else {
+            panic!("expected a collection");
+        };
+        assert_eq!(collection.len(), 3);
+
+        // Too large to clip relative to sites this close together
+        let geo = Geob::from_text("SRID=0;MULTIPOINT((0 0),(1e-300 0),(0 1e-300))").unwrap();
+        assert_eq!(
+            geo.voronoi_polygons(Some(&Envelope::new(-1e308, -1e308, 1e308, 1e308)))
+                .geometry()
+                .num_points(),
+            0
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! Proptest strategies for every [`GeoType`](crate::GeoType)
+
+use alloc::vec::Vec;
+use proptest::{
+    collection::vec,
+    prelude::*,
+    test_runner::{Config, TestRng, TestRunner},
+};
+
+use crate::{Coord, Geob, Geometry, SRID};
+
+pub fn coord() -> impl Strategy<Value = Coord> {
+    let n = prop_oneof![
+        8 => finite(),
+        1 => prop::num::f64::QUIET_NAN | prop::num::f64::INFINITE,
+    ];
+    (n.clone(), n).prop_map(Coord::from)
+}
+
+fn finite() -> impl Strategy<Value = f64> {
+    prop_oneof![
+        prop::num::f64::NORMAL | prop::num::f64::SUBNORMAL | prop::num::f64::ZERO,
+        (-1000i32..1000).prop_map(f64::from),
+    ]
+}
+
+fn coords(max: usize) -> impl Strategy<Value = Vec<Coord>> {
+    vec(coord(), 0..max)
+}
+
+/// Closed ring of at least four coordinates. The first coordinate is finite,
+/// geo_types closes a ring again when it starts with NaN.
+fn ring() -> impl Strategy<Value = Vec<Coord>> {
+    ((finite(), finite()), vec(coord(), 2..7)).prop_map(|(first, mut ring)| {
+        ring.insert(0, Coord::from(first));
+        ring.push(ring[0]);
+        ring
+    })
+}
+
+fn polygon() -> impl Strategy<Value = Vec<Vec<Coord>>> {
+    vec(ring(), 0..3)
+}
+
+pub fn geometry() -> impl Strategy<Value = Geometry> {
+    let leaf = prop_oneof![
+        coord().prop_map(Geometry::Point),
+        coords(8).prop_map(Geometry::LineString),
+        polygon().prop_map(Geometry::Polygon),
+        coords(8).prop_map(Geometry::MultiPoint),
+        vec(coords(8), 0..4).prop_map(Geometry::MultiLineString),
+        vec(polygon(), 0..3).prop_map(Geometry::MultiPolygon),
+    ];
+
+    leaf.prop_recursive(3, 32, 4, |inner| {
+        vec(inner, 0..4).prop_map(Geometry::Collection)
+    })
+}
+
+pub fn geob() -> impl Strategy<Value = Geob> {
+    (geometry(), any::<u32>()).prop_map(|(geo, srid)| geo.to_geob(SRID::from(srid)).unwrap())
+}
+
+/// Runner with a fixed seed, so failures reproduce in the normal test suite
+pub fn runner() -> TestRunner {
+    let config = Config {
+        failure_persistence: None,
+        ..Config::default()
+    };
+    let rng = TestRng::deterministic_rng(config.rng_algorithm);
+    TestRunner::new_with_rng(config, rng)
+}
@@ -1,19 +0,0 @@
-mod collection;
-mod coords;
-mod geometry;
-
-mod line_string;
-mod multi_polygon;
-mod point;
-mod polygon;
-
-pub use self::{
-    collection::GeometryCollection,
-    coords::Coord,
-    geometry::Geometry,
-    geometry::{GeoKind, GeoType},
-    line_string::{LineString, MultiPoint},
-    multi_polygon::MultiPolygon,
-    point::Point,
-    polygon::{MultiLineString, Polygon},
-};
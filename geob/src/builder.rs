@@ -0,0 +1,513 @@
+use alloc::vec::Vec;
+use byteorder::{BigEndian, LittleEndian};
+use core::fmt;
+use udled::bytes::Endian;
+
+use crate::{
+    Envelope, GeoType, Geob, SRID,
+    types::{CoordRef, ENVELOPE_LEN, FLAG_ENVELOPE, FLAG_LITTLE_ENDIAN, GeometryRef, PolygonRef},
+    util::write_f64,
+    writer::{BinaryWriter, ToBytes},
+};
+
+/// Maximum nesting depth of a [`GeometryWriter`].
+///
+/// A multipolygon needs three levels (multipolygon, polygon, ring), nested
+/// collections need one more level each.
+pub const MAX_DEPTH: usize = 16;
+
+/// A [`GeometryWriter`] writing into a `Vec<u8>`, see [`Geob::builder`]
+pub type GeobBuilder = GeometryWriter<Vec<u8>>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuilderError<E> {
+    /// Error reported by the underlying writer
+    Writer(E),
+    /// The operation is not valid inside the current geometry
+    Unexpected {
+        parent: Option<GeoType>,
+        found: &'static str,
+    },
+    /// Nested deeper than [`MAX_DEPTH`]
+    TooDeep,
+    /// `finish` was called before every geometry was ended
+    Incomplete,
+}
+
+impl<E> From<E> for BuilderError<E> {
+    fn from(value: E) -> Self {
+        BuilderError::Writer(value)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for BuilderError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuilderError::Writer(err) => write!(f, "writer: {err}"),
+            BuilderError::Unexpected {
+                parent: Some(parent),
+                found,
+            } => write!(f, "unexpected {found} in {parent}"),
+            BuilderError::Unexpected {
+                parent: None,
+                found,
+            } => write!(f, "unexpected {found}"),
+            BuilderError::TooDeep => write!(f, "geometry nested deeper than {MAX_DEPTH}"),
+            BuilderError::Incomplete => write!(f, "geometry is not complete"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> core::error::Error for BuilderError<E> {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    LineString,
+    Ring,
+    Polygon,
+    MultiPoint,
+    MultiLineString,
+    MultiPolygon,
+    Collection,
+}
+
+impl Kind {
+    fn geo_type(&self) -> GeoType {
+        match self {
+            Kind::LineString | Kind::Ring => GeoType::LineString,
+            Kind::Polygon => GeoType::Polygon,
+            Kind::MultiPoint => GeoType::MultiPoint,
+            Kind::MultiLineString => GeoType::MultiLineString,
+            Kind::MultiPolygon => GeoType::MultiPolygon,
+            Kind::Collection => GeoType::Collection,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    kind: Kind,
+    pos: usize,
+    count: u32,
+}
+
+const EMPTY: Frame = Frame {
+    kind: Kind::Collection,
+    pos: 0,
+    count: 0,
+};
+
+/// Streaming writer for geob encoded geometries.
+///
+/// Geometries are opened with one of the `begin_*` methods, filled with
+/// coordinates or child geometries and closed with [`GeometryWriter::end`],
+/// which back-patches the element count. Does not allocate, so works with any
+/// [`BinaryWriter`].
+///
+/// ```
+/// use geob::{Geob, SRID};
+///
+/// let mut builder = Geob::builder(SRID::WGS84);
+///
+/// builder.begin_polygon().unwrap();
+/// builder.begin_ring().unwrap();
+/// builder.coord(0.0, 0.0).unwrap();
+/// builder.coord(1.0, 0.0).unwrap();
+/// builder.coord(1.0, 1.0).unwrap();
+/// builder.coord(0.0, 0.0).unwrap();
+/// builder.end().unwrap();
+/// builder.end().unwrap();
+///
+/// let geob = builder.build().unwrap();
+///
+/// assert_eq!(geob.to_string(), "SRID=4326;POLYGON((0 0, 1 0, 1 1, 0 0))");
+/// ```
+pub struct GeometryWriter<W> {
+    output: W,
+    endian: Endian,
+    stack: [Frame; MAX_DEPTH],
+    depth: usize,
+    done: bool,
+    envelope: Option<usize>,
+    bounds: Option<Envelope>,
+}
+
+impl<W: BinaryWriter> GeometryWriter<W> {
+    /// Write the geob header into `output`
+    pub fn new(mut output: W, srid: SRID, endian: Endian) -> Result<Self, BuilderError<W::Error>> {
+        endian.write(&mut output, endian)?;
+        srid.write(&mut output, endian)?;
+
+        Ok(GeometryWriter {
+            output,
+            endian,
+            stack: [EMPTY; MAX_DEPTH],
+            depth: 0,
+            done: false,
+            envelope: None,
+            bounds: None,
+        })
+    }
+
+    /// Write the geob header into `output`, reserving room for a bounding box
+    /// which is filled in by [`GeometryWriter::finish`]
+    pub fn with_envelope(
+        mut output: W,
+        srid: SRID,
+        endian: Endian,
+    ) -> Result<Self, BuilderError<W::Error>> {
+        let flags = match endian {
+            Endian::Big => FLAG_ENVELOPE,
+            Endian::Lt => FLAG_ENVELOPE | FLAG_LITTLE_ENDIAN,
+        };
+
+        output.write_u8(flags)?;
+        srid.write(&mut output, endian)?;
+
+        let envelope = output.position();
+        output.write_all(&[0; ENVELOPE_LEN])?;
+
+        Ok(GeometryWriter {
+            output,
+            endian,
+            stack: [EMPTY; MAX_DEPTH],
+            depth: 0,
+            done: false,
+            envelope: Some(envelope),
+            bounds: None,
+        })
+    }
+
+    /// Bounding box of the coordinates written so far
+    pub fn bounds(&self) -> Option<Envelope> {
+        self.bounds
+    }
+
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    /// Current nesting depth
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Write a point, either as the root geometry, inside a collection or
+    /// inside a multipoint.
+    pub fn point(&mut self, x: f64, y: f64) -> Result<(), BuilderError<W::Error>> {
+        match self.parent() {
+            Some(Kind::MultiPoint) => {}
+            _ => self.start(GeoType::Point, "point")?,
+        }
+
+        self.write_coord(x, y)
+    }
+
+    /// Push a coordinate onto the open linestring, ring or multipoint
+    pub fn coord(&mut self, x: f64, y: f64) -> Result<(), BuilderError<W::Error>> {
+        match self.parent() {
+            Some(Kind::LineString | Kind::Ring | Kind::MultiPoint) => self.write_coord(x, y),
+            parent => Err(unexpected(parent, "coordinate")),
+        }
+    }
+
+    pub fn begin_line_string(&mut self) -> Result<(), BuilderError<W::Error>> {
+        match self.parent() {
+            Some(Kind::MultiLineString) => {}
+            _ => self.start(GeoType::LineString, "linestring")?,
+        }
+
+        self.push(Kind::LineString)
+    }
+
+    pub fn begin_polygon(&mut self) -> Result<(), BuilderError<W::Error>> {
+        match self.parent() {
+            Some(Kind::MultiPolygon) => {}
+            _ => self.start(GeoType::Polygon, "polygon")?,
+        }
+
+        self.push(Kind::Polygon)
+    }
+
+    /// Begin a ring of the open polygon. The first ring is the exterior.
+    pub fn begin_ring(&mut self) -> Result<(), BuilderError<W::Error>> {
+        match self.parent() {
+            Some(Kind::Polygon) => self.push(Kind::Ring),
+            parent => Err(unexpected(parent, "ring")),
+        }
+    }
+
+    pub fn begin_multi_point(&mut self) -> Result<(), BuilderError<W::Error>> {
+        self.start(GeoType::MultiPoint, "multipoint")?;
+        self.push(Kind::MultiPoint)
+    }
+
+    pub fn begin_multi_line_string(&mut self) -> Result<(), BuilderError<W::Error>> {
+        self.start(GeoType::MultiLineString, "multilinestring")?;
+        self.push(Kind::MultiLineString)
+    }
+
+    pub fn begin_multi_polygon(&mut self) -> Result<(), BuilderError<W::Error>> {
+        self.start(GeoType::MultiPolygon, "multipolygon")?;
+        self.push(Kind::MultiPolygon)
+    }
+
+    pub fn begin_collection(&mut self) -> Result<(), BuilderError<W::Error>> {
+        self.start(GeoType::Collection, "collection")?;
+        self.push(Kind::Collection)
+    }
+
+    /// Copy a whole geometry, either as the root geometry or as a child of the
+    /// open geometry
+    pub fn geometry(&mut self, geo: &GeometryRef<'_>) -> Result<(), BuilderError<W::Error>> {
+        match geo {
+            GeometryRef::Point(point) => self.point(point.x(), point.y()),
+            GeometryRef::LineString(line) => {
+                self.begin_line_string()?;
+                self.coords(line.iter())?;
+                self.end()
+            }
+            GeometryRef::Polygon(polygon) => self.polygon(polygon),
+            GeometryRef::MultiPoint(points) => {
+                self.begin_multi_point()?;
+                self.coords(points.iter())?;
+                self.end()
+            }
+            GeometryRef::MultiLineString(lines) => {
+                self.begin_multi_line_string()?;
+                for line in lines.iter() {
+                    self.begin_line_string()?;
+                    self.coords(line.iter())?;
+                    self.end()?;
+                }
+                self.end()
+            }
+            GeometryRef::MultiPolygon(polygons) => {
+                self.begin_multi_polygon()?;
+                for polygon in polygons.iter() {
+                    self.polygon(&polygon)?;
+                }
+                self.end()
+            }
+            GeometryRef::Collection(collection) => {
+                self.begin_collection()?;
+                for geo in collection.iter() {
+                    self.geometry(&geo)?;
+                }
+                self.end()
+            }
+        }
+    }
+
+    fn polygon(&mut self, polygon: &PolygonRef<'_>) -> Result<(), BuilderError<W::Error>> {
+        self.begin_polygon()?;
+        for ring in polygon.iter() {
+            self.begin_ring()?;
+            self.coords(ring.iter())?;
+            self.end()?;
+        }
+        self.end()
+    }
+
+    fn coords<'a>(
+        &mut self,
+        coords: impl Iterator<Item = CoordRef<'a>>,
+    ) -> Result<(), BuilderError<W::Error>> {
+        for coord in coords {
+            self.coord(coord.x(), coord.y())?;
+        }
+        Ok(())
+    }
+
+    /// Close the innermost open geometry and write its element count
+    pub fn end(&mut self) -> Result<(), BuilderError<W::Error>> {
+        if self.depth == 0 {
+            return Err(unexpected(None, "end"));
+        }
+
+        self.depth -= 1;
+        let frame = self.stack[self.depth];
+
+        match self.endian {
+            Endian::Big => self
+                .output
+                .write_u32_at::<BigEndian>(frame.pos, frame.count)?,
+            Endian::Lt => self
+                .output
+                .write_u32_at::<LittleEndian>(frame.pos, frame.count)?,
+        }
+
+        self.increment();
+
+        Ok(())
+    }
+
+    /// Return the underlying writer once the root geometry is complete
+    pub fn finish(mut self) -> Result<W, BuilderError<W::Error>> {
+        if !self.done || self.depth != 0 {
+            return Err(BuilderError::Incomplete);
+        }
+
+        if let Some(pos) = self.envelope {
+            let Envelope {
+                min_x,
+                min_y,
+                max_x,
+                max_y,
+            } = self
+                .bounds
+                .unwrap_or(Envelope::new(f64::NAN, f64::NAN, f64::NAN, f64::NAN));
+
+            let mut buf = [0; ENVELOPE_LEN];
+            for (idx, n) in [min_x, min_y, max_x, max_y].into_iter().enumerate() {
+                write_f64(&mut buf[idx * 8..], n, self.endian);
+            }
+
+            self.output.write_all_at(&buf, pos)?;
+        }
+
+        Ok(self.output)
+    }
+
+    fn parent(&self) -> Option<Kind> {
+        self.depth.checked_sub(1).map(|idx| self.stack[idx].kind)
+    }
+
+    /// Write the type byte of a geometry that may stand on its own
+    fn start(&mut self, ty: GeoType, found: &'static str) -> Result<(), BuilderError<W::Error>> {
+        match self.parent() {
+            None if !self.done => {}
+            Some(Kind::Collection) => {}
+            parent => return Err(unexpected(parent, found)),
+        }
+
+        ty.write(&mut self.output, self.endian)?;
+
+        if self.depth == 0 {
+            self.done = true;
+        }
+
+        Ok(())
+    }
+
+    fn push(&mut self, kind: Kind) -> Result<(), BuilderError<W::Error>> {
+        if self.depth == MAX_DEPTH {
+            return Err(BuilderError::TooDeep);
+        }
+
+        let pos = self.output.position();
+        0u32.write(&mut self.output, self.endian)?;
+
+        self.stack[self.depth] = Frame {
+            kind,
+            pos,
+            count: 0,
+        };
+        self.depth += 1;
+
+        Ok(())
+    }
+
+    fn write_coord(&mut self, x: f64, y: f64) -> Result<(), BuilderError<W::Error>> {
+        x.write(&mut self.output, self.endian)?;
+        y.write(&mut self.output, self.endian)?;
+
+        match &mut self.bounds {
+            Some(bounds) => bounds.expand(x, y),
+            None => self.bounds = Some(Envelope::from_coord(x, y)),
+        }
+
+        self.increment();
+
+        Ok(())
+    }
+
+    fn increment(&mut self) {
+        if let Some(idx) = self.depth.checked_sub(1) {
+            self.stack[idx].count += 1;
+        }
+    }
+}
+
+impl GeometryWriter<Vec<u8>> {
+    pub fn build(self) -> Result<Geob, BuilderError<core::convert::Infallible>> {
+        self.finish().map(Geob::new)
+    }
+}
+
+impl Geob {
+    /// Create a builder using native endian
+    pub fn builder(srid: SRID) -> GeobBuilder {
+        GeometryWriter::new(Vec::new(), srid, Endian::native()).unwrap()
+    }
+}
+
+fn unexpected<E>(parent: Option<Kind>, found: &'static str) -> BuilderError<E> {
+    BuilderError::Unexpected {
+        parent: parent.map(|kind| kind.geo_type()),
+        found,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::ToString;
+
+    use super::BuilderError;
+    use crate::{GeoType, Geob, SRID};
+
+    #[test]
+    fn build() {
+        let mut builder = Geob::builder(SRID::WGS84);
+
+        builder.begin_collection().unwrap();
+        builder.point(1.0, 2.0).unwrap();
+        builder.begin_multi_polygon().unwrap();
+        for offset in [0.0, 10.0] {
+            builder.begin_polygon().unwrap();
+            builder.begin_ring().unwrap();
+            for (x, y) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)] {
+                builder.coord(x + offset, y).unwrap();
+            }
+            builder.end().unwrap();
+            builder.end().unwrap();
+        }
+        builder.end().unwrap();
+        builder.begin_line_string().unwrap();
+        builder.coord(0.0, 0.0).unwrap();
+        builder.coord(5.0, 5.0).unwrap();
+        builder.end().unwrap();
+        builder.end().unwrap();
+
+        let geob = builder.build().unwrap();
+
+        let text = "SRID=4326;GEOMETRYCOLLECTION(POINT(1 2), MULTIPOLYGON(((0 0, 1 0, 1 1, 0 0)), ((10 0, 11 0, 11 1, 10 0))), LINESTRING(0 0, 5 5))";
+
+        assert_eq!(geob.kind(), GeoType::Collection);
+        assert_eq!(geob.to_string(), text);
+        assert_eq!(Geob::from_bytes(geob.slice().to_vec()).unwrap(), geob);
+    }
+
+    #[test]
+    fn invalid() {
+        let mut builder = Geob::builder(SRID::WGS84);
+        assert!(matches!(
+            builder.coord(0.0, 0.0),
+            Err(BuilderError::Unexpected { parent: None, .. })
+        ));
+
+        builder.begin_line_string().unwrap();
+        assert!(builder.begin_ring().is_err());
+        assert!(builder.point(1.0, 1.0).is_err());
+        builder.coord(0.0, 0.0).unwrap();
+        builder.end().unwrap();
+
+        assert!(builder.point(1.0, 1.0).is_err());
+        assert!(builder.end().is_err());
+
+        assert!(matches!(
+            Geob::builder(SRID::WGS84).build(),
+            Err(BuilderError::Incomplete)
+        ));
+    }
+}
@@ -0,0 +1,378 @@
+use alloc::vec::Vec;
+use core::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+};
+use udled::bytes::Endian;
+
+use crate::{
+    GeoType, Geob, GeobRef,
+    types::{CoordRef, CoordSeqRef, FLAG_LITTLE_ENDIAN, GEOB_HEADER, GeometryRef, Layout},
+    util::read_u32,
+};
+
+impl Geob {
+    /// Copy of the geometry encoded with `endian`
+    pub fn to_endian(&self, endian: Endian) -> Geob {
+        let from = self.endian();
+        if from == endian {
+            return self.clone();
+        }
+
+        let layout = self.as_ref().layout();
+        let header = self.as_ref().header_len();
+        let mut output = Vec::from(self.slice());
+
+        output[0] ^= FLAG_LITTLE_ENDIAN;
+        output[1..GEOB_HEADER].reverse();
+        swap_numbers(&mut output[GEOB_HEADER..header], 8);
+        swap(&mut output[header..], layout);
+
+        Geob::new(output)
+    }
+
+    /// Copy of the geometry in canonical form: little endian, with negative
+    /// zero written as zero and every NaN as the same quiet NaN.
+    ///
+    /// Geometries that are equal and agree on caching an envelope have the
+    /// same canonical bytes.
+    pub fn canonicalize(&self) -> Geob {
+        if self.is_canonical() {
+            return self.clone();
+        }
+
+        let mut geo = self.to_endian(Endian::Lt);
+        geo.rewrite_coords(|x, y| (canonical(x), canonical(y)));
+        geo
+    }
+
+    /// Returns true if the bytes are already in canonical form, see
+    /// [`Geob::canonicalize`]
+    pub fn is_canonical(&self) -> bool {
+        if self.endian() != Endian::Lt {
+            return false;
+        }
+
+        let is_canonical = |n: f64| n.to_bits() == canonical(n).to_bits();
+
+        let mut canonical = true;
+        self.geometry()
+            .for_each_coord(&mut |x, y| canonical &= is_canonical(x) && is_canonical(y));
+
+        if let Some(envelope) = self.has_envelope().then(|| self.envelope()).flatten() {
+            canonical &= [
+                envelope.min_x,
+                envelope.min_y,
+                envelope.max_x,
+                envelope.max_y,
+            ]
+            .into_iter()
+            .all(is_canonical);
+        }
+
+        canonical
+    }
+}
+
+fn canonical(n: f64) -> f64 {
+    if n.is_nan() {
+        f64::NAN
+    } else if n == 0.0 {
+        0.0
+    } else {
+        n
+    }
+}
+
+// Equality, hashing and ordering follow the canonical form, so -0.0 equals
+// 0.0, NaN equals NaN and the endian and cached envelope are ignored.
+// Geometries sort by SRID, type, then coordinates as nested slices.
+
+impl PartialEq for Geob {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_ref() == other.as_ref()
+    }
+}
+
+impl Eq for Geob {}
+
+impl Hash for Geob {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_ref().hash(state);
+    }
+}
+
+impl PartialOrd for Geob {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Geob {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_ref().cmp(&other.as_ref())
+    }
+}
+
+impl<'a> PartialEq for GeobRef<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<'a> Eq for GeobRef<'a> {}
+
+impl<'a> Hash for GeobRef<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.srid().hash(state);
+        hash_geometry(&self.geometry(), state);
+    }
+}
+
+impl<'a> PartialOrd for GeobRef<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a> Ord for GeobRef<'a> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.srid()
+            .cmp(&other.srid())
+            .then_with(|| cmp_geometry(&self.geometry(), &other.geometry()))
+    }
+}
+
+fn cmp_geometry(a: &GeometryRef<'_>, b: &GeometryRef<'_>) -> Ordering {
+    match (a, b) {
+        (GeometryRef::Point(a), GeometryRef::Point(b)) => canonical(a.x())
+            .total_cmp(&canonical(b.x()))
+            .then_with(|| canonical(a.y()).total_cmp(&canonical(b.y()))),
+        (GeometryRef::LineString(a), GeometryRef::LineString(b)) => cmp_coord_seq(a.0, b.0),
+        (GeometryRef::MultiPoint(a), GeometryRef::MultiPoint(b)) => {
+            cmp_iter(a.iter(), b.iter(), cmp_coord)
+        }
+        (GeometryRef::MultiLineString(a), GeometryRef::MultiLineString(b)) => {
+            cmp_iter(a.iter(), b.iter(), cmp_coord_seq)
+        }
+        (GeometryRef::Polygon(a), GeometryRef::Polygon(b)) => {
+            cmp_iter(a.iter(), b.iter(), cmp_coord_seq)
+        }
+        (GeometryRef::MultiPolygon(a), GeometryRef::MultiPolygon(b)) => {
+            cmp_iter(a.iter(), b.iter(), |a, b| {
+                cmp_iter(a.iter(), b.iter(), cmp_coord_seq)
+            })
+        }
+        (GeometryRef::Collection(a), GeometryRef::Collection(b)) => {
+            cmp_iter(a.iter(), b.iter(), |a, b| cmp_geometry(&a, &b))
+        }
+        (a, b) => a.kind().cmp(&b.kind()),
+    }
+}
+
+fn cmp_coord(a: CoordRef<'_>, b: CoordRef<'_>) -> Ordering {
+    canonical(a.x())
+        .total_cmp(&canonical(b.x()))
+        .then_with(|| canonical(a.y()).total_cmp(&canonical(b.y())))
+}
+
+fn cmp_coord_seq(a: CoordSeqRef<'_>, b: CoordSeqRef<'_>) -> Ordering {
+    cmp_iter(a.iter(), b.iter(), cmp_coord)
+}
+
+/// Lexicographic order, shorter first on a common prefix
+fn cmp_iter<T>(
+    mut a: impl Iterator<Item = T>,
+    mut b: impl Iterator<Item = T>,
+    cmp: impl Fn(T, T) -> Ordering,
+) -> Ordering {
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match cmp(a, b) {
+                Ordering::Equal => {}
+                ordering => return ordering,
+            },
+        }
+    }
+}
+
+fn hash_geometry<H: Hasher>(geo: &GeometryRef<'_>, state: &mut H) {
+    geo.kind().hash(state);
+
+    match geo {
+        GeometryRef::Point(point) => {
+            hash_coord(point.x(), point.y(), state);
+        }
+        GeometryRef::LineString(line) => hash_coord_seq(line.0, state),
+        GeometryRef::MultiPoint(points) => {
+            points.len().hash(state);
+            points.iter().for_each(|c| hash_coord(c.x(), c.y(), state));
+        }
+        GeometryRef::MultiLineString(lines) => {
+            lines.len().hash(state);
+            lines.iter().for_each(|line| hash_coord_seq(line, state));
+        }
+        GeometryRef::Polygon(polygon) => {
+            polygon.len().hash(state);
+            polygon.iter().for_each(|ring| hash_coord_seq(ring, state));
+        }
+        GeometryRef::MultiPolygon(polygons) => {
+            polygons.len().hash(state);
+            for polygon in polygons.iter() {
+                polygon.len().hash(state);
+                polygon.iter().for_each(|ring| hash_coord_seq(ring, state));
+            }
+        }
+        GeometryRef::Collection(collection) => {
+            collection.len().hash(state);
+            collection.iter().for_each(|geo| hash_geometry(&geo, state));
+        }
+    }
+}
+
+fn hash_coord<H: Hasher>(x: f64, y: f64, state: &mut H) {
+    canonical(x).to_bits().hash(state);
+    canonical(y).to_bits().hash(state);
+}
+
+fn hash_coord_seq<H: Hasher>(seq: CoordSeqRef<'_>, state: &mut H) {
+    seq.len().hash(state);
+    seq.iter().for_each(|c| hash_coord(c.x(), c.y(), state));
+}
+
+/// Reverse the bytes of every count and coordinate of an encoded geometry,
+/// starting at the type byte. `layout` is the layout before the swap.
+fn swap(buf: &mut [u8], layout: Layout) -> usize {
+    let ty = GeoType::from_u8(buf[0]).unwrap();
+    let buf = &mut buf[1..];
+
+    let len = match ty {
+        GeoType::Point => swap_coords(&mut buf[..layout.coord_len()], layout),
+        GeoType::LineString | GeoType::MultiPoint => swap_coord_seq(buf, layout),
+        GeoType::Polygon | GeoType::MultiLineString => swap_multi_coord_seq(buf, layout),
+        GeoType::MultiPolygon => {
+            let num = swap_count(buf, layout);
+            let mut size = 4;
+            for _ in 0..num {
+                size += swap_multi_coord_seq(&mut buf[size..], layout);
+            }
+            size
+        }
+        GeoType::Collection => {
+            let num = swap_count(buf, layout);
+            let mut size = 4;
+            for _ in 0..num {
+                size += swap(&mut buf[size..], layout);
+            }
+            size
+        }
+    };
+
+    1 + len
+}
+
+fn swap_count(buf: &mut [u8], layout: Layout) -> usize {
+    let num = read_u32(buf, layout.endian) as usize;
+    buf[..4].reverse();
+    num
+}
+
+/// Reverse every `width` bytes
+fn swap_numbers(buf: &mut [u8], width: usize) -> usize {
+    buf.chunks_exact_mut(width).for_each(|n| n.reverse());
+    buf.len()
+}
+
+fn swap_coords(buf: &mut [u8], layout: Layout) -> usize {
+    swap_numbers(buf, layout.coord_len() / 2)
+}
+
+fn swap_coord_seq(buf: &mut [u8], layout: Layout) -> usize {
+    let num = swap_count(buf, layout);
+    4 + swap_coords(&mut buf[4..4 + num * layout.coord_len()], layout)
+}
+
+fn swap_multi_coord_seq(buf: &mut [u8], layout: Layout) -> usize {
+    let num = swap_count(buf, layout);
+    let mut size = 4;
+    for _ in 0..num {
+        size += swap_coord_seq(&mut buf[size..], layout);
+    }
+    size
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{string::ToString, vec, vec::Vec};
+    use udled::bytes::Endian;
+
+    use crate::Geob;
+
+    #[test]
+    fn canonical() {
+        let geo = Geob::from_text(
+            "SRID=4326;GEOMETRYCOLLECTION(POINT(1.0 2.0), POLYGON((0.0 0.0, 1.0 0.0, 1.0 1.0, 0.0 0.0)))",
+        )
+        .unwrap()
+        .with_envelope();
+
+        let big = geo.to_endian(Endian::Big);
+        assert_eq!(big.endian(), Endian::Big);
+        assert_eq!(big, geo);
+        assert_eq!(big.envelope(), geo.envelope());
+        assert_eq!(big.to_string(), geo.to_string());
+
+        let little = big.to_endian(Endian::Lt);
+        assert_eq!(little.slice(), geo.to_endian(Endian::Lt).slice());
+        assert!(little.is_canonical());
+        assert!(!big.is_canonical());
+        assert_eq!(big.canonicalize().slice(), little.slice());
+
+        let zero = Geob::from_text("SRID=0;POINT(0.0 1.0)").unwrap();
+        let negative = Geob::from_text("SRID=0;POINT(-0.0 1.0)").unwrap();
+        assert_ne!(
+            zero.canonicalize().slice(),
+            negative.to_endian(Endian::Lt).slice()
+        );
+        assert_eq!(zero.canonicalize().slice(), negative.canonicalize().slice());
+    }
+
+    #[test]
+    fn ordering() {
+        let parse = |text: &str| Geob::from_text(text).unwrap();
+
+        let mut geometries = vec![
+            parse("SRID=3857;POINT(0.0 0.0)"),
+            parse("SRID=4326;LINESTRING(0.0 0.0, 1.0 1.0)"),
+            parse("SRID=4326;POINT(2.0 0.0)"),
+            parse("SRID=4326;LINESTRING(0.0 0.0)"),
+            parse("SRID=4326;POINT(1.0 5.0)").to_endian(Endian::Big),
+            parse("SRID=4326;POINT(1.0 5.0)").with_envelope(),
+            parse("SRID=4326;LINESTRING(-1.0 0.0, 1.0 1.0)"),
+            parse("SRID=3857;POINT(-0.0 0.0)"),
+        ];
+
+        geometries.sort();
+        geometries.dedup();
+
+        let text: Vec<_> = geometries.iter().map(|geo| geo.to_string()).collect();
+        assert_eq!(
+            text,
+            [
+                "SRID=3857;POINT(0 0)",
+                "SRID=4326;POINT(1 5)",
+                "SRID=4326;POINT(2 0)",
+                "SRID=4326;LINESTRING(-1 0, 1 1)",
+                "SRID=4326;LINESTRING(0 0)",
+                "SRID=4326;LINESTRING(0 0, 1 1)",
+            ]
+        );
+
+        let nan = Geob::new_point(0.into(), f64::NAN, 0.0).unwrap();
+        assert_eq!(nan, nan.to_endian(Endian::Big));
+        assert!(nan > Geob::new_point(0.into(), f64::INFINITY, 0.0).unwrap());
+    }
+}
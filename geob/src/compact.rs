@@ -0,0 +1,334 @@
+//! Compact storage encodings, with 8 bytes per coordinate instead of 16.
+//!
+//! A compact blob is a geob with [`FLAG_F32`] or [`FLAG_QUANTIZED`] set in the
+//! header. Coordinates are two f32, or two i32 steps of the scale from the
+//! origin. Quantized blobs carry the scale and origin x and y as f64 after the
+//! envelope, which stays f64.
+//!
+//! Compact blobs are read in place, [`CoordRef`](crate::types::CoordRef)
+//! decodes every coordinate on access. Operations that rewrite coordinates,
+//! like projection and the affine transforms, widen to f64 first.
+
+use alloc::vec::Vec;
+use core::fmt;
+use libm::round;
+
+use crate::{
+    GeoType, Geob,
+    projection::update_envelope,
+    types::{ENVELOPE_LEN, FLAG_ENVELOPE, FLAG_LITTLE_ENDIAN, GEOB_HEADER, GRID_LEN, Layout},
+    util::{get_endian, read_u32},
+    writer::ToBytes,
+};
+
+pub use crate::types::{FLAG_F32, FLAG_QUANTIZED};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compact {
+    /// Single precision floats, about 7 significant digits
+    F32,
+    /// Integers on a grid of `scale` anchored at `origin`
+    Quantized { scale: f64, origin: (f64, f64) },
+}
+
+impl Compact {
+    /// Quantized on a grid of `scale` anchored at `(0, 0)`. A scale of `1e-7`
+    /// keeps longitude/latitude to about a centimetre.
+    pub fn quantized(scale: f64) -> Compact {
+        Compact::Quantized {
+            scale,
+            origin: (0.0, 0.0),
+        }
+    }
+
+    fn flag(&self) -> u8 {
+        match self {
+            Compact::F32 => FLAG_F32,
+            Compact::Quantized { .. } => FLAG_QUANTIZED,
+        }
+    }
+
+    /// Returns true if the coordinate can be encoded. Non-finite values fit
+    /// f32 but not the grid.
+    fn fits(&self, x: f64, y: f64) -> bool {
+        match *self {
+            Compact::F32 => [x, y]
+                .into_iter()
+                .all(|n| !n.is_finite() || (n as f32).is_finite()),
+            Compact::Quantized { scale, origin } => {
+                [(x, origin.0), (y, origin.1)]
+                    .into_iter()
+                    .all(|(n, origin)| {
+                        (i32::MIN as f64..=i32::MAX as f64).contains(&round((n - origin) / scale))
+                    })
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompactError {
+    InvalidScale(f64),
+    /// The coordinate does not fit the encoding
+    OutOfRange(f64, f64),
+}
+
+impl fmt::Display for CompactError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompactError::InvalidScale(scale) => write!(f, "scale must be positive: {scale}"),
+            CompactError::OutOfRange(x, y) => {
+                write!(f, "coordinate out of range of the encoding: {x} {y}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for CompactError {}
+
+/// Returns true if the blob starts with the flags of a compact geob
+pub fn is_compact(bytes: &[u8]) -> bool {
+    bytes.first().is_some_and(|flags| {
+        get_endian(*flags).is_some() && flags & (FLAG_F32 | FLAG_QUANTIZED) != 0
+    })
+}
+
+impl Geob {
+    /// Encode with 8 bytes per coordinate. See the [module](self) docs for
+    /// the layout.
+    pub fn to_compact(&self, compact: Compact) -> Result<Geob, CompactError> {
+        if let Compact::Quantized { scale, .. } = compact
+            && !(scale.is_finite() && scale > 0.0)
+        {
+            return Err(CompactError::InvalidScale(scale));
+        }
+
+        self.reencode(Some(compact))
+    }
+
+    /// Copy with f64 coordinates, the geometry itself if it is not compact
+    pub fn to_f64(&self) -> Geob {
+        self.reencode(None).unwrap()
+    }
+
+    /// See [`GeobRef::compact`](crate::GeobRef::compact)
+    pub fn compact(&self) -> Option<Compact> {
+        self.as_ref().compact()
+    }
+
+    fn reencode(&self, compact: Option<Compact>) -> Result<Geob, CompactError> {
+        let geo = self.as_ref();
+        let from = geo.layout();
+        if from.compact == compact {
+            return Ok(self.clone());
+        }
+
+        let to = Layout {
+            endian: from.endian,
+            compact,
+        };
+
+        let bytes = self.slice();
+        let body = geo.body();
+
+        let mut output = Vec::with_capacity(GEOB_HEADER + ENVELOPE_LEN + GRID_LEN + body.len());
+        output.push(
+            bytes[0] & (FLAG_LITTLE_ENDIAN | FLAG_ENVELOPE) | compact.map_or(0, |c| c.flag()),
+        );
+        output.extend_from_slice(&bytes[1..GEOB_HEADER]);
+
+        if self.has_envelope() {
+            output.extend_from_slice(&bytes[GEOB_HEADER..GEOB_HEADER + ENVELOPE_LEN]);
+        }
+
+        if let Some(Compact::Quantized { scale, origin }) = compact {
+            for n in [scale, origin.0, origin.1] {
+                n.write(&mut output, to.endian).unwrap();
+            }
+        }
+
+        walk(&mut &*body, from, &mut output, &mut |x, y, output| {
+            if !compact.is_none_or(|compact| compact.fits(x, y)) {
+                return Err(CompactError::OutOfRange(x, y));
+            }
+
+            let start = output.len();
+            output.resize(start + to.coord_len(), 0);
+            to.write(&mut output[start..], x, y);
+
+            Ok(())
+        })?;
+
+        // Rounded coordinates may fall outside the original envelope
+        if self.has_envelope() && compact.is_some() {
+            update_envelope(&mut output, to);
+        }
+
+        Ok(Geob::new(output))
+    }
+}
+
+/// Copy an encoded geometry, starting at the type byte, passing every
+/// coordinate through `coord`
+fn walk<F>(
+    input: &mut &[u8],
+    layout: Layout,
+    output: &mut Vec<u8>,
+    coord: &mut F,
+) -> Result<(), CompactError>
+where
+    F: FnMut(f64, f64, &mut Vec<u8>) -> Result<(), CompactError>,
+{
+    let ty = take(input, 1)[0];
+    output.push(ty);
+
+    match GeoType::from_u8(ty).unwrap() {
+        GeoType::Point => walk_coord(input, layout, output, coord),
+        GeoType::LineString | GeoType::MultiPoint => walk_coord_seq(input, layout, output, coord),
+        GeoType::Polygon | GeoType::MultiLineString => {
+            for _ in 0..count(input, layout, output) {
+                walk_coord_seq(input, layout, output, coord)?;
+            }
+            Ok(())
+        }
+        GeoType::MultiPolygon => {
+            for _ in 0..count(input, layout, output) {
+                for _ in 0..count(input, layout, output) {
+                    walk_coord_seq(input, layout, output, coord)?;
+                }
+            }
+            Ok(())
+        }
+        GeoType::Collection => {
+            for _ in 0..count(input, layout, output) {
+                walk(input, layout, output, coord)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn walk_coord<F>(
+    input: &mut &[u8],
+    layout: Layout,
+    output: &mut Vec<u8>,
+    coord: &mut F,
+) -> Result<(), CompactError>
+where
+    F: FnMut(f64, f64, &mut Vec<u8>) -> Result<(), CompactError>,
+{
+    let buf = take(input, layout.coord_len());
+    coord(layout.x(buf), layout.y(buf), output)
+}
+
+fn walk_coord_seq<F>(
+    input: &mut &[u8],
+    layout: Layout,
+    output: &mut Vec<u8>,
+    coord: &mut F,
+) -> Result<(), CompactError>
+where
+    F: FnMut(f64, f64, &mut Vec<u8>) -> Result<(), CompactError>,
+{
+    for _ in 0..count(input, layout, output) {
+        walk_coord(input, layout, output, coord)?;
+    }
+    Ok(())
+}
+
+fn count(input: &mut &[u8], layout: Layout, output: &mut Vec<u8>) -> u32 {
+    let bytes = take(input, 4);
+    output.extend_from_slice(bytes);
+    read_u32(bytes, layout.endian)
+}
+
+fn take<'a>(input: &mut &'a [u8], n: usize) -> &'a [u8] {
+    let (head, tail) = input.split_at(n);
+    *input = tail;
+    head
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::ToString;
+    use udled::bytes::Endian;
+
+    use super::{Compact, CompactError, is_compact};
+    use crate::{Geob, GeobRef};
+
+    #[test]
+    fn compact() {
+        let geo = Geob::from_text(
+            "SRID=4326;GEOMETRYCOLLECTION(POINT(10.5 59.25), POLYGON((0.0 0.0, 1.0 0.0, 1.0 1.0, 0.0 0.0)))",
+        )
+        .unwrap()
+        .with_envelope();
+
+        for geo in [geo.clone(), geo.to_endian(Endian::Big)] {
+            let compact = geo.to_compact(Compact::F32).unwrap();
+            assert!(is_compact(compact.slice()));
+            assert_eq!(compact.compact(), Some(Compact::F32));
+            assert_eq!(compact.len(), geo.len() - 5 * 8);
+
+            assert_eq!(compact, geo);
+            assert_eq!(compact.envelope(), geo.envelope());
+            assert_eq!(compact.to_string(), geo.to_string());
+            assert_eq!(Geob::from_bytes(compact.slice()).unwrap(), compact);
+            assert_eq!(compact.to_f64().slice(), geo.slice());
+            assert_eq!(
+                compact.without_envelope().with_envelope().slice(),
+                compact.slice()
+            );
+
+            let swapped = compact.to_endian(Endian::Lt).to_endian(Endian::Big);
+            assert_eq!(swapped.to_endian(geo.endian()).slice(), compact.slice());
+            assert_eq!(compact.canonicalize().compact(), Some(Compact::F32));
+
+            let mut moved = compact.clone();
+            moved.map_coords(|x, y| (x + 0.1, y));
+            assert_eq!(moved.compact(), None);
+        }
+
+        let precise =
+            Geob::from_text("SRID=4326;LINESTRING(10.123456789 59.987654321, 0.0 0.0)").unwrap();
+
+        let quantized = precise.to_compact(Compact::quantized(1e-7)).unwrap();
+        let (a, b) = (
+            quantized.geometry().centroid(),
+            precise.geometry().centroid(),
+        );
+        let (a, b) = (a.unwrap(), b.unwrap());
+        assert!((a.0 - b.0).abs() < 1e-7 && (a.1 - b.1).abs() < 1e-7);
+        assert_ne!(quantized, precise);
+
+        let cached = quantized.with_envelope();
+        assert_eq!(Geob::from_bytes(cached.slice()).unwrap(), quantized);
+        assert_eq!(cached.envelope(), quantized.envelope());
+        assert_eq!(cached.without_envelope().slice(), quantized.slice());
+        assert_eq!(
+            quantized.to_compact(Compact::F32).unwrap().compact(),
+            Some(Compact::F32)
+        );
+
+        assert_eq!(
+            precise.to_compact(Compact::quantized(1e-9)),
+            Err(CompactError::OutOfRange(10.123456789, 59.987654321))
+        );
+        let far = Geob::new_point(0.into(), 1e300, 0.0).unwrap();
+        assert_eq!(
+            far.to_compact(Compact::F32),
+            Err(CompactError::OutOfRange(1e300, 0.0))
+        );
+        let infinite = Geob::new_point(0.into(), f64::INFINITY, 0.0).unwrap();
+        assert_eq!(infinite.to_compact(Compact::F32).unwrap(), infinite);
+
+        assert_eq!(
+            precise.to_compact(Compact::quantized(0.0)),
+            Err(CompactError::InvalidScale(0.0))
+        );
+
+        let bytes = quantized.slice();
+        assert!(GeobRef::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(!is_compact(precise.slice()));
+    }
+}
@@ -0,0 +1,136 @@
+//! Space filling curve keys. Sorting geometries by the key of their envelope
+//! center keeps nearby geometries close together.
+
+use crate::{Envelope, Geob, GeobRef};
+
+/// Bits per axis. Keys use `2 * ORDER` bits and fit a non negative `i64`.
+pub const ORDER: u32 = 31;
+
+const SIDE: u32 = 1 << ORDER;
+
+/// Distance along the Hilbert curve of the cell `(x, y)`, using the lower
+/// [`ORDER`] bits of each axis
+pub fn hilbert(x: u32, y: u32) -> u64 {
+    let (mut x, mut y) = (x & (SIDE - 1), y & (SIDE - 1));
+    let mut d = 0;
+
+    let mut s = SIDE / 2;
+    while s > 0 {
+        let rx = (x & s != 0) as u64;
+        let ry = (y & s != 0) as u64;
+        d += (s as u64) * (s as u64) * ((3 * rx) ^ ry);
+
+        // Rotate the quadrant so the curve enters at its origin
+        if ry == 0 {
+            if rx == 1 {
+                x = SIDE - 1 - x;
+                y = SIDE - 1 - y;
+            }
+            core::mem::swap(&mut x, &mut y);
+        }
+
+        s /= 2;
+    }
+
+    d
+}
+
+/// Morton (Z-order) key of the cell `(x, y)`, interleaving the lower
+/// [`ORDER`] bits of each axis with x in the even bits
+pub fn morton(x: u32, y: u32) -> u64 {
+    spread(x & (SIDE - 1)) | (spread(y & (SIDE - 1)) << 1)
+}
+
+fn spread(n: u32) -> u64 {
+    let mut n = n as u64;
+    n = (n | (n << 16)) & 0x0000_ffff_0000_ffff;
+    n = (n | (n << 8)) & 0x00ff_00ff_00ff_00ff;
+    n = (n | (n << 4)) & 0x0f0f_0f0f_0f0f_0f0f;
+    n = (n | (n << 2)) & 0x3333_3333_3333_3333;
+    (n | (n << 1)) & 0x5555_5555_5555_5555
+}
+
+/// Grid cell of `(x, y)` after dividing `bounds` into `2^ORDER` cells per
+/// axis. Coordinates outside the bounds fall in the nearest edge cell.
+pub fn cell(x: f64, y: f64, bounds: &Envelope) -> (u32, u32) {
+    let scale = |n: f64, min: f64, max: f64| {
+        if max > min {
+            ((n - min) / (max - min) * SIDE as f64).clamp(0.0, (SIDE - 1) as f64) as u32
+        } else {
+            0
+        }
+    };
+
+    (
+        scale(x, bounds.min_x, bounds.max_x),
+        scale(y, bounds.min_y, bounds.max_y),
+    )
+}
+
+impl<'a> GeobRef<'a> {
+    /// Hilbert key of the envelope center within `bounds`, `None` if the
+    /// geometry is empty
+    pub fn hilbert_key(&self, bounds: &Envelope) -> Option<u64> {
+        let (x, y) = self.envelope()?.center();
+        let (x, y) = cell(x, y, bounds);
+        Some(hilbert(x, y))
+    }
+
+    /// Morton key of the envelope center within `bounds`, `None` if the
+    /// geometry is empty
+    pub fn morton_key(&self, bounds: &Envelope) -> Option<u64> {
+        let (x, y) = self.envelope()?.center();
+        let (x, y) = cell(x, y, bounds);
+        Some(morton(x, y))
+    }
+}
+
+impl Geob {
+    /// See [`GeobRef::hilbert_key`]
+    pub fn hilbert_key(&self, bounds: &Envelope) -> Option<u64> {
+        self.as_ref().hilbert_key(bounds)
+    }
+
+    /// See [`GeobRef::morton_key`]
+    pub fn morton_key(&self, bounds: &Envelope) -> Option<u64> {
+        self.as_ref().morton_key(bounds)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use super::{SIDE, cell, hilbert, morton};
+    use crate::{Envelope, Geob};
+
+    #[test]
+    fn curves() {
+        assert_eq!(morton(3, 5), 0b100111);
+        assert_eq!(morton(SIDE - 1, SIDE - 1), (1 << 62) - 1);
+        assert_eq!(hilbert(0, 0), 0);
+        assert!(hilbert(SIDE - 1, 0) < 1 << 62);
+
+        // The first 4^k cells of the curve fill the 2^k block at the origin,
+        // each one next to the previous
+        let mut cells: Vec<_> = (0..8)
+            .flat_map(|x| (0..8).map(move |y| (hilbert(x, y), (x, y))))
+            .collect();
+        cells.sort();
+
+        for (idx, pair) in cells.windows(2).enumerate() {
+            let ((d, (x1, y1)), (_, (x2, y2))) = (pair[0], pair[1]);
+            assert_eq!(d, idx as u64);
+            assert_eq!(x1.abs_diff(x2) + y1.abs_diff(y2), 1);
+        }
+
+        let bounds = Envelope::new(0.0, 0.0, 10.0, 10.0);
+        assert_eq!(cell(-1.0, 20.0, &bounds), (0, SIDE - 1));
+        assert_eq!(cell(5.0, 5.0, &bounds), (SIDE / 2, SIDE / 2));
+
+        let geo = Geob::from_text("SRID=0;LINESTRING(0.0 0.0, 10.0 10.0)").unwrap();
+        assert_eq!(geo.morton_key(&bounds), Some(morton(SIDE / 2, SIDE / 2)));
+        let empty = Geob::from_text("SRID=0;GEOMETRYCOLLECTION()").unwrap();
+        assert_eq!(empty.hilbert_key(&bounds), None);
+    }
+}
@@ -0,0 +1,252 @@
+use udled::bytes::Endian;
+
+use crate::{
+    GeoType, Geob, SRID,
+    types::Layout,
+    util::{read_f64, read_u32},
+    writer::{BinaryWriter, ToBytes},
+};
+
+/// Axis aligned bounding box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Envelope {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+impl Envelope {
+    pub fn new(min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Envelope {
+        Envelope {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        }
+    }
+
+    pub fn from_coord(x: f64, y: f64) -> Envelope {
+        Envelope::new(x, y, x, y)
+    }
+
+    pub fn width(&self) -> f64 {
+        self.max_x - self.min_x
+    }
+
+    pub fn height(&self) -> f64 {
+        self.max_y - self.min_y
+    }
+
+    pub fn center(&self) -> (f64, f64) {
+        (
+            (self.min_x + self.max_x) / 2.0,
+            (self.min_y + self.max_y) / 2.0,
+        )
+    }
+
+    pub fn expand(&mut self, x: f64, y: f64) {
+        self.min_x = self.min_x.min(x);
+        self.min_y = self.min_y.min(y);
+        self.max_x = self.max_x.max(x);
+        self.max_y = self.max_y.max(y);
+    }
+
+    pub fn merge(&self, other: &Envelope) -> Envelope {
+        Envelope::new(
+            self.min_x.min(other.min_x),
+            self.min_y.min(other.min_y),
+            self.max_x.max(other.max_x),
+            self.max_y.max(other.max_y),
+        )
+    }
+
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.min_x && x <= self.max_x && y >= self.min_y && y <= self.max_y
+    }
+
+    pub fn intersects(&self, other: &Envelope) -> bool {
+        self.min_x <= other.max_x
+            && self.max_x >= other.min_x
+            && self.min_y <= other.max_y
+            && self.max_y >= other.min_y
+    }
+
+    /// Polygon covering the envelope, counter clockwise from the lower left
+    /// corner
+    pub fn to_polygon(&self, srid: SRID) -> Geob {
+        let mut builder = Geob::builder(srid);
+
+        builder.begin_polygon().unwrap();
+        builder.begin_ring().unwrap();
+        for (x, y) in [
+            (self.min_x, self.min_y),
+            (self.max_x, self.min_y),
+            (self.max_x, self.max_y),
+            (self.min_x, self.max_y),
+            (self.min_x, self.min_y),
+        ] {
+            builder.coord(x, y).unwrap();
+        }
+        builder.end().unwrap();
+        builder.end().unwrap();
+
+        builder.build().unwrap()
+    }
+
+    /// Read an envelope stored in a geob header. Empty geometries store NaN.
+    pub(crate) fn read(buf: &[u8], endian: Endian) -> Option<Envelope> {
+        let envelope = Envelope::new(
+            read_f64(buf, endian),
+            read_f64(&buf[8..], endian),
+            read_f64(&buf[16..], endian),
+            read_f64(&buf[24..], endian),
+        );
+
+        if envelope.min_x.is_nan() {
+            None
+        } else {
+            Some(envelope)
+        }
+    }
+
+    /// Compute the envelope of an encoded geometry, starting at the type byte
+    pub(crate) fn compute(buf: &[u8], layout: Layout) -> Option<Envelope> {
+        let mut envelope: Option<Envelope> = None;
+
+        visit_coords(buf, layout, &mut |x, y| match &mut envelope {
+            Some(envelope) => envelope.expand(x, y),
+            None => envelope = Some(Envelope::from_coord(x, y)),
+        });
+
+        envelope
+    }
+}
+
+impl ToBytes for Envelope {
+    fn write<W: BinaryWriter>(&self, output: &mut W, endian: Endian) -> Result<(), W::Error> {
+        self.min_x.write(output, endian)?;
+        self.min_y.write(output, endian)?;
+        self.max_x.write(output, endian)?;
+        self.max_y.write(output, endian)
+    }
+}
+
+impl ToBytes for Option<Envelope> {
+    fn write<W: BinaryWriter>(&self, output: &mut W, endian: Endian) -> Result<(), W::Error> {
+        match self {
+            Some(envelope) => envelope.write(output, endian),
+            None => Envelope::new(f64::NAN, f64::NAN, f64::NAN, f64::NAN).write(output, endian),
+        }
+    }
+}
+
+/// Call `func` for every coordinate of the encoded geometry starting at the
+/// type byte. Returns the number of bytes consumed.
+pub(crate) fn visit_coords<F>(buf: &[u8], layout: Layout, func: &mut F) -> usize
+where
+    F: FnMut(f64, f64),
+{
+    let ty = GeoType::from_u8(buf[0]).unwrap();
+    let buf = &buf[1..];
+
+    let len = match ty {
+        GeoType::Point => visit_coord(buf, layout, func),
+        GeoType::LineString | GeoType::MultiPoint => visit_coord_seq(buf, layout, func),
+        GeoType::Polygon | GeoType::MultiLineString => visit_multi_coord_seq(buf, layout, func),
+        GeoType::MultiPolygon => {
+            let num = read_u32(buf, layout.endian) as usize;
+            let mut size = 4;
+            for _ in 0..num {
+                size += visit_multi_coord_seq(&buf[size..], layout, func);
+            }
+            size
+        }
+        GeoType::Collection => {
+            let num = read_u32(buf, layout.endian) as usize;
+            let mut size = 4;
+            for _ in 0..num {
+                size += visit_coords(&buf[size..], layout, func);
+            }
+            size
+        }
+    };
+
+    1 + len
+}
+
+fn visit_coord<F: FnMut(f64, f64)>(buf: &[u8], layout: Layout, func: &mut F) -> usize {
+    func(layout.x(buf), layout.y(buf));
+    layout.coord_len()
+}
+
+fn visit_coord_seq<F: FnMut(f64, f64)>(buf: &[u8], layout: Layout, func: &mut F) -> usize {
+    let num = read_u32(buf, layout.endian) as usize;
+
+    for i in 0..num {
+        visit_coord(&buf[4 + i * layout.coord_len()..], layout, func);
+    }
+
+    4 + num * layout.coord_len()
+}
+
+fn visit_multi_coord_seq<F: FnMut(f64, f64)>(buf: &[u8], layout: Layout, func: &mut F) -> usize {
+    let num = read_u32(buf, layout.endian) as usize;
+    let mut size = 4;
+
+    for _ in 0..num {
+        size += visit_coord_seq(&buf[size..], layout, func);
+    }
+
+    size
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{string::ToString, vec::Vec};
+    use udled::bytes::Endian;
+
+    use super::Envelope;
+    use crate::{Geob, SRID, builder::GeometryWriter};
+
+    #[test]
+    fn cached_envelope() {
+        let geo = Geob::from_text("SRID=4326;LINESTRING(10.0 20.0, -5.0 40.0, 3.0 -1.0)").unwrap();
+        let expected = Envelope::new(-5.0, -1.0, 10.0, 40.0);
+
+        assert!(!geo.has_envelope());
+        assert_eq!(geo.envelope(), Some(expected));
+
+        let cached = geo.with_envelope();
+        assert!(cached.has_envelope());
+        assert_eq!(cached.len(), geo.len() + 32);
+        assert_eq!(cached.envelope(), Some(expected));
+        assert_eq!(cached, geo);
+        assert_eq!(cached.to_string(), geo.to_string());
+        assert_eq!(Geob::from_bytes(cached.slice().to_vec()).unwrap(), geo);
+        assert_eq!(cached.without_envelope().slice(), geo.slice());
+
+        let projected = cached.project_into(SRID::WEB_MERCATOR.into());
+        assert!(projected.has_envelope());
+        assert_eq!(
+            projected.envelope(),
+            projected.without_envelope().envelope()
+        );
+    }
+
+    #[test]
+    fn builder_envelope() {
+        let mut builder =
+            GeometryWriter::with_envelope(Vec::new(), SRID::WGS84, Endian::Big).unwrap();
+        builder.begin_multi_point().unwrap();
+        builder.point(1.0, 2.0).unwrap();
+        builder.point(-3.0, 4.0).unwrap();
+        builder.end().unwrap();
+
+        let geo = builder.build().unwrap();
+
+        assert!(geo.has_envelope());
+        assert_eq!(geo.endian(), Endian::Big);
+        assert_eq!(geo.envelope(), Some(Envelope::new(-3.0, 2.0, 1.0, 4.0)));
+    }
+}
@@ -0,0 +1,66 @@
+use core::fmt;
+
+use crate::builder::MAX_DEPTH;
+
+/// Error decoding a geob. Offsets are in bytes from the start of the blob.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The header flags have unknown bits set
+    InvalidEndian(u8),
+    UnknownType {
+        tag: u8,
+        offset: usize,
+    },
+    /// `needed` bytes were expected at `offset`, but the input ended
+    Truncated {
+        needed: usize,
+        offset: usize,
+    },
+    TrailingBytes {
+        offset: usize,
+    },
+    /// A polygon with more rings than the remaining input can hold
+    InvalidRingCount {
+        count: u32,
+        offset: usize,
+    },
+    /// A multi geometry or collection with more members than the remaining
+    /// input can hold
+    InvalidCount {
+        count: u32,
+        offset: usize,
+    },
+    /// Collections nested deeper than [`MAX_DEPTH`]
+    TooDeep {
+        offset: usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidEndian(flags) => write!(f, "invalid header flags: {flags:#b}"),
+            Error::UnknownType { tag, offset } => {
+                write!(f, "unknown geometry type {tag} at offset {offset}")
+            }
+            Error::Truncated { needed, offset } => {
+                write!(f, "expected {needed} bytes at offset {offset}")
+            }
+            Error::TrailingBytes { offset } => write!(f, "trailing bytes at offset {offset}"),
+            Error::InvalidRingCount { count, offset } => {
+                write!(f, "invalid ring count {count} at offset {offset}")
+            }
+            Error::InvalidCount { count, offset } => {
+                write!(f, "invalid count {count} at offset {offset}")
+            }
+            Error::TooDeep { offset } => {
+                write!(
+                    f,
+                    "geometry nested deeper than {MAX_DEPTH} at offset {offset}"
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for Error {}
@@ -1,13 +1,18 @@
 use crate::{
-    GeoType, SRID,
-    types::{GeobParser, GeobRef, GeometryRef, LineStringRef, PointRef, PolygonRef},
+    Error, GeoType, SRID,
+    envelope::Envelope,
+    types::{
+        ENVELOPE_LEN, FLAG_ENVELOPE, GEOB_HEADER, GeobRef, GeometryRef, LineStringRef, PointRef,
+        PolygonRef,
+    },
     util::{get_endian, read_u32, write_u32},
+    validate::validate,
     wkt,
     writer::{BinaryWriter, ToBytes},
 };
 use alloc::{sync::Arc, vec::Vec};
 use core::fmt;
-use udled::{Input, bytes::Endian};
+use udled::bytes::Endian;
 
 #[derive(Clone)]
 pub struct Geob(Arc<[u8]>);
@@ -24,12 +29,6 @@ impl fmt::Display for Geob {
     }
 }
 
-impl PartialEq for Geob {
-    fn eq(&self, other: &Self) -> bool {
-        self.srid() == other.srid() && self.geometry() == other.geometry()
-    }
-}
-
 impl Geob {
     pub fn new_point(srid: SRID, x: f64, y: f64) -> Result<Geob, <Vec<u8> as BinaryWriter>::Error> {
         let endian = Endian::native();
@@ -57,9 +56,9 @@ impl Geob {
         wkt::parse(input, Endian::native())
     }
 
-    pub fn from_bytes<T: Into<Vec<u8>> + AsRef<[u8]>>(bytes: T) -> Result<Geob, udled::Error> {
-        let mut input = Input::new(bytes.as_ref());
-        input.eat(GeobParser)?;
+    /// Copy `bytes` after checking that they hold exactly one geometry
+    pub fn from_bytes<T: Into<Vec<u8>> + AsRef<[u8]>>(bytes: T) -> Result<Geob, Error> {
+        validate(bytes.as_ref())?;
 
         let bytes: Vec<u8> = bytes.into();
 
@@ -70,6 +69,10 @@ impl Geob {
         GeobRef::new(&self.0)
     }
 
+    /// # Safety
+    ///
+    /// `bytes` must hold a valid geob encoded geometry. Accessors index into the
+    /// buffer without bounds checks beyond what the slice itself provides.
     pub unsafe fn from_bytes_unchecked<T: Into<Vec<u8>> + AsRef<[u8]>>(bytes: T) -> Geob {
         let bytes: Vec<u8> = bytes.into();
         Self(Arc::from(bytes))
@@ -85,17 +88,62 @@ impl Geob {
 
     pub fn set_srid(&mut self, srid: SRID) {
         let endian = self.endian();
-        write_u32(Arc::make_mut(&mut self.0), srid.into(), endian);
+        write_u32(&mut Arc::make_mut(&mut self.0)[1..], srid.into(), endian);
     }
 
     pub fn kind(&self) -> GeoType {
-        GeoType::from_u8(self.0[5]).unwrap()
+        self.as_ref().kind()
+    }
+
+    /// Returns true if the header carries a cached bounding box
+    pub fn has_envelope(&self) -> bool {
+        self.as_ref().has_envelope()
+    }
+
+    /// Bounding box of the geometry, see [`GeobRef::envelope`]
+    pub fn envelope(&self) -> Option<Envelope> {
+        self.as_ref().envelope()
+    }
+
+    /// Copy of the geometry with a bounding box cached in the header
+    pub fn with_envelope(&self) -> Geob {
+        if self.has_envelope() {
+            return self.clone();
+        }
+
+        let endian = self.endian();
+
+        let mut output = Vec::with_capacity(self.len() + ENVELOPE_LEN);
+        output.push(self.0[0] | FLAG_ENVELOPE);
+        output.extend_from_slice(&self.0[1..GEOB_HEADER]);
+        self.envelope().write(&mut output, endian).unwrap();
+        output.extend_from_slice(&self.0[GEOB_HEADER..]);
+
+        Geob::new(output)
+    }
+
+    /// Copy of the geometry without a cached bounding box
+    pub fn without_envelope(&self) -> Geob {
+        if !self.has_envelope() {
+            return self.clone();
+        }
+
+        let mut output = Vec::with_capacity(self.len() - ENVELOPE_LEN);
+        output.push(self.0[0] & !FLAG_ENVELOPE);
+        output.extend_from_slice(&self.0[1..GEOB_HEADER]);
+        output.extend_from_slice(&self.0[GEOB_HEADER + ENVELOPE_LEN..]);
+
+        Geob::new(output)
     }
 
     pub fn len(&self) -> usize {
         self.0.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     pub fn geometry(&self) -> GeometryRef<'_> {
         GeobRef::new(&self.0).geometry()
     }
@@ -123,11 +171,11 @@ impl Geob {
 }
 
 impl Geob {
-    #[allow(unused)]
     pub(crate) fn slice_mut(&mut self) -> &mut [u8] {
         Arc::make_mut(&mut self.0)
     }
 
+    #[allow(unused)]
     pub(crate) fn slice(&self) -> &[u8] {
         &self.0
     }
@@ -0,0 +1,209 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+use crate::{Envelope, Geob, SRID, types::GeometryRef};
+
+const BASE32: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+pub const MAX_PRECISION: usize = 12;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeohashError {
+    InvalidPrecision(usize),
+    InvalidCharacter(char),
+    InvalidCoord(f64, f64),
+    InvalidSrid(SRID),
+    NotAPoint,
+}
+
+impl fmt::Display for GeohashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeohashError::InvalidPrecision(n) => {
+                write!(f, "precision must be between 1 and {MAX_PRECISION}: {n}")
+            }
+            GeohashError::InvalidCharacter(c) => write!(f, "invalid geohash character: {c}"),
+            GeohashError::InvalidCoord(x, y) => write!(f, "coordinate out of range: {x} {y}"),
+            GeohashError::InvalidSrid(srid) => write!(f, "expected SRID 4326, found {srid}"),
+            GeohashError::NotAPoint => write!(f, "geohash requires a point"),
+        }
+    }
+}
+
+impl core::error::Error for GeohashError {}
+
+/// Encode a longitude/latitude coordinate
+pub fn encode(lon: f64, lat: f64, precision: usize) -> Result<String, GeohashError> {
+    if !(1..=MAX_PRECISION).contains(&precision) {
+        return Err(GeohashError::InvalidPrecision(precision));
+    }
+
+    if !(-180.0..=180.0).contains(&lon) || !(-90.0..=90.0).contains(&lat) {
+        return Err(GeohashError::InvalidCoord(lon, lat));
+    }
+
+    let (mut lon_range, mut lat_range) = ((-180.0, 180.0), (-90.0, 90.0));
+    let mut output = String::with_capacity(precision);
+    let mut even = true;
+
+    for _ in 0..precision {
+        let mut idx = 0;
+
+        for _ in 0..5 {
+            let (range, value) = if even {
+                (&mut lon_range, lon)
+            } else {
+                (&mut lat_range, lat)
+            };
+
+            let mid = (range.0 + range.1) / 2.0;
+            idx <<= 1;
+            if value >= mid {
+                idx |= 1;
+                range.0 = mid;
+            } else {
+                range.1 = mid;
+            }
+
+            even = !even;
+        }
+
+        output.push(BASE32[idx] as char);
+    }
+
+    Ok(output)
+}
+
+/// Bounds of the cell identified by the geohash
+pub fn decode(hash: &str) -> Result<Envelope, GeohashError> {
+    if !(1..=MAX_PRECISION).contains(&hash.len()) {
+        return Err(GeohashError::InvalidPrecision(hash.len()));
+    }
+
+    let (mut lon_range, mut lat_range) = ((-180.0, 180.0), (-90.0, 90.0));
+    let mut even = true;
+
+    for c in hash.chars() {
+        let idx = BASE32
+            .iter()
+            .position(|b| *b as char == c.to_ascii_lowercase())
+            .ok_or(GeohashError::InvalidCharacter(c))?;
+
+        for bit in (0..5).rev() {
+            let range = if even { &mut lon_range } else { &mut lat_range };
+
+            let mid = (range.0 + range.1) / 2.0;
+            if (idx >> bit) & 1 == 1 {
+                range.0 = mid;
+            } else {
+                range.1 = mid;
+            }
+
+            even = !even;
+        }
+    }
+
+    Ok(Envelope::new(
+        lon_range.0,
+        lat_range.0,
+        lon_range.1,
+        lat_range.1,
+    ))
+}
+
+/// Width and height in degrees of a cell at the precision
+pub fn cell_size(precision: usize) -> (f64, f64) {
+    let bits = 5 * precision as i32;
+    let lon_bits = (bits + 1) / 2;
+    let lat_bits = bits / 2;
+
+    (
+        360.0 / (1u64 << lon_bits) as f64,
+        180.0 / (1u64 << lat_bits) as f64,
+    )
+}
+
+/// Geohashes of the cells at the precision intersecting the envelope
+pub fn cover(envelope: &Envelope, precision: usize) -> Result<Vec<String>, GeohashError> {
+    if !(1..=MAX_PRECISION).contains(&precision) {
+        return Err(GeohashError::InvalidPrecision(precision));
+    }
+
+    let (width, height) = cell_size(precision);
+    let cols = (360.0 / width) as i64;
+    let rows = (180.0 / height) as i64;
+
+    let col = |lon: f64| (((lon.clamp(-180.0, 180.0) + 180.0) / width) as i64).min(cols - 1);
+    let row = |lat: f64| (((lat.clamp(-90.0, 90.0) + 90.0) / height) as i64).min(rows - 1);
+
+    let mut output = Vec::new();
+
+    for j in row(envelope.min_y)..=row(envelope.max_y) {
+        for i in col(envelope.min_x)..=col(envelope.max_x) {
+            output.push(encode(
+                (i as f64 + 0.5) * width - 180.0,
+                (j as f64 + 0.5) * height - 90.0,
+                precision,
+            )?);
+        }
+    }
+
+    Ok(output)
+}
+
+impl Geob {
+    /// Geohash of a [`SRID::WGS84`] point
+    pub fn geohash(&self, precision: usize) -> Result<String, GeohashError> {
+        if self.srid() != SRID::WGS84 {
+            return Err(GeohashError::InvalidSrid(self.srid()));
+        }
+
+        match self.geometry() {
+            GeometryRef::Point(point) => encode(point.x(), point.y(), precision),
+            _ => Err(GeohashError::NotAPoint),
+        }
+    }
+
+    /// [`SRID::WGS84`] point at the center of the geohash cell
+    pub fn from_geohash(hash: &str) -> Result<Geob, GeohashError> {
+        let (x, y) = decode(hash)?.center();
+        Ok(Geob::new_point(SRID::WGS84, x, y).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{GeohashError, cover, decode, encode};
+    use crate::{Envelope, Geob, SRID};
+
+    #[test]
+    fn geohash() {
+        assert_eq!(encode(-5.6, 42.6, 5).unwrap(), "ezs42");
+        assert_eq!(encode(10.40744, 57.64911, 11).unwrap(), "u4pruydqqvj");
+
+        let cell = decode("ezs42").unwrap();
+        assert!(cell.contains(-5.6, 42.6));
+        assert!(cell.width() < 0.05 && cell.height() < 0.05);
+
+        assert_eq!(decode("ezs4a"), Err(GeohashError::InvalidCharacter('a')));
+        assert_eq!(
+            encode(0.0, 0.0, 13),
+            Err(GeohashError::InvalidPrecision(13))
+        );
+
+        let point = Geob::new_point(SRID::WGS84, 10.40744, 57.64911).unwrap();
+        assert_eq!(point.geohash(6).unwrap(), "u4pruy");
+        assert_eq!(
+            Geob::new_point(SRID::WEB_MERCATOR, 0.0, 0.0)
+                .unwrap()
+                .geohash(6),
+            Err(GeohashError::InvalidSrid(SRID::WEB_MERCATOR))
+        );
+
+        let center = Geob::from_geohash("u4pruy").unwrap();
+        assert_eq!(center.geohash(6).unwrap(), "u4pruy");
+
+        let cells = cover(&Envelope::new(-1.0, -1.0, 1.0, 1.0), 1).unwrap();
+        assert_eq!(cells, ["7", "k", "e", "s"]);
+    }
+}
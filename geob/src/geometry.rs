@@ -0,0 +1,232 @@
+//! Owned geometries. [`Geometry`] is the allocating counterpart of
+//! [`GeometryRef`], for geometries that are edited before being encoded again.
+
+use alloc::vec::Vec;
+use core::convert::Infallible;
+
+use crate::{
+    GeoType, Geob, GeobRef, SRID,
+    builder::{BuilderError, GeometryWriter},
+    types::{CoordRef, CoordSeqRef, GeometryRef, PolygonRef},
+    writer::BinaryWriter,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Coord {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Coord {
+    pub const fn new(x: f64, y: f64) -> Coord {
+        Coord { x, y }
+    }
+}
+
+impl From<(f64, f64)> for Coord {
+    fn from((x, y): (f64, f64)) -> Self {
+        Coord { x, y }
+    }
+}
+
+impl From<CoordRef<'_>> for Coord {
+    fn from(coord: CoordRef<'_>) -> Self {
+        Coord::new(coord.x(), coord.y())
+    }
+}
+
+/// An owned geometry. Polygons are lists of rings, the first ring being the
+/// exterior.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    Point(Coord),
+    LineString(Vec<Coord>),
+    Polygon(Vec<Vec<Coord>>),
+    MultiPoint(Vec<Coord>),
+    MultiLineString(Vec<Vec<Coord>>),
+    MultiPolygon(Vec<Vec<Vec<Coord>>>),
+    Collection(Vec<Geometry>),
+}
+
+impl Geometry {
+    pub fn kind(&self) -> GeoType {
+        match self {
+            Geometry::Point(_) => GeoType::Point,
+            Geometry::LineString(_) => GeoType::LineString,
+            Geometry::Polygon(_) => GeoType::Polygon,
+            Geometry::MultiPoint(_) => GeoType::MultiPoint,
+            Geometry::MultiLineString(_) => GeoType::MultiLineString,
+            Geometry::MultiPolygon(_) => GeoType::MultiPolygon,
+            Geometry::Collection(_) => GeoType::Collection,
+        }
+    }
+
+    /// Write the geometry, either as the root geometry or as a child of the
+    /// open geometry
+    pub fn write<W: BinaryWriter>(
+        &self,
+        writer: &mut GeometryWriter<W>,
+    ) -> Result<(), BuilderError<W::Error>> {
+        match self {
+            Geometry::Point(coord) => writer.point(coord.x, coord.y),
+            Geometry::LineString(coords) => {
+                writer.begin_line_string()?;
+                write_coords(writer, coords)?;
+                writer.end()
+            }
+            Geometry::Polygon(rings) => write_polygon(writer, rings),
+            Geometry::MultiPoint(coords) => {
+                writer.begin_multi_point()?;
+                write_coords(writer, coords)?;
+                writer.end()
+            }
+            Geometry::MultiLineString(lines) => {
+                writer.begin_multi_line_string()?;
+                for line in lines {
+                    writer.begin_line_string()?;
+                    write_coords(writer, line)?;
+                    writer.end()?;
+                }
+                writer.end()
+            }
+            Geometry::MultiPolygon(polygons) => {
+                writer.begin_multi_polygon()?;
+                for rings in polygons {
+                    write_polygon(writer, rings)?;
+                }
+                writer.end()
+            }
+            Geometry::Collection(geometries) => {
+                writer.begin_collection()?;
+                for geo in geometries {
+                    geo.write(writer)?;
+                }
+                writer.end()
+            }
+        }
+    }
+
+    /// Encode as a geob. Fails if collections are nested deeper than
+    /// [`MAX_DEPTH`](crate::builder::MAX_DEPTH).
+    pub fn to_geob(&self, srid: SRID) -> Result<Geob, BuilderError<Infallible>> {
+        let mut builder = Geob::builder(srid);
+        self.write(&mut builder)?;
+        builder.build()
+    }
+}
+
+fn write_coords<W: BinaryWriter>(
+    writer: &mut GeometryWriter<W>,
+    coords: &[Coord],
+) -> Result<(), BuilderError<W::Error>> {
+    for coord in coords {
+        writer.coord(coord.x, coord.y)?;
+    }
+    Ok(())
+}
+
+fn write_polygon<W: BinaryWriter>(
+    writer: &mut GeometryWriter<W>,
+    rings: &[Vec<Coord>],
+) -> Result<(), BuilderError<W::Error>> {
+    writer.begin_polygon()?;
+    for ring in rings {
+        writer.begin_ring()?;
+        write_coords(writer, ring)?;
+        writer.end()?;
+    }
+    writer.end()
+}
+
+fn coords(seq: CoordSeqRef<'_>) -> Vec<Coord> {
+    seq.iter().map(Coord::from).collect()
+}
+
+fn polygon(polygon: PolygonRef<'_>) -> Vec<Vec<Coord>> {
+    polygon.iter().map(coords).collect()
+}
+
+impl From<GeometryRef<'_>> for Geometry {
+    fn from(geo: GeometryRef<'_>) -> Self {
+        match geo {
+            GeometryRef::Point(point) => Geometry::Point(point.coord().into()),
+            GeometryRef::LineString(line) => {
+                Geometry::LineString(line.iter().map(Coord::from).collect())
+            }
+            GeometryRef::Polygon(rings) => Geometry::Polygon(polygon(rings)),
+            GeometryRef::MultiPoint(points) => {
+                Geometry::MultiPoint(points.iter().map(Coord::from).collect())
+            }
+            GeometryRef::MultiLineString(lines) => {
+                Geometry::MultiLineString(lines.iter().map(coords).collect())
+            }
+            GeometryRef::MultiPolygon(polygons) => {
+                Geometry::MultiPolygon(polygons.iter().map(polygon).collect())
+            }
+            GeometryRef::Collection(collection) => {
+                Geometry::Collection(collection.iter().map(Geometry::from).collect())
+            }
+        }
+    }
+}
+
+impl<'a> GeobRef<'a> {
+    /// Decode into an owned [`Geometry`]
+    pub fn to_geometry(&self) -> Geometry {
+        self.geometry().into()
+    }
+}
+
+impl Geob {
+    /// Decode into an owned [`Geometry`]
+    pub fn to_geometry(&self) -> Geometry {
+        self.geometry().into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{string::ToString, vec, vec::Vec};
+
+    use super::{Coord, Geometry};
+    use crate::{Geob, SRID, builder::BuilderError};
+
+    #[test]
+    fn round_trip() {
+        let ring = |offset: f64| {
+            [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)]
+                .map(|(x, y)| Coord::new(x + offset, y))
+                .to_vec()
+        };
+
+        let mut owned = Geometry::Collection(vec![
+            Geometry::Point(Coord::new(1.0, 2.0)),
+            Geometry::MultiPolygon(vec![vec![ring(0.0)], vec![ring(10.0)]]),
+            Geometry::MultiLineString(vec![vec![(0.0, 0.0).into(), (5.0, 5.0).into()]]),
+            Geometry::MultiPoint(vec![Coord::new(3.0, 3.0)]),
+        ]);
+
+        let geo = owned.to_geob(SRID::WGS84).unwrap();
+        assert_eq!(
+            geo.to_string(),
+            "SRID=4326;GEOMETRYCOLLECTION(POINT(1 2), MULTIPOLYGON(((0 0, 1 0, 1 1, 0 0)), ((10 0, 11 0, 11 1, 10 0))), MULTILINESTRING((0 0, 5 5)), MULTIPOINT(3 3))"
+        );
+        assert_eq!(geo.to_geometry(), owned);
+
+        let Geometry::Collection(children) = &mut owned else {
+            panic!("expected a collection");
+        };
+        children[0] = Geometry::Point(Coord::new(5.0, 6.0));
+        children.truncate(1);
+
+        assert_eq!(
+            owned.to_geob(SRID::WGS84).unwrap(),
+            Geob::from_text("SRID=4326;GEOMETRYCOLLECTION(POINT(5.0 6.0))").unwrap()
+        );
+
+        let nested = (0..20).fold(Geometry::Collection(Vec::new()), |geo, _| {
+            Geometry::Collection(vec![geo])
+        });
+        assert_eq!(nested.to_geob(SRID::WGS84), Err(BuilderError::TooDeep));
+    }
+}
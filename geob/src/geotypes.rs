@@ -8,9 +8,9 @@ use crate::{
 };
 use alloc::vec::Vec;
 use geo_traits::{
-    CoordTrait, GeometryCollectionTrait, LineStringTrait, MultiLineStringTrait, MultiPointTrait,
-    MultiPolygonTrait, PointTrait, PolygonTrait, UnimplementedLine, UnimplementedRect,
-    UnimplementedTriangle, to_geo::ToGeoRect,
+    CoordTrait, GeometryCollectionTrait, LineStringTrait, LineTrait, MultiLineStringTrait,
+    MultiPointTrait, MultiPolygonTrait, PointTrait, PolygonTrait, TriangleTrait, UnimplementedLine,
+    UnimplementedRect, UnimplementedTriangle, to_geo::ToGeoRect,
 };
 use udled::bytes::Endian;
 
@@ -123,7 +123,7 @@ fn process_inner<T: geo_traits::GeometryTrait<T = f64>, W: BinaryWriter>(
         }
         geo_traits::GeometryType::GeometryCollection(col) => {
             if top {
-                GeoType::MultiPolygon.write(output, endian)?;
+                GeoType::Collection.write(output, endian)?;
             }
 
             (col.num_geometries() as u32).write(output, endian)?;
@@ -136,8 +136,33 @@ fn process_inner<T: geo_traits::GeometryTrait<T = f64>, W: BinaryWriter>(
             let polygon = rect.to_rect().to_polygon();
             process_inner(&polygon, output, endian, top)?;
         }
-        geo_traits::GeometryType::Triangle(_) => todo!(),
-        geo_traits::GeometryType::Line(_) => todo!(),
+        geo_traits::GeometryType::Triangle(triangle) => {
+            if top {
+                GeoType::Polygon.write(output, endian)?;
+            }
+
+            1u32.write(output, endian)?;
+            4u32.write(output, endian)?;
+
+            for c in triangle.coords().into_iter().chain([triangle.first()]) {
+                let (x, y) = c.x_y();
+                x.write(output, endian)?;
+                y.write(output, endian)?;
+            }
+        }
+        geo_traits::GeometryType::Line(line) => {
+            if top {
+                GeoType::LineString.write(output, endian)?;
+            }
+
+            2u32.write(output, endian)?;
+
+            for c in line.coords() {
+                let (x, y) = c.x_y();
+                x.write(output, endian)?;
+                y.write(output, endian)?;
+            }
+        }
     };
 
     Ok(())
@@ -450,3 +475,26 @@ impl<'input> geo_traits::GeometryTrait for GeometryRef<'input> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use geo_traits::to_geo::ToGeoGeometry;
+    use proptest::prop_assert_eq;
+
+    use crate::{Geob, arbitrary};
+
+    #[test]
+    fn round_trip() {
+        arbitrary::runner()
+            .run(&arbitrary::geob(), |geo| {
+                let geo_type = geo.geometry().to_geometry();
+                let back = Geob::from_geo_type(&geo_type, geo.srid());
+                prop_assert_eq!(&back, &geo);
+                // NaN never equals itself in geo_types, compare as geobs
+                let again = Geob::from_geo_type(&back.geometry().to_geometry(), geo.srid());
+                prop_assert_eq!(again, back);
+                Ok(())
+            })
+            .unwrap();
+    }
+}
@@ -0,0 +1,138 @@
+use alloc::vec::Vec;
+use udled::bytes::Endian;
+
+use crate::{
+    Geob, SRID,
+    wkb::{Cursor, WkbError, read_wkb, write_wkb},
+    writer::ToBytes,
+};
+
+const MAGIC: &[u8; 2] = b"GP";
+const VERSION: u8 = 0;
+
+const FLAG_LITTLE_ENDIAN: u8 = 0b1;
+const FLAG_EMPTY: u8 = 0b1_0000;
+const FLAG_EXTENDED: u8 = 0b10_0000;
+
+/// Returns true if the bytes start with a GeoPackage binary header
+pub fn is_gpkg(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+impl Geob {
+    /// Decode a GeoPackage binary geometry. A non positive `srs_id` maps to
+    /// [`SRID::UNKNOWN`].
+    pub fn from_gpkg(bytes: &[u8]) -> Result<Geob, WkbError> {
+        let mut cursor = Cursor::new(bytes);
+
+        if cursor.take(2)? != MAGIC {
+            return Err(WkbError::InvalidHeader("missing GP magic"));
+        }
+
+        if cursor.u8()? != VERSION {
+            return Err(WkbError::InvalidHeader("unsupported version"));
+        }
+
+        let flags = cursor.u8()?;
+
+        if flags & FLAG_EXTENDED != 0 {
+            return Err(WkbError::InvalidHeader("extended geometries not supported"));
+        }
+
+        let endian = if flags & FLAG_LITTLE_ENDIAN != 0 {
+            Endian::Lt
+        } else {
+            Endian::Big
+        };
+
+        let srs_id = cursor.u32(endian)? as i32;
+
+        let envelope_len = match (flags >> 1) & 0b111 {
+            0 => 0,
+            1 => 32,
+            2 | 3 => 48,
+            4 => 64,
+            _ => return Err(WkbError::InvalidHeader("invalid envelope indicator")),
+        };
+
+        cursor.take(envelope_len)?;
+
+        let srid = if srs_id > 0 {
+            SRID::from(srs_id as u32)
+        } else {
+            SRID::UNKNOWN
+        };
+
+        let geo = read_wkb(&mut cursor, srid)?;
+
+        if !cursor.is_empty() {
+            return Err(WkbError::TrailingBytes);
+        }
+
+        Ok(geo)
+    }
+
+    /// Encode as a GeoPackage binary geometry with an xy envelope
+    pub fn to_gpkg(&self) -> Vec<u8> {
+        let endian = self.endian();
+        let envelope = self.envelope();
+
+        let mut flags = match endian {
+            Endian::Lt => FLAG_LITTLE_ENDIAN,
+            Endian::Big => 0,
+        };
+
+        flags |= match envelope {
+            Some(_) => 1 << 1,
+            None => FLAG_EMPTY,
+        };
+
+        let mut output = Vec::new();
+        output.extend_from_slice(MAGIC);
+        output.push(VERSION);
+        output.push(flags);
+
+        let srid: u32 = self.srid().into();
+        srid.write(&mut output, endian).unwrap();
+
+        if let Some(envelope) = envelope {
+            for n in [
+                envelope.min_x,
+                envelope.max_x,
+                envelope.min_y,
+                envelope.max_y,
+            ] {
+                n.write(&mut output, endian).unwrap();
+            }
+        }
+
+        write_wkb(&self.geometry(), &mut output, endian).unwrap();
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Geob, SRID};
+
+    #[test]
+    fn round_trip() {
+        let geo =
+            Geob::from_text("SRID=4326;POLYGON((0.0 0.0, 10.0 0.0, 10.0 10.0, 0.0 10.0, 0.0 0.0))")
+                .unwrap();
+
+        let gpkg = geo.to_gpkg();
+        assert_eq!(&gpkg[..4], &[b'G', b'P', 0, (geo.slice()[0] & 1) | 0b10]);
+        assert_eq!(Geob::from_gpkg(&gpkg).unwrap(), geo);
+
+        // Big endian header, srs_id -1 and no envelope
+        let mut gpkg = alloc::vec![b'G', b'P', 0, 0];
+        gpkg.extend_from_slice(&(-1i32).to_be_bytes());
+        gpkg.extend_from_slice(&geo.to_wkb());
+
+        let decoded = Geob::from_gpkg(&gpkg).unwrap();
+        assert_eq!(decoded.srid(), SRID::UNKNOWN);
+        assert_eq!(decoded.geometry().area(), 100.0);
+    }
+}
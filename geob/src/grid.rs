@@ -0,0 +1,188 @@
+//! Square and hexagon grids.
+//!
+//! Cell `(0, 0)` is anchored at the grid origin. Hexagons are flat topped with
+//! `size` as the edge length and odd columns shifted up half a cell.
+
+use libm::{ceil, floor, sqrt};
+
+use crate::{Envelope, Geob, SRID};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridKind {
+    Square,
+    Hexagon,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Grid {
+    pub kind: GridKind,
+    pub size: f64,
+    pub origin: (f64, f64),
+}
+
+impl Grid {
+    pub fn new(kind: GridKind, size: f64) -> Grid {
+        Grid {
+            kind,
+            size,
+            origin: (0.0, 0.0),
+        }
+    }
+
+    pub fn square(size: f64) -> Grid {
+        Grid::new(GridKind::Square, size)
+    }
+
+    pub fn hexagon(size: f64) -> Grid {
+        Grid::new(GridKind::Hexagon, size)
+    }
+
+    pub fn with_origin(self, x: f64, y: f64) -> Grid {
+        Grid {
+            origin: (x, y),
+            ..self
+        }
+    }
+
+    fn hex_height(&self) -> f64 {
+        sqrt(3.0) * self.size
+    }
+
+    /// Center of the cell
+    pub fn center(&self, i: i64, j: i64) -> (f64, f64) {
+        let (x0, y0) = self.origin;
+
+        match self.kind {
+            GridKind::Square => (
+                x0 + (i as f64 + 0.5) * self.size,
+                y0 + (j as f64 + 0.5) * self.size,
+            ),
+            GridKind::Hexagon => {
+                let height = self.hex_height();
+                let shift = if i.rem_euclid(2) == 1 {
+                    height / 2.0
+                } else {
+                    0.0
+                };
+
+                (
+                    x0 + i as f64 * 1.5 * self.size,
+                    y0 + j as f64 * height + shift,
+                )
+            }
+        }
+    }
+
+    /// Bounding box of the cell
+    pub fn cell_envelope(&self, i: i64, j: i64) -> Envelope {
+        let (x, y) = self.center(i, j);
+
+        let (dx, dy) = match self.kind {
+            GridKind::Square => (self.size / 2.0, self.size / 2.0),
+            GridKind::Hexagon => (self.size, self.hex_height() / 2.0),
+        };
+
+        Envelope::new(x - dx, y - dy, x + dx, y + dy)
+    }
+
+    /// Polygon of the cell, counter clockwise
+    pub fn cell(&self, i: i64, j: i64, srid: SRID) -> Geob {
+        let mut builder = Geob::builder(srid);
+
+        builder.begin_polygon().unwrap();
+        builder.begin_ring().unwrap();
+
+        match self.kind {
+            GridKind::Square => {
+                let e = self.cell_envelope(i, j);
+                for (x, y) in [
+                    (e.min_x, e.min_y),
+                    (e.max_x, e.min_y),
+                    (e.max_x, e.max_y),
+                    (e.min_x, e.max_y),
+                    (e.min_x, e.min_y),
+                ] {
+                    builder.coord(x, y).unwrap();
+                }
+            }
+            GridKind::Hexagon => {
+                let (x, y) = self.center(i, j);
+                let (dx, dy) = (self.size / 2.0, self.hex_height() / 2.0);
+                for (x, y) in [
+                    (x - self.size, y),
+                    (x - dx, y - dy),
+                    (x + dx, y - dy),
+                    (x + self.size, y),
+                    (x + dx, y + dy),
+                    (x - dx, y + dy),
+                    (x - self.size, y),
+                ] {
+                    builder.coord(x, y).unwrap();
+                }
+            }
+        }
+
+        builder.end().unwrap();
+        builder.end().unwrap();
+
+        builder.build().unwrap()
+    }
+
+    /// Cells whose bounding box intersects the envelope, column by column
+    pub fn cells(&self, envelope: &Envelope) -> impl Iterator<Item = (i64, i64)> + use<> {
+        let grid = *self;
+        let envelope = *envelope;
+        let (x0, y0) = self.origin;
+
+        let (step_x, step_y, pad_x, pad_y) = match self.kind {
+            GridKind::Square => (self.size, self.size, 0.0, 0.0),
+            GridKind::Hexagon => (
+                1.5 * self.size,
+                self.hex_height(),
+                self.size,
+                self.hex_height(),
+            ),
+        };
+
+        let i_min = floor((envelope.min_x - x0 - pad_x) / step_x) as i64;
+        let i_max = ceil((envelope.max_x - x0 + pad_x) / step_x) as i64;
+        let j_min = floor((envelope.min_y - y0 - pad_y) / step_y) as i64;
+        let j_max = ceil((envelope.max_y - y0 + pad_y) / step_y) as i64;
+
+        (i_min..=i_max)
+            .flat_map(move |i| (j_min..=j_max).map(move |j| (i, j)))
+            .filter(move |(i, j)| {
+                let cell = grid.cell_envelope(*i, *j);
+                // Cells merely touching the envelope are left out
+                cell.min_x < envelope.max_x
+                    && cell.max_x > envelope.min_x
+                    && cell.min_y < envelope.max_y
+                    && cell.max_y > envelope.min_y
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use super::Grid;
+    use crate::{Envelope, SRID};
+
+    #[test]
+    fn grid() {
+        let grid = Grid::square(10.0);
+        let cells: Vec<_> = grid.cells(&Envelope::new(0.0, 0.0, 20.0, 5.0)).collect();
+        assert_eq!(cells, [(0, 0), (1, 0)]);
+        assert_eq!(grid.cell(1, 0, SRID::UNKNOWN).geometry().area(), 100.0);
+
+        let hex = Grid::hexagon(1.0);
+        let area = hex.cell(3, -2, SRID::UNKNOWN).geometry().signed_area();
+        assert!((area - 1.5 * libm::sqrt(3.0)).abs() < 1e-12);
+
+        // Neighbouring hexagons share an edge
+        assert_eq!(hex.center(1, 0), (1.5, libm::sqrt(3.0) / 2.0));
+        let cells: Vec<_> = hex.cells(&Envelope::new(-0.1, -0.1, 0.1, 0.1)).collect();
+        assert_eq!(cells, [(0, 0)]);
+    }
+}
@@ -1,28 +1,49 @@
 #![no_std]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
-// mod binary;
+pub mod affine;
+pub mod algorithm;
+#[cfg(test)]
+mod arbitrary;
+pub mod builder;
+mod canonical;
+pub mod compact;
+pub mod curve;
+mod envelope;
+mod error;
 mod geob;
+pub mod geohash;
+mod geometry;
+pub mod gpkg;
+pub mod grid;
+pub mod mvt;
+pub mod spatialite;
 #[cfg(feature = "sqlite")]
 mod sqlite;
 pub mod srid;
+mod twkb;
 pub mod types;
 mod util;
+mod validate;
+pub mod wkb;
 pub mod wkt;
-mod writer;
+pub mod writer;
 
 #[cfg(feature = "rstar")]
 pub mod rstar;
 
 #[cfg(feature = "geo-traits")]
 mod geotypes;
-#[cfg(feature = "proj")]
-mod projection;
+pub mod projection;
 
 pub use self::{
-    // binary::GeoType,
+    envelope::Envelope,
+    error::Error,
     geob::Geob,
-    srid::{EPSG, SRID},
+    geometry::{Coord, Geometry},
+    srid::{AreaOfUse, AxisOrder, CRS, CoordinateSystem, Datum, EPSG, SRID, Unit},
     types::{GeoType, GeobRef},
 };
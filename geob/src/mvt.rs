@@ -0,0 +1,1149 @@
+//! Mapbox Vector Tile encoding.
+//!
+//! Geometries are first transformed into tile coordinates with
+//! [`Geob::to_tile`] and then added to a [`Layer`], which encodes a protobuf
+//! tile.
+
+use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
+use core::mem;
+use libm::round;
+
+use crate::{
+    Envelope, Geob, SRID,
+    builder::GeobBuilder,
+    types::{CoordSeqRef, GeometryRef, PolygonRef},
+    util::{write_varint, zigzag},
+};
+
+/// Half the circumference of the Web Mercator projection in meters
+const ORIGIN_SHIFT: f64 = 20_037_508.342_789_244;
+
+pub const DEFAULT_EXTENT: u32 = 4096;
+pub const DEFAULT_BUFFER: u32 = 256;
+
+const VERSION: u64 = 2;
+
+// Geometry commands
+const MOVE_TO: u32 = 1;
+const LINE_TO: u32 = 2;
+const CLOSE_PATH: u32 = 7;
+
+// Feature geometry types
+const POINT: u64 = 1;
+const LINE_STRING: u64 = 2;
+const POLYGON: u64 = 3;
+
+/// Bounds of the XYZ tile in Web Mercator meters, `None` if the tile does not
+/// exist at the zoom level
+pub fn tile_envelope(z: u32, x: u32, y: u32) -> Option<Envelope> {
+    let n = 1u32.checked_shl(z)?;
+
+    if x >= n || y >= n {
+        return None;
+    }
+
+    let size = 2.0 * ORIGIN_SHIFT / n as f64;
+
+    Some(Envelope::new(
+        -ORIGIN_SHIFT + x as f64 * size,
+        ORIGIN_SHIFT - (y + 1) as f64 * size,
+        -ORIGIN_SHIFT + (x + 1) as f64 * size,
+        ORIGIN_SHIFT - y as f64 * size,
+    ))
+}
+
+impl Geob {
+    /// Transform into the integer coordinate space of a tile covering `bounds`,
+    /// with the y axis pointing down.
+    ///
+    /// With `clip` the geometry is clipped to the tile extended by `buffer`.
+    /// Collections keep only their highest dimension components. Returns
+    /// `None` if nothing remains, or if the bounds have no area.
+    pub fn to_tile(&self, bounds: &Envelope, extent: u32, buffer: u32, clip: bool) -> Option<Geob> {
+        if !(bounds.width() > 0.0 && bounds.height() > 0.0) {
+            return None;
+        }
+
+        let buffer = buffer as f64;
+        let extent = extent as f64;
+
+        let mut tile = Tile {
+            bounds: *bounds,
+            scale_x: extent / bounds.width(),
+            scale_y: extent / bounds.height(),
+            clip: clip.then(|| Envelope::new(-buffer, -buffer, extent + buffer, extent + buffer)),
+            points: Vec::new(),
+            lines: Vec::new(),
+            polygons: Vec::new(),
+        };
+
+        tile.add(&self.geometry());
+        tile.build()
+    }
+}
+
+type Coord = (f64, f64);
+
+struct Tile {
+    bounds: Envelope,
+    scale_x: f64,
+    scale_y: f64,
+    clip: Option<Envelope>,
+    points: Vec<Coord>,
+    lines: Vec<Vec<Coord>>,
+    polygons: Vec<Vec<Vec<Coord>>>,
+}
+
+impl Tile {
+    fn project(&self, x: f64, y: f64) -> Coord {
+        (
+            (x - self.bounds.min_x) * self.scale_x,
+            (self.bounds.max_y - y) * self.scale_y,
+        )
+    }
+
+    fn project_seq(&self, coords: CoordSeqRef<'_>) -> Vec<Coord> {
+        coords.iter().map(|c| self.project(c.x(), c.y())).collect()
+    }
+
+    fn add(&mut self, geo: &GeometryRef<'_>) {
+        match geo {
+            GeometryRef::Point(point) => self.add_point(point.x(), point.y()),
+            GeometryRef::MultiPoint(points) => {
+                for coord in points.iter() {
+                    self.add_point(coord.x(), coord.y());
+                }
+            }
+            GeometryRef::LineString(line) => self.add_line(line.0),
+            GeometryRef::MultiLineString(lines) => {
+                for line in lines.iter() {
+                    self.add_line(line);
+                }
+            }
+            GeometryRef::Polygon(polygon) => self.add_polygon(*polygon),
+            GeometryRef::MultiPolygon(polygons) => {
+                for polygon in polygons.iter() {
+                    self.add_polygon(polygon);
+                }
+            }
+            GeometryRef::Collection(collection) => {
+                for geo in collection.iter() {
+                    self.add(&geo);
+                }
+            }
+        }
+    }
+
+    fn add_point(&mut self, x: f64, y: f64) {
+        let coord = self.project(x, y);
+
+        if coord.0.is_nan() || coord.1.is_nan() {
+            return;
+        }
+
+        if let Some(clip) = &self.clip
+            && !clip.contains(coord.0, coord.1)
+        {
+            return;
+        }
+
+        self.points.push(snap(coord));
+    }
+
+    fn add_line(&mut self, line: CoordSeqRef<'_>) {
+        let line = self.project_seq(line);
+
+        let parts = match &self.clip {
+            Some(clip) => clip_line(&line, clip),
+            None => vec![line],
+        };
+
+        for part in parts {
+            let part = snap_seq(&part);
+            if part.len() >= 2 {
+                self.lines.push(part);
+            }
+        }
+    }
+
+    fn add_polygon(&mut self, polygon: PolygonRef<'_>) {
+        let mut rings = Vec::new();
+
+        for (idx, ring) in polygon.iter().enumerate() {
+            let mut ring = self.project_seq(ring);
+            // Work on open rings
+            if ring.len() > 1 && ring.first() == ring.last() {
+                ring.pop();
+            }
+
+            if let Some(clip) = &self.clip {
+                ring = clip_ring(&ring, clip);
+            }
+
+            let mut ring = snap_seq(&ring);
+            if ring.len() > 1 && ring.first() == ring.last() {
+                ring.pop();
+            }
+
+            if ring.len() < 3 || ring_area(&ring) == 0.0 {
+                if idx == 0 {
+                    return;
+                }
+                continue;
+            }
+
+            ring.push(ring[0]);
+            rings.push(ring);
+        }
+
+        if !rings.is_empty() {
+            self.polygons.push(rings);
+        }
+    }
+
+    fn build(self) -> Option<Geob> {
+        let mut builder = Geob::builder(SRID::UNKNOWN);
+
+        if !self.polygons.is_empty() {
+            let multi = self.polygons.len() > 1;
+            if multi {
+                builder.begin_multi_polygon().unwrap();
+            }
+            for polygon in &self.polygons {
+                builder.begin_polygon().unwrap();
+                for ring in polygon {
+                    builder.begin_ring().unwrap();
+                    coords(&mut builder, ring);
+                    builder.end().unwrap();
+                }
+                builder.end().unwrap();
+            }
+            if multi {
+                builder.end().unwrap();
+            }
+        } else if !self.lines.is_empty() {
+            let multi = self.lines.len() > 1;
+            if multi {
+                builder.begin_multi_line_string().unwrap();
+            }
+            for line in &self.lines {
+                builder.begin_line_string().unwrap();
+                coords(&mut builder, line);
+                builder.end().unwrap();
+            }
+            if multi {
+                builder.end().unwrap();
+            }
+        } else if let [(x, y)] = self.points[..] {
+            builder.point(x, y).unwrap();
+        } else if !self.points.is_empty() {
+            builder.begin_multi_point().unwrap();
+            for &(x, y) in &self.points {
+                builder.point(x, y).unwrap();
+            }
+            builder.end().unwrap();
+        } else {
+            return None;
+        }
+
+        Some(builder.build().unwrap())
+    }
+}
+
+fn coords(builder: &mut GeobBuilder, coords: &[Coord]) {
+    for &(x, y) in coords {
+        builder.coord(x, y).unwrap();
+    }
+}
+
+fn snap((x, y): Coord) -> Coord {
+    (round(x), round(y))
+}
+
+/// Snap to the integer grid, dropping repeated coordinates
+fn snap_seq(coords: &[Coord]) -> Vec<Coord> {
+    let mut output: Vec<Coord> = Vec::with_capacity(coords.len());
+
+    for &coord in coords {
+        let coord = snap(coord);
+        if output.last() != Some(&coord) {
+            output.push(coord);
+        }
+    }
+
+    output
+}
+
+/// Surveyor's formula for an open ring. Positive for clockwise rings when the
+/// y axis points down.
+fn ring_area(ring: &[Coord]) -> f64 {
+    let mut sum = 0.0;
+    let mut prev = ring[ring.len() - 1];
+
+    for &coord in ring {
+        sum += prev.0 * coord.1 - coord.0 * prev.1;
+        prev = coord;
+    }
+
+    sum / 2.0
+}
+
+/// Liang-Barsky clipping of a segment
+fn clip_segment(a: Coord, b: Coord, clip: &Envelope) -> Option<(Coord, Coord)> {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let (mut t0, mut t1) = (0.0f64, 1.0f64);
+
+    for (p, q) in [
+        (-dx, a.0 - clip.min_x),
+        (dx, clip.max_x - a.0),
+        (-dy, a.1 - clip.min_y),
+        (dy, clip.max_y - a.1),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+            continue;
+        }
+
+        let r = q / p;
+        if p < 0.0 {
+            if r > t1 {
+                return None;
+            }
+            t0 = t0.max(r);
+        } else {
+            if r < t0 {
+                return None;
+            }
+            t1 = t1.min(r);
+        }
+    }
+
+    // Keep unclipped endpoints exact so consecutive segments stay connected
+    let start = if t0 > 0.0 {
+        (a.0 + t0 * dx, a.1 + t0 * dy)
+    } else {
+        a
+    };
+    let end = if t1 < 1.0 {
+        (a.0 + t1 * dx, a.1 + t1 * dy)
+    } else {
+        b
+    };
+
+    Some((start, end))
+}
+
+/// Clip a line, splitting it where it leaves the clip box
+fn clip_line(line: &[Coord], clip: &Envelope) -> Vec<Vec<Coord>> {
+    let mut parts = Vec::new();
+    let mut current: Vec<Coord> = Vec::new();
+
+    for segment in line.windows(2) {
+        match clip_segment(segment[0], segment[1], clip) {
+            Some((start, end)) => {
+                if current.last() != Some(&start) {
+                    if current.len() >= 2 {
+                        parts.push(mem::take(&mut current));
+                    }
+                    current.clear();
+                    current.push(start);
+                }
+                current.push(end);
+            }
+            None => {
+                if current.len() >= 2 {
+                    parts.push(mem::take(&mut current));
+                }
+                current.clear();
+            }
+        }
+    }
+
+    if current.len() >= 2 {
+        parts.push(current);
+    }
+
+    parts
+}
+
+/// Sutherland-Hodgman clipping of an open ring
+pub(crate) fn clip_ring(ring: &[Coord], clip: &Envelope) -> Vec<Coord> {
+    let mut output = ring.to_vec();
+
+    for edge in 0..4 {
+        let input = mem::take(&mut output);
+        let Some(&last) = input.last() else {
+            break;
+        };
+
+        let inside = |(x, y): Coord| match edge {
+            0 => x >= clip.min_x,
+            1 => x <= clip.max_x,
+            2 => y >= clip.min_y,
+            _ => y <= clip.max_y,
+        };
+
+        let intersect = |(x1, y1): Coord, (x2, y2): Coord| match edge {
+            0 | 1 => {
+                let x = if edge == 0 { clip.min_x } else { clip.max_x };
+                (x, y1 + (y2 - y1) * (x - x1) / (x2 - x1))
+            }
+            _ => {
+                let y = if edge == 2 { clip.min_y } else { clip.max_y };
+                (x1 + (x2 - x1) * (y - y1) / (y2 - y1), y)
+            }
+        };
+
+        let mut prev = last;
+        for &coord in &input {
+            match (inside(prev), inside(coord)) {
+                (true, true) => output.push(coord),
+                (true, false) => output.push(intersect(prev, coord)),
+                (false, true) => {
+                    output.push(intersect(prev, coord));
+                    output.push(coord);
+                }
+                (false, false) => {}
+            }
+            prev = coord;
+        }
+    }
+
+    output
+}
+
+/// Feature property value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Double(f64),
+    Int(i64),
+    Bool(bool),
+}
+
+/// Orderable form of [`Value`] used to deduplicate values
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum ValueKey {
+    String(String),
+    Double(u64),
+    Int(i64),
+    Bool(bool),
+}
+
+impl From<&Value> for ValueKey {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::String(s) => ValueKey::String(s.clone()),
+            Value::Double(n) => ValueKey::Double(n.to_bits()),
+            Value::Int(n) => ValueKey::Int(*n),
+            Value::Bool(b) => ValueKey::Bool(*b),
+        }
+    }
+}
+
+/// A vector tile layer. Keys and values are deduplicated across features.
+#[derive(Debug, Clone)]
+pub struct Layer {
+    name: String,
+    extent: u32,
+    keys: Vec<String>,
+    key_index: BTreeMap<String, u32>,
+    values: Vec<Value>,
+    value_index: BTreeMap<ValueKey, u32>,
+    features: Vec<u8>,
+    len: usize,
+}
+
+impl Layer {
+    pub fn new(name: impl Into<String>, extent: u32) -> Layer {
+        Layer {
+            name: name.into(),
+            extent,
+            keys: Vec::new(),
+            key_index: BTreeMap::new(),
+            values: Vec::new(),
+            value_index: BTreeMap::new(),
+            features: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn extent(&self) -> u32 {
+        self.extent
+    }
+
+    /// Number of features
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Add a feature with a geometry in tile coordinates, see
+    /// [`Geob::to_tile`]. Returns `false` if the geometry is empty, a
+    /// collection or has coordinates outside the i32 range of the tile, in
+    /// which case the feature is skipped. Unclipped geometries far outside
+    /// the tile can hit the range.
+    pub fn add_feature<'b, I>(
+        &mut self,
+        geo: &GeometryRef<'_>,
+        id: Option<u64>,
+        properties: I,
+    ) -> bool
+    where
+        I: IntoIterator<Item = (&'b str, Value)>,
+    {
+        let Some((kind, geometry)) = encode_geometry(geo) else {
+            return false;
+        };
+
+        let mut tags = Vec::new();
+        for (key, value) in properties {
+            tags.push(self.key(key));
+            tags.push(self.value(value));
+        }
+
+        let mut feature = Vec::new();
+
+        if let Some(id) = id {
+            write_key(&mut feature, 1, WIRE_VARINT);
+            write_varint(&mut feature, id);
+        }
+
+        if !tags.is_empty() {
+            write_packed(&mut feature, 2, &tags);
+        }
+
+        write_key(&mut feature, 3, WIRE_VARINT);
+        write_varint(&mut feature, kind);
+
+        write_packed(&mut feature, 4, &geometry);
+
+        write_bytes(&mut self.features, 2, &feature);
+        self.len += 1;
+
+        true
+    }
+
+    fn key(&mut self, key: &str) -> u32 {
+        if let Some(idx) = self.key_index.get(key) {
+            return *idx;
+        }
+
+        let idx = self.keys.len() as u32;
+        self.keys.push(key.into());
+        self.key_index.insert(key.into(), idx);
+        idx
+    }
+
+    fn value(&mut self, value: Value) -> u32 {
+        let key = ValueKey::from(&value);
+
+        if let Some(idx) = self.value_index.get(&key) {
+            return *idx;
+        }
+
+        let idx = self.values.len() as u32;
+        self.values.push(value);
+        self.value_index.insert(key, idx);
+        idx
+    }
+
+    /// Encode as a tile containing only this layer. Encoded tiles can be
+    /// concatenated to combine layers.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut layer = Vec::new();
+
+        write_key(&mut layer, 15, WIRE_VARINT);
+        write_varint(&mut layer, VERSION);
+
+        write_bytes(&mut layer, 1, self.name.as_bytes());
+
+        layer.extend_from_slice(&self.features);
+
+        for key in &self.keys {
+            write_bytes(&mut layer, 3, key.as_bytes());
+        }
+
+        for value in &self.values {
+            let mut output = Vec::new();
+            match value {
+                Value::String(s) => write_bytes(&mut output, 1, s.as_bytes()),
+                Value::Double(n) => {
+                    write_key(&mut output, 3, WIRE_FIXED64);
+                    output.extend_from_slice(&n.to_le_bytes());
+                }
+                Value::Int(n) => {
+                    write_key(&mut output, 6, WIRE_VARINT);
+                    write_varint(&mut output, zigzag(*n));
+                }
+                Value::Bool(b) => {
+                    write_key(&mut output, 7, WIRE_VARINT);
+                    write_varint(&mut output, *b as u64);
+                }
+            }
+            write_bytes(&mut layer, 4, &output);
+        }
+
+        write_key(&mut layer, 5, WIRE_VARINT);
+        write_varint(&mut layer, self.extent as u64);
+
+        let mut tile = Vec::with_capacity(layer.len() + 8);
+        write_bytes(&mut tile, 3, &layer);
+        tile
+    }
+}
+
+const WIRE_VARINT: u64 = 0;
+const WIRE_FIXED64: u64 = 1;
+const WIRE_BYTES: u64 = 2;
+
+fn write_key(output: &mut Vec<u8>, field: u64, wire: u64) {
+    write_varint(output, (field << 3) | wire);
+}
+
+fn write_bytes(output: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    write_key(output, field, WIRE_BYTES);
+    write_varint(output, bytes.len() as u64);
+    output.extend_from_slice(bytes);
+}
+
+fn write_packed(output: &mut Vec<u8>, field: u64, values: &[u32]) {
+    let mut packed = Vec::with_capacity(values.len());
+    for value in values {
+        write_varint(&mut packed, *value as u64);
+    }
+    write_bytes(output, field, &packed);
+}
+
+struct Commands {
+    output: Vec<u32>,
+    cursor: (i64, i64),
+}
+
+impl Commands {
+    fn command(&mut self, id: u32, count: usize) {
+        self.output.push((id & 0x7) | ((count as u32) << 3));
+    }
+
+    /// `None` if the coordinate or the delta from the cursor does not fit an
+    /// i32 parameter
+    fn coord(&mut self, x: f64, y: f64) -> Option<()> {
+        let (x, y) = (round(x), round(y));
+        if !(x.is_finite() && y.is_finite()) {
+            return None;
+        }
+
+        let (x, y) = (x as i64, y as i64);
+        let dx = i32::try_from(x - self.cursor.0).ok()?;
+        let dy = i32::try_from(y - self.cursor.1).ok()?;
+        i32::try_from(x).ok()?;
+        i32::try_from(y).ok()?;
+
+        self.output.push(zigzag(dx as i64) as u32);
+        self.output.push(zigzag(dy as i64) as u32);
+        self.cursor = (x, y);
+        Some(())
+    }
+
+    fn line(&mut self, line: CoordSeqRef<'_>) -> Option<()> {
+        if line.len() < 2 {
+            return Some(());
+        }
+
+        let mut coords = line.iter();
+        let first = coords.next()?;
+
+        self.command(MOVE_TO, 1);
+        self.coord(first.x(), first.y())?;
+        self.command(LINE_TO, line.len() - 1);
+        for coord in coords {
+            self.coord(coord.x(), coord.y())?;
+        }
+
+        Some(())
+    }
+
+    fn polygon(&mut self, polygon: PolygonRef<'_>) -> Option<()> {
+        for (idx, ring) in polygon.iter().enumerate() {
+            let mut ring: Vec<Coord> = ring.iter().map(|c| (c.x(), c.y())).collect();
+            if ring.len() > 1 && ring.first() == ring.last() {
+                ring.pop();
+            }
+
+            if ring.len() < 3 {
+                continue;
+            }
+
+            // Exterior rings are clockwise in tile coordinates, holes counter clockwise
+            if (ring_area(&ring) > 0.0) != (idx == 0) {
+                ring.reverse();
+            }
+
+            self.command(MOVE_TO, 1);
+            self.coord(ring[0].0, ring[0].1)?;
+            self.command(LINE_TO, ring.len() - 1);
+            for &(x, y) in &ring[1..] {
+                self.coord(x, y)?;
+            }
+            self.command(CLOSE_PATH, 1);
+        }
+
+        Some(())
+    }
+}
+
+fn encode_geometry(geo: &GeometryRef<'_>) -> Option<(u64, Vec<u32>)> {
+    let mut commands = Commands {
+        output: Vec::new(),
+        cursor: (0, 0),
+    };
+
+    let kind = match geo {
+        GeometryRef::Point(point) => {
+            commands.command(MOVE_TO, 1);
+            commands.coord(point.x(), point.y())?;
+            POINT
+        }
+        GeometryRef::MultiPoint(points) => {
+            commands.command(MOVE_TO, points.len());
+            for coord in points.iter() {
+                commands.coord(coord.x(), coord.y())?;
+            }
+            POINT
+        }
+        GeometryRef::LineString(line) => {
+            commands.line(line.0)?;
+            LINE_STRING
+        }
+        GeometryRef::MultiLineString(lines) => {
+            for line in lines.iter() {
+                commands.line(line)?;
+            }
+            LINE_STRING
+        }
+        GeometryRef::Polygon(polygon) => {
+            commands.polygon(*polygon)?;
+            POLYGON
+        }
+        GeometryRef::MultiPolygon(polygons) => {
+            for polygon in polygons.iter() {
+                commands.polygon(polygon)?;
+            }
+            POLYGON
+        }
+        GeometryRef::Collection(_) => return None,
+    };
+
+    if commands.output.len() <= 1 {
+        return None;
+    }
+
+    Some((kind, commands.output))
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{string::String, vec, vec::Vec};
+
+    use super::{
+        CLOSE_PATH, DEFAULT_BUFFER, DEFAULT_EXTENT, LINE_TO, Layer, MOVE_TO, POINT, POLYGON, Value,
+        clip_ring, encode_geometry, ring_area, tile_envelope,
+    };
+    use crate::{Envelope, Geob, SRID, util::unzigzag};
+
+    enum Field<'a> {
+        Varint(u64),
+        Fixed64(u64),
+        Bytes(&'a [u8]),
+    }
+
+    fn varint(buf: &mut &[u8]) -> u64 {
+        let mut n = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = buf[0];
+            *buf = &buf[1..];
+            n |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        n
+    }
+
+    /// Fields of a protobuf message in order
+    fn fields(mut buf: &[u8]) -> Vec<(u64, Field<'_>)> {
+        let mut output = Vec::new();
+        while !buf.is_empty() {
+            let key = varint(&mut buf);
+            let field = match key & 0x7 {
+                0 => Field::Varint(varint(&mut buf)),
+                1 => {
+                    let (n, rest) = buf.split_at(8);
+                    buf = rest;
+                    Field::Fixed64(u64::from_le_bytes(n.try_into().unwrap()))
+                }
+                2 => {
+                    let len = varint(&mut buf) as usize;
+                    let (bytes, rest) = buf.split_at(len);
+                    buf = rest;
+                    Field::Bytes(bytes)
+                }
+                wire => panic!("unexpected wire type {wire}"),
+            };
+            output.push((key >> 3, field));
+        }
+        output
+    }
+
+    fn packed(mut buf: &[u8]) -> Vec<u32> {
+        let mut output = Vec::new();
+        while !buf.is_empty() {
+            output.push(varint(&mut buf) as u32);
+        }
+        output
+    }
+
+    /// Parts of a geometry command stream, each MoveTo starts a part and
+    /// ClosePath repeats its first point
+    fn paths(commands: &[u32]) -> Vec<Vec<(i64, i64)>> {
+        let (mut output, mut cursor) = (Vec::<Vec<_>>::new(), (0, 0));
+        let mut commands = commands.iter();
+        while let Some(command) = commands.next() {
+            let (id, count) = (command & 0x7, command >> 3);
+            if id == CLOSE_PATH {
+                let part = output.last_mut().unwrap();
+                part.push(part[0]);
+                continue;
+            }
+            if id == MOVE_TO {
+                output.push(Vec::new());
+            }
+            for _ in 0..count {
+                cursor.0 += unzigzag(*commands.next().unwrap() as u64);
+                cursor.1 += unzigzag(*commands.next().unwrap() as u64);
+                output.last_mut().unwrap().push(cursor);
+            }
+        }
+        output
+    }
+
+    fn area(path: &[(i64, i64)]) -> f64 {
+        let ring: Vec<_> = path[..path.len() - 1]
+            .iter()
+            .map(|&(x, y)| (x as f64, y as f64))
+            .collect();
+        ring_area(&ring)
+    }
+
+    #[test]
+    fn tile_geometry() {
+        let bounds = tile_envelope(0, 0, 0).unwrap();
+        assert_eq!(bounds.min_x, -bounds.max_x);
+        assert_eq!(tile_envelope(1, 2, 0), None);
+
+        let line = Geob::from_text(
+            "SRID=3857;LINESTRING(-30000000.0 0.0, 0.0 0.0, 0.0 10018754.171394622)",
+        )
+        .unwrap();
+
+        let tile = line
+            .to_tile(&bounds, DEFAULT_EXTENT, DEFAULT_BUFFER, true)
+            .unwrap();
+        let coords: Vec<_> = tile
+            .as_line_string()
+            .unwrap()
+            .iter()
+            .map(|c| (c.x(), c.y()))
+            .collect();
+        assert_eq!(
+            coords,
+            [(-256.0, 2048.0), (2048.0, 2048.0), (2048.0, 1024.0)]
+        );
+
+        assert_eq!(
+            Geob::new_point(SRID::WEB_MERCATOR, 3e7, 0.0)
+                .unwrap()
+                .to_tile(&bounds, DEFAULT_EXTENT, DEFAULT_BUFFER, true),
+            None
+        );
+    }
+
+    #[test]
+    fn encode_layer() {
+        let point = Geob::new_point(SRID::UNKNOWN, 25.0, 17.0).unwrap();
+
+        let mut layer = Layer::new("points", 4096);
+        assert!(layer.add_feature(
+            &point.geometry(),
+            Some(1),
+            [("name", Value::String("a".into())), ("rank", Value::Int(1))]
+        ));
+        assert!(layer.add_feature(
+            &point.geometry(),
+            None,
+            [("name", Value::String("a".into()))]
+        ));
+
+        let tile = layer.encode();
+
+        // Tile.layers, then Layer.version = 2
+        assert_eq!(tile[0], 0x1a);
+        assert_eq!(&tile[2..4], &[0x78, 0x02]);
+        // MoveTo(1), zigzag(25), zigzag(17)
+        assert!(tile.windows(3).any(|w| w == [9, 50, 34]));
+        assert_eq!(layer.keys.len(), 2);
+        assert_eq!(layer.values.len(), 2);
+    }
+
+    #[test]
+    fn polygon_winding() {
+        // Counter clockwise exterior and clockwise hole in tile coordinates
+        let polygon = Geob::from_text(
+            "SRID=0;POLYGON((0 0, 0 10, 10 10, 10 0, 0 0), (2 2, 4 2, 4 4, 2 4, 2 2))",
+        )
+        .unwrap();
+
+        let (kind, commands) = encode_geometry(&polygon.geometry()).unwrap();
+        assert_eq!(kind, POLYGON);
+        // MoveTo(1), LineTo(3), ClosePath(1) per ring
+        let words: Vec<_> = [0, 3, 10, 11, 14, 21]
+            .into_iter()
+            .map(|idx| commands[idx])
+            .collect();
+        assert_eq!(
+            words,
+            [
+                MOVE_TO | 1 << 3,
+                LINE_TO | 3 << 3,
+                CLOSE_PATH | 1 << 3,
+                MOVE_TO | 1 << 3,
+                LINE_TO | 3 << 3,
+                CLOSE_PATH | 1 << 3
+            ]
+        );
+        assert_eq!(commands.len(), 22);
+
+        let rings = paths(&commands);
+        assert_eq!(rings.len(), 2);
+        assert_eq!(rings[0].first(), rings[0].last());
+        assert_eq!(area(&rings[0]), 100.0);
+        assert_eq!(area(&rings[1]), -4.0);
+
+        // Already correctly wound rings are kept
+        let polygon = Geob::from_text("SRID=0;POLYGON((0 0, 10 0, 10 10, 0 10, 0 0))").unwrap();
+        let (_, commands) = encode_geometry(&polygon.geometry()).unwrap();
+        assert_eq!(
+            paths(&commands)[0],
+            [(0, 0), (10, 0), (10, 10), (0, 10), (0, 0)]
+        );
+    }
+
+    #[test]
+    fn clip_polygon() {
+        let clip = Envelope::new(0.0, 0.0, 10.0, 10.0);
+        let ring = [(-5.0, -5.0), (5.0, -5.0), (5.0, 5.0), (-5.0, 5.0)];
+
+        let clipped = clip_ring(&ring, &clip);
+        assert_eq!(ring_area(&clipped), 25.0);
+        assert!(
+            clipped
+                .iter()
+                .all(|&(x, y)| (0.0..=5.0).contains(&x) && (0.0..=5.0).contains(&y))
+        );
+
+        assert!(clip_ring(&[(20.0, 20.0), (30.0, 20.0), (30.0, 30.0)], &clip).is_empty());
+        assert_eq!(
+            clip_ring(&ring, &Envelope::new(-10.0, -10.0, 10.0, 10.0)),
+            ring
+        );
+
+        let bounds = Envelope::new(0.0, 0.0, 4096.0, 4096.0);
+        let polygon = Geob::from_text(
+            "SRID=0;POLYGON((-100 -100, 5000 -100, 5000 5000, -100 5000, -100 -100), \
+             (100 100, 200 100, 200 200, 100 200, 100 100))",
+        )
+        .unwrap();
+
+        let tile = polygon.to_tile(&bounds, 4096, 0, true).unwrap();
+        let envelope = tile.envelope().unwrap();
+        assert_eq!(
+            (
+                envelope.min_x,
+                envelope.min_y,
+                envelope.max_x,
+                envelope.max_y
+            ),
+            (0.0, 0.0, 4096.0, 4096.0)
+        );
+        assert_eq!(tile.as_polygon().unwrap().len(), 2);
+
+        let unclipped = polygon.to_tile(&bounds, 4096, 0, false).unwrap();
+        assert_eq!(unclipped.envelope().unwrap().max_x, 5000.0);
+
+        let outside =
+            Geob::from_text("SRID=0;POLYGON((5000 5000, 6000 5000, 6000 6000, 5000 5000))")
+                .unwrap();
+        assert_eq!(outside.to_tile(&bounds, 4096, 0, true), None);
+
+        let flat = Envelope::new(0.0, 0.0, 4096.0, 0.0);
+        assert_eq!(polygon.to_tile(&flat, 4096, 0, false), None);
+    }
+
+    #[test]
+    fn multi_geometries() {
+        let points = Geob::from_text("SRID=0;MULTIPOINT(1 2, 3 4, 5 6)").unwrap();
+        let (kind, commands) = encode_geometry(&points.geometry()).unwrap();
+        assert_eq!(kind, POINT);
+        assert_eq!(commands[0], MOVE_TO | 3 << 3);
+        assert_eq!(paths(&commands), [vec![(1, 2), (3, 4), (5, 6)]]);
+
+        let lines = Geob::from_text("SRID=0;MULTILINESTRING((0 0, 1 1), (5 5, 6 6, 7 5))").unwrap();
+        let (_, commands) = encode_geometry(&lines.geometry()).unwrap();
+        assert_eq!(
+            paths(&commands),
+            [vec![(0, 0), (1, 1)], vec![(5, 5), (6, 6), (7, 5)]]
+        );
+
+        let polygons = Geob::from_text(
+            "SRID=0;MULTIPOLYGON(((0 0, 4 0, 4 4, 0 4, 0 0)), ((10 10, 14 10, 14 14, 10 14, 10 10)))",
+        )
+        .unwrap();
+        let (_, commands) = encode_geometry(&polygons.geometry()).unwrap();
+        let rings = paths(&commands);
+        assert_eq!(rings.len(), 2);
+        assert!(rings.iter().all(|ring| area(ring) == 16.0));
+        assert_eq!(
+            commands
+                .iter()
+                .filter(|&&c| c == CLOSE_PATH | 1 << 3)
+                .count(),
+            2
+        );
+
+        let mut layer = Layer::new("mixed", 4096);
+        let collection = Geob::from_text("SRID=0;GEOMETRYCOLLECTION(POINT(1 2))").unwrap();
+        assert!(!layer.add_feature(&collection.geometry(), None, []));
+        assert!(layer.is_empty());
+    }
+
+    #[test]
+    fn out_of_range() {
+        let mut layer = Layer::new("far", 4096);
+
+        let far = Geob::new_point(SRID::UNKNOWN, 3e9, 0.0).unwrap();
+        assert!(!layer.add_feature(&far.geometry(), None, []));
+
+        let nan = Geob::new_point(SRID::UNKNOWN, f64::NAN, 0.0).unwrap();
+        assert!(!layer.add_feature(&nan.geometry(), None, []));
+
+        // Both ends fit, the delta between them does not
+        let line = Geob::from_text("SRID=0;LINESTRING(-2000000000 0, 2000000000 0)").unwrap();
+        assert!(!layer.add_feature(&line.geometry(), None, []));
+
+        let line = Geob::from_text("SRID=0;LINESTRING(0 0, 2000000000 0)").unwrap();
+        assert!(layer.add_feature(&line.geometry(), None, []));
+        assert_eq!(layer.len(), 1);
+
+        let bounds = Envelope::new(0.0, 0.0, 4096.0, 4096.0);
+        let tile = far.to_tile(&bounds, 4096, 0, false).unwrap();
+        assert!(!layer.add_feature(&tile.geometry(), None, []));
+    }
+
+    #[test]
+    fn decode_tile() {
+        let mut layer = Layer::new("places", 512);
+        let point = Geob::new_point(SRID::UNKNOWN, 25.0, 17.0).unwrap();
+        let line = Geob::from_text("SRID=0;LINESTRING(1 1, 4 5)").unwrap();
+
+        assert!(layer.add_feature(
+            &point.geometry(),
+            Some(7),
+            [
+                ("name", Value::String("a".into())),
+                ("rank", Value::Int(-3)),
+                ("open", Value::Bool(true))
+            ]
+        ));
+        assert!(layer.add_feature(
+            &line.geometry(),
+            None,
+            [
+                ("rank", Value::Double(1.5)),
+                ("name", Value::String("a".into()))
+            ]
+        ));
+
+        let tile = layer.encode();
+        let layers = fields(&tile);
+        assert_eq!(layers.len(), 1);
+        let (3, Field::Bytes(layer)) = layers[0] else {
+            panic!("expected a layer");
+        };
+
+        let (mut version, mut name, mut extent) = (0, String::new(), 0);
+        let (mut keys, mut values, mut features) = (Vec::new(), Vec::new(), Vec::new());
+        for (field, value) in fields(layer) {
+            match (field, value) {
+                (15, Field::Varint(n)) => version = n,
+                (1, Field::Bytes(b)) => name = String::from_utf8(b.to_vec()).unwrap(),
+                (2, Field::Bytes(b)) => features.push(b),
+                (3, Field::Bytes(b)) => keys.push(core::str::from_utf8(b).unwrap()),
+                (4, Field::Bytes(b)) => values.push(match fields(b)[0] {
+                    (1, Field::Bytes(s)) => Value::String(String::from_utf8(s.to_vec()).unwrap()),
+                    (3, Field::Fixed64(n)) => Value::Double(f64::from_bits(n)),
+                    (6, Field::Varint(n)) => Value::Int(unzigzag(n)),
+                    (7, Field::Varint(n)) => Value::Bool(n != 0),
+                    _ => panic!("unexpected value"),
+                }),
+                (5, Field::Varint(n)) => extent = n,
+                _ => panic!("unexpected layer field {field}"),
+            }
+        }
+
+        assert_eq!((version, name.as_str(), extent), (2, "places", 512));
+        assert_eq!(keys, ["name", "rank", "open"]);
+        assert_eq!(
+            values,
+            [
+                Value::String("a".into()),
+                Value::Int(-3),
+                Value::Bool(true),
+                Value::Double(1.5)
+            ]
+        );
+
+        let features: Vec<_> = features
+            .into_iter()
+            .map(|feature| {
+                let (mut id, mut tags, mut kind, mut geometry) = (None, Vec::new(), 0, Vec::new());
+                for (field, value) in fields(feature) {
+                    match (field, value) {
+                        (1, Field::Varint(n)) => id = Some(n),
+                        (2, Field::Bytes(b)) => tags = packed(b),
+                        (3, Field::Varint(n)) => kind = n,
+                        (4, Field::Bytes(b)) => geometry = paths(&packed(b)),
+                        _ => panic!("unexpected feature field {field}"),
+                    }
+                }
+                (id, tags, kind, geometry)
+            })
+            .collect();
+
+        assert_eq!(
+            features,
+            [
+                (Some(7), vec![0, 0, 1, 1, 2, 2], 1, vec![vec![(25, 17)]]),
+                (None, vec![1, 3, 0, 0], 2, vec![vec![(1, 1), (4, 5)]]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use core::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+use libm::{asin, atan, atan2, atanh, cos, cosh, exp, log, sin, sinh, sqrt, tan};
+
+use crate::{SRID, projection::ProjectionError};
+
+// WGS84 ellipsoid
+const A: f64 = 6_378_137.0;
+const F: f64 = 1.0 / 298.257_223_563;
+
+// UTM
+const K0: f64 = 0.9996;
+const FALSE_EASTING: f64 = 500_000.0;
+const FALSE_NORTHING_SOUTH: f64 = 10_000_000.0;
+
+const UTM_NORTH: u32 = 32600;
+const UTM_SOUTH: u32 = 32700;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Crs {
+    Geographic,
+    WebMercator,
+    Utm { zone: u8, south: bool },
+}
+
+impl Crs {
+    fn from_srid(srid: SRID) -> Option<Crs> {
+        let id: u32 = srid.into();
+        match id {
+            4326 => Some(Crs::Geographic),
+            3857 => Some(Crs::WebMercator),
+            32601..=32660 => Some(Crs::Utm {
+                zone: (id - UTM_NORTH) as u8,
+                south: false,
+            }),
+            32701..=32760 => Some(Crs::Utm {
+                zone: (id - UTM_SOUTH) as u8,
+                south: true,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Convert projected coordinates into longitude/latitude in degrees
+    fn unproject(&self, x: f64, y: f64) -> (f64, f64) {
+        match self {
+            Crs::Geographic => (x, y),
+            Crs::WebMercator => {
+                let lon = x / A;
+                let lat = 2.0 * atan(exp(y / A)) - FRAC_PI_2;
+                (lon.to_degrees(), lat.to_degrees())
+            }
+            Crs::Utm { zone, south } => TRANSVERSE_MERCATOR.inverse(*zone, *south, x, y),
+        }
+    }
+
+    /// Convert longitude/latitude in degrees into projected coordinates
+    fn project(&self, lon: f64, lat: f64) -> (f64, f64) {
+        match self {
+            Crs::Geographic => (lon, lat),
+            Crs::WebMercator => {
+                let x = A * lon.to_radians();
+                let y = A * log(tan(FRAC_PI_4 + lat.to_radians() / 2.0));
+                (x, y)
+            }
+            Crs::Utm { zone, south } => TRANSVERSE_MERCATOR.forward(*zone, *south, lon, lat),
+        }
+    }
+}
+
+/// Pure-Rust transform between WGS84, Web Mercator and the WGS84 UTM zones
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Builtin {
+    from: Crs,
+    to: Crs,
+}
+
+impl Builtin {
+    pub fn new(from: SRID, to: SRID) -> Option<Builtin> {
+        Some(Builtin {
+            from: Crs::from_srid(from)?,
+            to: Crs::from_srid(to)?,
+        })
+    }
+
+    pub fn is_supported(srid: SRID) -> bool {
+        Crs::from_srid(srid).is_some()
+    }
+
+    pub fn convert(&self, x: f64, y: f64) -> (f64, f64) {
+        if self.from == self.to {
+            return (x, y);
+        }
+
+        let (lon, lat) = self.from.unproject(x, y);
+        self.to.project(lon, lat)
+    }
+
+    /// Like [`Builtin::convert`] but fails if the coordinate is outside the
+    /// domain of either projection
+    pub fn try_convert(&self, x: f64, y: f64) -> Result<(f64, f64), ProjectionError> {
+        let out_of_domain = || ProjectionError::OutOfDomain { x, y };
+
+        if !x.is_finite() || !y.is_finite() {
+            return Err(out_of_domain());
+        }
+
+        if self.from == self.to {
+            return Ok((x, y));
+        }
+
+        let (lon, lat) = self.from.unproject(x, y);
+
+        if !(-90.0..=90.0).contains(&lat) || !lon.is_finite() {
+            return Err(out_of_domain());
+        }
+
+        if self.to == Crs::WebMercator && lat.abs() == 90.0 {
+            return Err(out_of_domain());
+        }
+
+        let (x, y) = self.to.project(lon, lat);
+
+        if !x.is_finite() || !y.is_finite() {
+            return Err(out_of_domain());
+        }
+
+        Ok((x, y))
+    }
+}
+
+const TRANSVERSE_MERCATOR: TransverseMercator = TransverseMercator::new();
+
+/// Transverse Mercator using the Krüger series to sixth order in n,
+/// see Karney (2011) "Transverse Mercator with an accuracy of a few nanometers".
+struct TransverseMercator {
+    e: f64,
+    a: f64,
+    alpha: [f64; 6],
+    beta: [f64; 6],
+    conformal: [f64; 4],
+}
+
+impl TransverseMercator {
+    const fn new() -> TransverseMercator {
+        let n = F / (2.0 - F);
+        let n2 = n * n;
+        let n3 = n2 * n;
+        let n4 = n3 * n;
+        let n5 = n4 * n;
+        let n6 = n5 * n;
+
+        let e2 = F * (2.0 - F);
+        let e4 = e2 * e2;
+        let e6 = e4 * e2;
+        let e8 = e6 * e2;
+
+        TransverseMercator {
+            e: 0.081_819_190_842_621_5,
+            a: A / (1.0 + n) * (1.0 + n2 / 4.0 + n4 / 64.0 + n6 / 256.0),
+            alpha: [
+                n / 2.0 - 2.0 * n2 / 3.0 + 5.0 * n3 / 16.0 + 41.0 * n4 / 180.0 - 127.0 * n5 / 288.0
+                    + 7891.0 * n6 / 37800.0,
+                13.0 * n2 / 48.0 - 3.0 * n3 / 5.0 + 557.0 * n4 / 1440.0 + 281.0 * n5 / 630.0
+                    - 1983433.0 * n6 / 1935360.0,
+                61.0 * n3 / 240.0 - 103.0 * n4 / 140.0
+                    + 15061.0 * n5 / 26880.0
+                    + 167603.0 * n6 / 181440.0,
+                49561.0 * n4 / 161280.0 - 179.0 * n5 / 168.0 + 6601661.0 * n6 / 7257600.0,
+                34729.0 * n5 / 80640.0 - 3418889.0 * n6 / 1995840.0,
+                212378941.0 * n6 / 319334400.0,
+            ],
+            beta: [
+                n / 2.0 - 2.0 * n2 / 3.0 + 37.0 * n3 / 96.0 - n4 / 360.0 - 81.0 * n5 / 512.0
+                    + 96199.0 * n6 / 604800.0,
+                n2 / 48.0 + n3 / 15.0 - 437.0 * n4 / 1440.0 + 46.0 * n5 / 105.0
+                    - 1118711.0 * n6 / 3870720.0,
+                17.0 * n3 / 480.0 - 37.0 * n4 / 840.0 - 209.0 * n5 / 4480.0 + 5569.0 * n6 / 90720.0,
+                4397.0 * n4 / 161280.0 - 11.0 * n5 / 504.0 - 830251.0 * n6 / 7257600.0,
+                4583.0 * n5 / 161280.0 - 108847.0 * n6 / 3991680.0,
+                20648693.0 * n6 / 638668800.0,
+            ],
+            // Conformal latitude to geodetic latitude
+            conformal: [
+                e2 / 2.0 + 5.0 * e4 / 24.0 + e6 / 12.0 + 13.0 * e8 / 360.0,
+                7.0 * e4 / 48.0 + 29.0 * e6 / 240.0 + 811.0 * e8 / 11520.0,
+                7.0 * e6 / 120.0 + 81.0 * e8 / 1120.0,
+                4279.0 * e8 / 161280.0,
+            ],
+        }
+    }
+
+    fn central_meridian(zone: u8) -> f64 {
+        (zone as f64 - 1.0) * 6.0 - 180.0 + 3.0
+    }
+
+    fn forward(&self, zone: u8, south: bool, lon: f64, lat: f64) -> (f64, f64) {
+        let phi = lat.to_radians();
+        let lambda = (lon - Self::central_meridian(zone)).to_radians();
+
+        let sin_phi = sin(phi);
+        let t = sinh(atanh(sin_phi) - self.e * atanh(self.e * sin_phi));
+
+        let xi_p = atan2(t, cos(lambda));
+        let eta_p = atanh(sin(lambda) / sqrt(1.0 + t * t));
+
+        let mut xi = xi_p;
+        let mut eta = eta_p;
+
+        for (j, alpha) in self.alpha.iter().enumerate() {
+            let k = 2.0 * (j + 1) as f64;
+            xi += alpha * sin(k * xi_p) * cosh(k * eta_p);
+            eta += alpha * cos(k * xi_p) * sinh(k * eta_p);
+        }
+
+        let x = FALSE_EASTING + K0 * self.a * eta;
+        let y = K0 * self.a * xi + if south { FALSE_NORTHING_SOUTH } else { 0.0 };
+
+        (x, y)
+    }
+
+    fn inverse(&self, zone: u8, south: bool, x: f64, y: f64) -> (f64, f64) {
+        let northing = if south { y - FALSE_NORTHING_SOUTH } else { y };
+
+        let xi = northing / (K0 * self.a);
+        let eta = (x - FALSE_EASTING) / (K0 * self.a);
+
+        let mut xi_p = xi;
+        let mut eta_p = eta;
+
+        for (j, beta) in self.beta.iter().enumerate() {
+            let k = 2.0 * (j + 1) as f64;
+            xi_p -= beta * sin(k * xi) * cosh(k * eta);
+            eta_p -= beta * cos(k * xi) * sinh(k * eta);
+        }
+
+        let chi = asin(sin(xi_p) / cosh(eta_p));
+
+        let mut phi = chi;
+        for (j, c) in self.conformal.iter().enumerate() {
+            phi += c * sin(2.0 * (j + 1) as f64 * chi);
+        }
+
+        let lambda = atan2(sinh(eta_p), cos(xi_p));
+
+        (
+            Self::central_meridian(zone) + lambda.to_degrees(),
+            phi.to_degrees(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Builtin;
+    use crate::SRID;
+
+    fn assert_close(left: (f64, f64), right: (f64, f64), eps: f64) {
+        assert!(
+            (left.0 - right.0).abs() < eps && (left.1 - right.1).abs() < eps,
+            "{left:?} != {right:?}"
+        );
+    }
+
+    #[test]
+    fn web_mercator() {
+        let builtin = Builtin::new(SRID::WGS84, SRID::WEB_MERCATOR).unwrap();
+        assert_close(
+            builtin.convert(12.568337, 55.676098),
+            (1399100.8750, 7494204.2961),
+            1e-3,
+        );
+
+        let inverse = Builtin::new(SRID::WEB_MERCATOR, SRID::WGS84).unwrap();
+        assert_close(
+            inverse.convert(1399100.8750, 7494204.2961),
+            (12.568337, 55.676098),
+            1e-8,
+        );
+    }
+
+    #[test]
+    fn out_of_domain() {
+        let builtin = Builtin::new(SRID::WGS84, SRID::WEB_MERCATOR).unwrap();
+        assert!(builtin.try_convert(10.0, 90.0).is_err());
+        assert!(builtin.try_convert(10.0, 95.0).is_err());
+        assert!(builtin.try_convert(f64::NAN, 10.0).is_err());
+    }
+
+    #[test]
+    fn utm() {
+        // Copenhagen in UTM zone 33N
+        let builtin = Builtin::new(SRID::WGS84, 32633.into()).unwrap();
+        let (x, y) = builtin.convert(12.568337, 55.676098);
+        assert_close((x, y), (347_093.257, 6_172_711.482), 1e-2);
+
+        let inverse = Builtin::new(32633.into(), SRID::WGS84).unwrap();
+        assert_close(inverse.convert(x, y), (12.568337, 55.676098), 1e-9);
+
+        // Sydney in UTM zone 56S
+        let builtin = Builtin::new(SRID::WGS84, 32756.into()).unwrap();
+        let (x, y) = builtin.convert(151.2093, -33.8688);
+        assert_close((x, y), (334_368.634, 6_250_948.345), 1e-2);
+        let inverse = Builtin::new(32756.into(), SRID::WGS84).unwrap();
+        assert_close(inverse.convert(x, y), (151.2093, -33.8688), 1e-9);
+    }
+}
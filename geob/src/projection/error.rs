@@ -0,0 +1,30 @@
+use alloc::string::String;
+use core::fmt;
+
+use crate::SRID;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProjectionError {
+    /// No transform is available between the two SRIDs
+    Unsupported { from: SRID, to: SRID },
+    /// The coordinate is outside the domain of the projection
+    OutOfDomain { x: f64, y: f64 },
+    /// Error reported by proj
+    Proj(String),
+}
+
+impl fmt::Display for ProjectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectionError::Unsupported { from, to } => {
+                write!(f, "unsupported projection from {from} to {to}")
+            }
+            ProjectionError::OutOfDomain { x, y } => {
+                write!(f, "coordinate ({x}, {y}) is outside the projection domain")
+            }
+            ProjectionError::Proj(err) => write!(f, "proj: {err}"),
+        }
+    }
+}
+
+impl core::error::Error for ProjectionError {}
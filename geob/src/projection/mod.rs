@@ -0,0 +1,224 @@
+mod builtin;
+mod error;
+#[cfg(feature = "proj")]
+mod proj;
+mod transformer;
+
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use udled::{Input, bytes::FromBytesExt};
+
+use crate::{
+    GeoType, Geob, SRID,
+    envelope::Envelope,
+    types::{ENVELOPE_LEN, GEOB_HEADER, Layout, header_len},
+    util::{read_u32, write_u32},
+    writer::ToBytes,
+};
+
+pub use self::{builtin::Builtin, error::ProjectionError, transformer::Transformer};
+
+impl Geob {
+    /// Reproject the geometry into `to`.
+    ///
+    /// Transforms between WGS84, Web Mercator and the WGS84 UTM zones are handled
+    /// by the built-in engine. Everything else is delegated to proj when the `proj`
+    /// feature is enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the transform is not supported or a coordinate is outside the
+    /// domain of the projection. See [`Geob::try_project`].
+    pub fn project(&mut self, to: u32) {
+        self.try_project(to.into()).unwrap()
+    }
+
+    pub fn project_into(&self, to: u32) -> Geob {
+        let mut this = self.clone();
+
+        this.project(to);
+
+        this
+    }
+
+    /// Reproject the geometry into `to`, leaving it untouched on failure.
+    ///
+    /// Every call sets up a new transform. Use a [`Transformer`] when projecting
+    /// many geometries.
+    pub fn try_project(&mut self, to: SRID) -> Result<(), ProjectionError> {
+        Transformer::new().project(self, to)
+    }
+
+    pub fn try_project_into(&self, to: SRID) -> Result<Geob, ProjectionError> {
+        let mut this = self.clone();
+
+        this.try_project(to)?;
+
+        Ok(this)
+    }
+
+    /// Rewrite every coordinate, keeping the SRID. The envelope is recomputed
+    /// when present.
+    ///
+    /// Compact geometries are widened to f64 first, so the new coordinates are
+    /// kept exactly.
+    pub fn map_coords<F>(&mut self, func: F)
+    where
+        F: FnMut(f64, f64) -> (f64, f64),
+    {
+        if self.compact().is_some() {
+            *self = self.to_f64();
+        }
+
+        self.rewrite_coords(func);
+    }
+
+    /// Rewrite every coordinate in place, in the encoding of the geometry
+    pub(crate) fn rewrite_coords<F>(&mut self, mut func: F)
+    where
+        F: FnMut(f64, f64) -> (f64, f64),
+    {
+        let layout = self.as_ref().layout();
+        let header = self.as_ref().header_len();
+        let has_envelope = self.has_envelope();
+
+        let output = self.slice_mut();
+        transform_inner(
+            &mut |x, y| Ok::<_, Infallible>(func(x, y)),
+            &mut output[header..],
+            layout,
+        )
+        .unwrap();
+
+        if has_envelope {
+            update_envelope(output, layout);
+        }
+    }
+}
+
+/// Compact geometries are widened to f64 first
+fn transform<F, E>(geo: &mut Geob, to: SRID, mut func: F) -> Result<(), E>
+where
+    F: FnMut(f64, f64) -> Result<(f64, f64), E>,
+{
+    let source = geo.to_f64();
+    let layout = source.as_ref().layout();
+    let header = source.as_ref().header_len();
+    let mut output = Vec::from(source.slice());
+
+    write_u32(&mut output[1..], to.into(), layout.endian);
+
+    transform_inner(&mut func, &mut output[header..], layout)?;
+
+    if geo.has_envelope() {
+        update_envelope(&mut output, layout);
+    }
+
+    *geo = Geob::new(output);
+
+    Ok(())
+}
+
+/// Recompute the cached envelope of an encoded geob
+pub(crate) fn update_envelope(output: &mut [u8], layout: Layout) {
+    let header = header_len(output[0]);
+    let envelope = Envelope::compute(&output[header..], layout);
+    let mut buf = Vec::with_capacity(ENVELOPE_LEN);
+    envelope.write(&mut buf, layout.endian).unwrap();
+    output[GEOB_HEADER..GEOB_HEADER + ENVELOPE_LEN].copy_from_slice(&buf);
+}
+
+fn transform_inner<F, E>(func: &mut F, out: &mut [u8], layout: Layout) -> Result<usize, E>
+where
+    F: FnMut(f64, f64) -> Result<(f64, f64), E>,
+{
+    let ty = Input::new(&*out)
+        .parse(GeoType::byteorder(layout.endian))
+        .unwrap();
+
+    let len = match ty.value {
+        GeoType::Point => transform_coords(func, &mut out[1..], layout)?,
+        GeoType::LineString => transform_line_string(func, &mut out[1..], layout)?,
+        GeoType::Polygon => transform_polygon(func, &mut out[1..], layout)?,
+        GeoType::MultiPoint => transform_line_string(func, &mut out[1..], layout)?,
+        GeoType::MultiLineString => transform_polygon(func, &mut out[1..], layout)?,
+        GeoType::MultiPolygon => transform_multipolygon(func, &mut out[1..], layout)?,
+        GeoType::Collection => transform_collection(func, &mut out[1..], layout)?,
+    };
+
+    Ok(1 + len)
+}
+
+fn transform_coords<F, E>(func: &mut F, buf: &mut [u8], layout: Layout) -> Result<usize, E>
+where
+    F: FnMut(f64, f64) -> Result<(f64, f64), E>,
+{
+    let (x, y) = func(layout.x(buf), layout.y(buf))?;
+
+    layout.write(buf, x, y);
+
+    Ok(layout.coord_len())
+}
+
+fn transform_line_string<F, E>(func: &mut F, buf: &mut [u8], layout: Layout) -> Result<usize, E>
+where
+    F: FnMut(f64, f64) -> Result<(f64, f64), E>,
+{
+    let num = read_u32(buf, layout.endian) as usize;
+    let offset = 4;
+
+    for i in 0..num {
+        let offset = offset + (i * layout.coord_len());
+        transform_coords(func, &mut buf[offset..], layout)?;
+    }
+
+    Ok(offset + num * layout.coord_len())
+}
+
+fn transform_polygon<F, E>(func: &mut F, buf: &mut [u8], layout: Layout) -> Result<usize, E>
+where
+    F: FnMut(f64, f64) -> Result<(f64, f64), E>,
+{
+    let num = read_u32(buf, layout.endian) as usize;
+    let offset = 4;
+
+    let mut size = offset;
+
+    for _ in 0..num {
+        size += transform_line_string(func, &mut buf[size..], layout)?;
+    }
+
+    Ok(size)
+}
+
+fn transform_multipolygon<F, E>(func: &mut F, buf: &mut [u8], layout: Layout) -> Result<usize, E>
+where
+    F: FnMut(f64, f64) -> Result<(f64, f64), E>,
+{
+    let num = read_u32(buf, layout.endian) as usize;
+    let offset = 4;
+
+    let mut size = offset;
+
+    for _ in 0..num {
+        size += transform_polygon(func, &mut buf[size..], layout)?;
+    }
+
+    Ok(size)
+}
+
+fn transform_collection<F, E>(func: &mut F, buf: &mut [u8], layout: Layout) -> Result<usize, E>
+where
+    F: FnMut(f64, f64) -> Result<(f64, f64), E>,
+{
+    let num = read_u32(buf, layout.endian) as usize;
+    let offset = 4;
+
+    let mut size = offset;
+
+    for _ in 0..num {
+        size += transform_inner(func, &mut buf[size..], layout)?;
+    }
+
+    Ok(size)
+}
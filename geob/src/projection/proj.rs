@@ -0,0 +1,37 @@
+use alloc::{format, string::ToString};
+use proj::Proj;
+
+use crate::{SRID, projection::ProjectionError};
+
+struct ProjCoord(f64, f64);
+
+impl proj::Coord<f64> for ProjCoord {
+    fn x(&self) -> f64 {
+        self.0
+    }
+
+    fn y(&self) -> f64 {
+        self.1
+    }
+
+    fn from_xy(x: f64, y: f64) -> Self {
+        Self(x, y)
+    }
+}
+
+pub fn create(from: SRID, to: SRID) -> Result<Proj, ProjectionError> {
+    Proj::new_known_crs(&format!("EPSG:{}", from), &format!("EPSG:{}", to), None)
+        .map_err(|err| ProjectionError::Proj(err.to_string()))
+}
+
+pub fn convert(proj: &Proj, x: f64, y: f64) -> Result<(f64, f64), ProjectionError> {
+    let coords = proj
+        .convert(ProjCoord(x, y))
+        .map_err(|_| ProjectionError::OutOfDomain { x, y })?;
+
+    if !coords.0.is_finite() || !coords.1.is_finite() {
+        return Err(ProjectionError::OutOfDomain { x, y });
+    }
+
+    Ok((coords.0, coords.1))
+}
@@ -0,0 +1,174 @@
+use alloc::collections::{BTreeMap, btree_map::Entry};
+
+use crate::{
+    Geob, SRID,
+    projection::{Builtin, ProjectionError, transform},
+};
+
+enum Transform {
+    Builtin(Builtin),
+    #[cfg(feature = "proj")]
+    Proj(proj::Proj),
+}
+
+impl Transform {
+    fn new(from: SRID, to: SRID) -> Result<Transform, ProjectionError> {
+        if let Some(builtin) = Builtin::new(from, to) {
+            return Ok(Transform::Builtin(builtin));
+        }
+
+        #[cfg(feature = "proj")]
+        {
+            super::proj::create(from, to).map(Transform::Proj)
+        }
+
+        #[cfg(not(feature = "proj"))]
+        Err(ProjectionError::Unsupported { from, to })
+    }
+
+    fn convert(&self, x: f64, y: f64) -> Result<(f64, f64), ProjectionError> {
+        match self {
+            Transform::Builtin(builtin) => builtin.try_convert(x, y),
+            #[cfg(feature = "proj")]
+            Transform::Proj(proj) => super::proj::convert(proj, x, y),
+        }
+    }
+}
+
+/// Cache of transforms keyed by (from, to) SRID pair.
+///
+/// Creating a proj transform is expensive, so keep a `Transformer` around when
+/// projecting many geometries.
+#[derive(Default)]
+pub struct Transformer {
+    cache: BTreeMap<(SRID, SRID), Transform>,
+}
+
+impl Transformer {
+    pub fn new() -> Transformer {
+        Transformer::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    /// Returns true if a transform between the two SRIDs is available
+    pub fn supports(&mut self, from: SRID, to: SRID) -> bool {
+        self.get(from, to).is_ok()
+    }
+
+    /// Transform a single coordinate
+    pub fn convert(
+        &mut self,
+        from: SRID,
+        to: SRID,
+        x: f64,
+        y: f64,
+    ) -> Result<(f64, f64), ProjectionError> {
+        self.get(from, to)?.convert(x, y)
+    }
+
+    /// Reproject the geometry into `to`, leaving it untouched on failure
+    pub fn project(&mut self, geo: &mut Geob, to: SRID) -> Result<(), ProjectionError> {
+        let from = geo.srid();
+
+        if from == to {
+            return Ok(());
+        }
+
+        let func = self.get(from, to)?;
+
+        transform(geo, to, |x, y| func.convert(x, y))
+    }
+
+    pub fn project_into(&mut self, geo: &Geob, to: SRID) -> Result<Geob, ProjectionError> {
+        let mut geo = geo.clone();
+        self.project(&mut geo, to)?;
+        Ok(geo)
+    }
+
+    fn get(&mut self, from: SRID, to: SRID) -> Result<&Transform, ProjectionError> {
+        let transform = match self.cache.entry((from, to)) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(Transform::new(from, to)?),
+        };
+
+        Ok(transform)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Transformer;
+    use crate::{Geob, SRID, projection::ProjectionError};
+
+    #[test]
+    fn cache() {
+        let mut transformer = Transformer::new();
+        assert!(transformer.is_empty());
+
+        assert!(transformer.supports(SRID::WGS84, SRID::WEB_MERCATOR));
+        transformer
+            .convert(SRID::WGS84, SRID::WEB_MERCATOR, 12.0, 55.0)
+            .unwrap();
+        assert_eq!(transformer.len(), 1);
+
+        let mut geo = Geob::new_point(SRID::WEB_MERCATOR, 1e6, 7e6).unwrap();
+        transformer.project(&mut geo, SRID::WGS84).unwrap();
+        assert_eq!(geo.srid(), SRID::WGS84);
+        assert_eq!(transformer.len(), 2);
+
+        // Projecting into the same SRID doesn't create a transform
+        transformer.project(&mut geo, SRID::WGS84).unwrap();
+        assert_eq!(transformer.len(), 2);
+
+        transformer.clear();
+        assert!(transformer.is_empty());
+    }
+
+    #[test]
+    fn untouched_on_error() {
+        let geo = Geob::from_text("SRID=4326;LINESTRING(10 50, 10 90)").unwrap();
+
+        let mut projected = geo.clone();
+        assert_eq!(
+            projected.try_project(SRID::WEB_MERCATOR),
+            Err(ProjectionError::OutOfDomain { x: 10.0, y: 90.0 })
+        );
+        assert_eq!(projected.as_ref().bytes, geo.as_ref().bytes);
+    }
+
+    #[cfg(not(feature = "proj"))]
+    #[test]
+    fn unsupported() {
+        let from = SRID::WGS84;
+        let to = SRID::from(2154);
+
+        let mut transformer = Transformer::new();
+        assert!(!transformer.supports(from, to));
+        assert_eq!(
+            transformer.convert(from, to, 2.0, 48.0),
+            Err(ProjectionError::Unsupported { from, to })
+        );
+        // Failed transforms are not cached
+        assert!(transformer.is_empty());
+
+        let geo = Geob::new_point(from, 2.0, 48.0).unwrap();
+        let mut projected = geo.clone();
+        assert_eq!(
+            projected.try_project(to),
+            Err(ProjectionError::Unsupported { from, to })
+        );
+        assert_eq!(projected.as_ref().bytes, geo.as_ref().bytes);
+        assert!(geo.try_project_into(to).is_err());
+    }
+}
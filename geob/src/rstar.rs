@@ -1,7 +1,6 @@
-use geo::BoundingRect;
-use geo_traits::to_geo::ToGeoGeometry;
+use rstar::Envelope as _;
 
-use crate::Geob;
+use crate::{Envelope, Geob};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct RStarPoint {
@@ -61,13 +60,18 @@ impl rstar::RTreeObject for Geob {
     type Envelope = rstar::AABB<RStarPoint>;
 
     fn envelope(&self) -> Self::Envelope {
-        let rect = self.geometry().to_geometry().bounding_rect().unwrap();
+        match Geob::envelope(self) {
+            Some(envelope) => envelope.into(),
+            None => rstar::AABB::new_empty(),
+        }
+    }
+}
 
-        let min = rect.min();
-        let max = rect.max();
+impl From<Envelope> for rstar::AABB<RStarPoint> {
+    fn from(value: Envelope) -> Self {
         rstar::AABB::from_corners(
-            RStarPoint { x: min.x, y: min.y },
-            RStarPoint { x: max.x, y: max.y },
+            RStarPoint::new(value.min_x, value.min_y),
+            RStarPoint::new(value.max_x, value.max_y),
         )
     }
 }
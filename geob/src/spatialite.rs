@@ -0,0 +1,297 @@
+use alloc::vec::Vec;
+use udled::bytes::Endian;
+
+use crate::{
+    GeoType, Geob, SRID,
+    builder::GeobBuilder,
+    types::GeometryRef,
+    wkb::{Cursor, WkbError, write_coords, write_polygon},
+    writer::{BinaryWriter, ToBytes},
+};
+
+const START: u8 = 0x00;
+const MBR_END: u8 = 0x7c;
+const ENTITY: u8 = 0x69;
+const END: u8 = 0xfe;
+
+/// Start byte, byte order, srid, mbr and mbr end
+const HEADER_LEN: usize = 39;
+
+/// Returns true if the bytes look like a SpatiaLite blob geometry
+pub fn is_spatialite(bytes: &[u8]) -> bool {
+    bytes.len() >= HEADER_LEN + 5
+        && bytes[0] == START
+        && bytes[1] <= 1
+        && bytes[HEADER_LEN - 1] == MBR_END
+        && bytes[bytes.len() - 1] == END
+}
+
+impl Geob {
+    /// Decode a SpatiaLite blob geometry. Z and M ordinates are dropped and
+    /// compressed geometries are not supported.
+    pub fn from_spatialite(bytes: &[u8]) -> Result<Geob, WkbError> {
+        let mut cursor = Cursor::new(bytes);
+
+        if cursor.u8()? != START {
+            return Err(WkbError::InvalidHeader("missing start byte"));
+        }
+
+        let endian = cursor.endian()?;
+        let srid = cursor.u32(endian)?;
+
+        // The MBR is recomputed when needed
+        cursor.take(32)?;
+
+        if cursor.u8()? != MBR_END {
+            return Err(WkbError::InvalidHeader("missing mbr end byte"));
+        }
+
+        let class = Class::read(&mut cursor, endian)?;
+
+        let mut builder = Geob::builder(SRID::from(srid));
+        read_body(&mut cursor, endian, &class, &mut builder)?;
+
+        if cursor.u8()? != END {
+            return Err(WkbError::InvalidHeader("missing end byte"));
+        }
+
+        if !cursor.is_empty() {
+            return Err(WkbError::TrailingBytes);
+        }
+
+        Ok(builder.build()?)
+    }
+
+    /// Encode as a 2D SpatiaLite blob geometry. Empty geometries get a zero
+    /// MBR.
+    pub fn to_spatialite(&self) -> Vec<u8> {
+        let endian = self.endian();
+        let mut output = Vec::with_capacity(self.len() + HEADER_LEN);
+
+        output.push(START);
+        endian.write(&mut output, endian).unwrap();
+        self.srid().write(&mut output, endian).unwrap();
+
+        let mbr = self
+            .envelope()
+            .map(|e| [e.min_x, e.min_y, e.max_x, e.max_y])
+            .unwrap_or_default();
+
+        for n in mbr {
+            n.write(&mut output, endian).unwrap();
+        }
+
+        output.push(MBR_END);
+        write_geometry(&self.geometry(), &mut output, endian).unwrap();
+        output.push(END);
+
+        output
+    }
+}
+
+struct Class {
+    ty: GeoType,
+    /// Number of ordinates per coordinate
+    dims: usize,
+}
+
+impl Class {
+    fn read(cursor: &mut Cursor<'_>, endian: Endian) -> Result<Class, WkbError> {
+        let raw = cursor.u32(endian)?;
+        let (base, dims) = (raw % 1000, raw / 1000);
+
+        let ty = u8::try_from(base)
+            .ok()
+            .filter(|_| dims <= 3)
+            .and_then(GeoType::from_u8)
+            .ok_or(WkbError::UnknownType(raw))?;
+
+        let dims = match dims {
+            0 => 2,
+            1 | 2 => 3,
+            _ => 4,
+        };
+
+        Ok(Class { ty, dims })
+    }
+
+    fn coord(&self, cursor: &mut Cursor<'_>, endian: Endian) -> Result<(f64, f64), WkbError> {
+        let x = cursor.f64(endian)?;
+        let y = cursor.f64(endian)?;
+        cursor.take((self.dims - 2) * 8)?;
+        Ok((x, y))
+    }
+}
+
+fn read_body(
+    cursor: &mut Cursor<'_>,
+    endian: Endian,
+    class: &Class,
+    builder: &mut GeobBuilder,
+) -> Result<(), WkbError> {
+    match class.ty {
+        GeoType::Point => {
+            let (x, y) = class.coord(cursor, endian)?;
+            builder.point(x, y)?;
+        }
+        GeoType::LineString => {
+            builder.begin_line_string()?;
+            read_coords(cursor, endian, class, builder)?;
+            builder.end()?;
+        }
+        GeoType::Polygon => {
+            builder.begin_polygon()?;
+            for _ in 0..cursor.u32(endian)? {
+                builder.begin_ring()?;
+                read_coords(cursor, endian, class, builder)?;
+                builder.end()?;
+            }
+            builder.end()?;
+        }
+        GeoType::MultiPoint => {
+            builder.begin_multi_point()?;
+            read_entities(cursor, endian, builder, Some(GeoType::Point))?;
+            builder.end()?;
+        }
+        GeoType::MultiLineString => {
+            builder.begin_multi_line_string()?;
+            read_entities(cursor, endian, builder, Some(GeoType::LineString))?;
+            builder.end()?;
+        }
+        GeoType::MultiPolygon => {
+            builder.begin_multi_polygon()?;
+            read_entities(cursor, endian, builder, Some(GeoType::Polygon))?;
+            builder.end()?;
+        }
+        GeoType::Collection => {
+            builder.begin_collection()?;
+            read_entities(cursor, endian, builder, None)?;
+            builder.end()?;
+        }
+    }
+
+    Ok(())
+}
+
+fn read_coords(
+    cursor: &mut Cursor<'_>,
+    endian: Endian,
+    class: &Class,
+    builder: &mut GeobBuilder,
+) -> Result<(), WkbError> {
+    for _ in 0..cursor.u32(endian)? {
+        let (x, y) = class.coord(cursor, endian)?;
+        builder.coord(x, y)?;
+    }
+
+    Ok(())
+}
+
+fn read_entities(
+    cursor: &mut Cursor<'_>,
+    endian: Endian,
+    builder: &mut GeobBuilder,
+    expected: Option<GeoType>,
+) -> Result<(), WkbError> {
+    for _ in 0..cursor.u32(endian)? {
+        if cursor.u8()? != ENTITY {
+            return Err(WkbError::InvalidHeader("missing entity marker"));
+        }
+
+        let class = Class::read(cursor, endian)?;
+
+        if let Some(expected) = expected
+            && expected != class.ty
+        {
+            return Err(WkbError::UnexpectedType {
+                expected,
+                found: class.ty,
+            });
+        }
+
+        read_body(cursor, endian, &class, builder)?;
+    }
+
+    Ok(())
+}
+
+fn write_geometry<W: BinaryWriter>(
+    geo: &GeometryRef<'_>,
+    output: &mut W,
+    endian: Endian,
+) -> Result<(), W::Error> {
+    match geo {
+        GeometryRef::Point(point) => {
+            (GeoType::Point as u32).write(output, endian)?;
+            point.x().write(output, endian)?;
+            point.y().write(output, endian)?;
+        }
+        GeometryRef::LineString(line) => {
+            (GeoType::LineString as u32).write(output, endian)?;
+            write_coords(line.0, output, endian)?;
+        }
+        GeometryRef::Polygon(polygon) => {
+            (GeoType::Polygon as u32).write(output, endian)?;
+            write_polygon(*polygon, output, endian)?;
+        }
+        GeometryRef::MultiPoint(points) => {
+            (GeoType::MultiPoint as u32).write(output, endian)?;
+            (points.len() as u32).write(output, endian)?;
+            for coord in points.iter() {
+                output.write_u8(ENTITY)?;
+                (GeoType::Point as u32).write(output, endian)?;
+                coord.x().write(output, endian)?;
+                coord.y().write(output, endian)?;
+            }
+        }
+        GeometryRef::MultiLineString(lines) => {
+            (GeoType::MultiLineString as u32).write(output, endian)?;
+            (lines.len() as u32).write(output, endian)?;
+            for line in lines.iter() {
+                output.write_u8(ENTITY)?;
+                (GeoType::LineString as u32).write(output, endian)?;
+                write_coords(line, output, endian)?;
+            }
+        }
+        GeometryRef::MultiPolygon(polygons) => {
+            (GeoType::MultiPolygon as u32).write(output, endian)?;
+            (polygons.len() as u32).write(output, endian)?;
+            for polygon in polygons.iter() {
+                output.write_u8(ENTITY)?;
+                (GeoType::Polygon as u32).write(output, endian)?;
+                write_polygon(polygon, output, endian)?;
+            }
+        }
+        GeometryRef::Collection(collection) => {
+            (GeoType::Collection as u32).write(output, endian)?;
+            (collection.len() as u32).write(output, endian)?;
+            for geo in collection.iter() {
+                output.write_u8(ENTITY)?;
+                write_geometry(&geo, output, endian)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::is_spatialite;
+    use crate::Geob;
+
+    #[test]
+    fn round_trip() {
+        let geo = Geob::from_text(
+            "SRID=4326;GEOMETRYCOLLECTION(POINT(1.0 2.0), POLYGON((0.0 0.0, 1.0 0.0, 1.0 1.0, 0.0 0.0)), MULTILINESTRING((0.0 0.0, 1.0 1.0)), LINESTRING(0.0 0.0, 5.0 5.0))",
+        )
+        .unwrap();
+
+        let blob = geo.to_spatialite();
+
+        assert!(is_spatialite(&blob));
+        assert!(!is_spatialite(geo.slice()));
+        assert_eq!(blob[blob.len() - 1], 0xfe);
+        assert_eq!(Geob::from_spatialite(&blob).unwrap(), geo);
+    }
+}
@@ -3,10 +3,25 @@ use rusqlite::{
     types::{FromSql, FromSqlError, Value, ValueRef},
 };
 
-use crate::{Geob, types::GeobRef};
+#[cfg(not(feature = "canonical"))]
+use crate::types::GeobRef;
+use crate::{Geob, SRID, gpkg::is_gpkg, spatialite::is_spatialite};
 
+/// Geometries are bound in their own encoding, use [`Geob::to_compact`] to
+/// store 8 bytes per coordinate.
+///
+/// With the `canonical` feature geometries are always bound in canonical
+/// form, so blob equality in SQL matches geometric equality. See
+/// [`Geob::canonicalize`].
 impl ToSql for Geob {
     fn to_sql(&self) -> Result<rusqlite::types::ToSqlOutput<'_>> {
+        #[cfg(feature = "canonical")]
+        if !self.is_canonical() {
+            return Ok(rusqlite::types::ToSqlOutput::Owned(Value::Blob(
+                self.canonicalize().slice().to_vec(),
+            )));
+        }
+
         Ok(rusqlite::types::ToSqlOutput::Borrowed(ValueRef::Blob(
             self.slice(),
         )))
@@ -21,26 +36,54 @@ impl FromSql for Geob {
                     core::str::from_utf8(text).map_err(|err| FromSqlError::Other(err.into()))?;
                 Geob::from_text(text).map_err(|err| FromSqlError::Other(err.into()))
             }
-            ValueRef::Blob(blob) => {
-                Geob::from_bytes(blob).map_err(|err| FromSqlError::Other(err.into()))
-            }
+            ValueRef::Blob(blob) => decode_blob(blob),
             _ => Err(FromSqlError::InvalidType),
         }
     }
 }
 
+/// Decode a Geob, GeoPackage, SpatiaLite or WKB blob. Compact geobs are kept
+/// in their encoding.
+fn decode_blob(blob: &[u8]) -> rusqlite::types::FromSqlResult<Geob> {
+    if is_gpkg(blob) {
+        return Geob::from_gpkg(blob).map_err(|err| FromSqlError::Other(err.into()));
+    }
+
+    // A big endian Geob can share the SpatiaLite signature
+    if is_spatialite(blob)
+        && let Ok(geo) = Geob::from_spatialite(blob)
+    {
+        return Ok(geo);
+    }
+
+    let err = match Geob::from_bytes(blob) {
+        Ok(geo) => return Ok(geo),
+        Err(err) => err,
+    };
+
+    // A blob that is neither is most likely a corrupt geob
+    Geob::from_wkb(blob, SRID::UNKNOWN).map_err(|_| FromSqlError::Other(err.into()))
+}
+
 impl From<Geob> for Value {
     fn from(value: Geob) -> Self {
+        #[cfg(feature = "canonical")]
+        let value = value.canonicalize();
+
         Value::Blob(value.slice().to_vec())
     }
 }
 
+/// Borrows the blob as it is. A borrow can't be canonicalized, so with the
+/// `canonical` feature only the owned conversions are available.
+#[cfg(not(feature = "canonical"))]
 impl<'a> From<&'a Geob> for ValueRef<'a> {
     fn from(value: &'a Geob) -> Self {
         ValueRef::Blob(value.slice())
     }
 }
 
+#[cfg(not(feature = "canonical"))]
 impl<'a> From<GeobRef<'a>> for ValueRef<'a> {
     fn from(value: GeobRef<'a>) -> Self {
         ValueRef::Blob(value.bytes)
@@ -1,14 +1,30 @@
-use alloc::{borrow::Cow, fmt};
+use alloc::fmt;
+use core::cmp::Ordering;
 
 use crate::writer::ToBytes;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(C)]
 pub enum Unit {
+    Degree,
     Meter,
+    Foot,
+    UsSurveyFoot,
     Unknown,
 }
 
+impl Unit {
+    /// Length of one unit in meters, `None` for angular and unknown units
+    pub const fn to_meters(&self) -> Option<f64> {
+        match self {
+            Unit::Meter => Some(1.0),
+            Unit::Foot => Some(0.3048),
+            Unit::UsSurveyFoot => Some(1200.0 / 3937.0),
+            Unit::Degree | Unit::Unknown => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(C)]
 pub enum CRS {
@@ -29,39 +45,395 @@ pub enum CoordinateSystem {
 pub enum Datum {
     WGS84,
     ETRS89,
+    NAD83,
+    OSGB36,
+    RGF93,
+    CH1903Plus,
+    Amersfoort,
+    GDA94,
+    GDA2020,
     Unknown,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// Axis order as defined by the EPSG registry.
+///
+/// Geob always stores x as easting/longitude and y as northing/latitude.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(C)]
+pub enum AxisOrder {
+    EastNorth,
+    NorthEast,
+}
+
+/// Bounding box in degrees where the CRS is valid
+#[derive(Debug, Clone, PartialEq)]
+pub struct AreaOfUse {
+    pub name: &'static str,
+    pub west: f64,
+    pub south: f64,
+    pub east: f64,
+    pub north: f64,
+}
+
+impl AreaOfUse {
+    pub const WORLD: AreaOfUse = AreaOfUse::new("World", -180.0, -90.0, 180.0, 90.0);
+
+    pub const fn new(
+        name: &'static str,
+        west: f64,
+        south: f64,
+        east: f64,
+        north: f64,
+    ) -> AreaOfUse {
+        AreaOfUse {
+            name,
+            west,
+            south,
+            east,
+            north,
+        }
+    }
+
+    pub fn contains(&self, lon: f64, lat: f64) -> bool {
+        lon >= self.west && lon <= self.east && lat >= self.south && lat <= self.north
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct EPSG {
     id: u32,
-    name: Cow<'static, str>,
+    name: &'static str,
     unit: Unit,
     datum: Datum,
     crs: CRS,
     cs: CoordinateSystem,
+    axis: AxisOrder,
+    area: AreaOfUse,
 }
 
 impl EPSG {
-    pub const WEB_MERCATOR: EPSG = EPSG {
-        id: 3857,
-        name: Cow::Borrowed("Web Mercator"),
-        unit: Unit::Meter,
-        datum: Datum::WGS84,
-        crs: CRS::Projected,
-        cs: CoordinateSystem::Cart2d,
-    };
+    pub const WEB_MERCATOR: EPSG = EPSG::projected(
+        3857,
+        "WGS 84 / Pseudo-Mercator",
+        Datum::WGS84,
+        AreaOfUse::new(
+            "World between 85.06°S and 85.06°N",
+            -180.0,
+            -85.06,
+            180.0,
+            85.06,
+        ),
+    );
+
+    pub const WGS84: EPSG = EPSG::geodetic(4326, "WGS 84", Datum::WGS84, AreaOfUse::WORLD);
+
+    const fn geodetic(id: u32, name: &'static str, datum: Datum, area: AreaOfUse) -> EPSG {
+        EPSG {
+            id,
+            name,
+            unit: Unit::Degree,
+            datum,
+            crs: CRS::Geodetic,
+            cs: CoordinateSystem::Ellipsoidal,
+            axis: AxisOrder::NorthEast,
+            area,
+        }
+    }
+
+    const fn projected(id: u32, name: &'static str, datum: Datum, area: AreaOfUse) -> EPSG {
+        EPSG {
+            id,
+            name,
+            unit: Unit::Meter,
+            datum,
+            crs: CRS::Projected,
+            cs: CoordinateSystem::Cart2d,
+            axis: AxisOrder::EastNorth,
+            area,
+        }
+    }
+
+    const fn utm(id: u32, name: &'static str, datum: Datum, zone: u32, south: bool) -> EPSG {
+        let west = -180.0 + 6.0 * (zone - 1) as f64;
+        let area = if south {
+            AreaOfUse::new("Southern hemisphere", west, -80.0, west + 6.0, 0.0)
+        } else {
+            AreaOfUse::new("Northern hemisphere", west, 0.0, west + 6.0, 84.0)
+        };
 
-    pub const WGS84: EPSG = EPSG {
-        id: 4326,
-        name: Cow::Borrowed("WGS84"),
-        unit: Unit::Meter,
-        datum: Datum::WGS84,
-        crs: CRS::Projected,
-        cs: CoordinateSystem::Ellipsoidal,
+        EPSG::projected(id, name, datum, area)
+    }
+
+    const fn with_unit(mut self, unit: Unit) -> EPSG {
+        self.unit = unit;
+        self
+    }
+
+    const fn with_axis(mut self, axis: AxisOrder) -> EPSG {
+        self.axis = axis;
+        self
+    }
+
+    /// Look up an EPSG code in the built-in registry
+    pub fn get(id: u32) -> Option<&'static EPSG> {
+        let table: &'static [EPSG] = match id {
+            32601..=32660 => &WGS84_UTM_NORTH,
+            32701..=32760 => &WGS84_UTM_SOUTH,
+            25828..=25838 => &ETRS89_UTM,
+            _ => REGISTRY,
+        };
+
+        table
+            .binary_search_by_key(&id, |epsg| epsg.id)
+            .ok()
+            .map(|idx| &table[idx])
+    }
+
+    /// Iterate over all EPSG codes in the built-in registry
+    pub fn all() -> impl Iterator<Item = &'static EPSG> {
+        REGISTRY
+            .iter()
+            .chain(ETRS89_UTM.iter())
+            .chain(WGS84_UTM_NORTH.iter())
+            .chain(WGS84_UTM_SOUTH.iter())
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn srid(&self) -> SRID {
+        SRID(self.id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    pub fn datum(&self) -> Datum {
+        self.datum
+    }
+
+    pub fn crs(&self) -> CRS {
+        self.crs
+    }
+
+    pub fn coordinate_system(&self) -> CoordinateSystem {
+        self.cs
+    }
+
+    pub fn axis_order(&self) -> AxisOrder {
+        self.axis
+    }
+
+    pub fn area_of_use(&self) -> &AreaOfUse {
+        &self.area
+    }
+
+    pub fn is_geodetic(&self) -> bool {
+        self.crs == CRS::Geodetic
+    }
+
+    pub fn is_projected(&self) -> bool {
+        self.crs == CRS::Projected
+    }
+}
+
+impl PartialEq for EPSG {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for EPSG {}
+
+impl PartialOrd for EPSG {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for EPSG {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+impl fmt::Display for EPSG {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "EPSG:{} {}", self.id, self.name)
+    }
+}
+
+// Sorted by id
+static REGISTRY: &[EPSG] = &[
+    EPSG::projected(
+        2056,
+        "CH1903+ / LV95",
+        Datum::CH1903Plus,
+        AreaOfUse::new("Liechtenstein; Switzerland", 5.96, 45.82, 10.49, 47.81),
+    ),
+    EPSG::projected(
+        2154,
+        "RGF93 v1 / Lambert-93",
+        Datum::RGF93,
+        AreaOfUse::new("France", -9.86, 41.15, 10.38, 51.56),
+    ),
+    EPSG::projected(
+        2227,
+        "NAD83 / California zone 3 (ftUS)",
+        Datum::NAD83,
+        AreaOfUse::new(
+            "USA - California - SPCS - 3",
+            -123.02,
+            36.73,
+            -117.83,
+            38.71,
+        ),
+    )
+    .with_unit(Unit::UsSurveyFoot),
+    EPSG::projected(
+        2263,
+        "NAD83 / New York Long Island (ftUS)",
+        Datum::NAD83,
+        AreaOfUse::new(
+            "USA - New York - SPCS - Long Island",
+            -74.26,
+            40.47,
+            -71.8,
+            41.3,
+        ),
+    )
+    .with_unit(Unit::UsSurveyFoot),
+    EPSG::projected(
+        3035,
+        "ETRS89-extended / LAEA Europe",
+        Datum::ETRS89,
+        AreaOfUse::new(
+            "Europe - European Union (EU) countries and candidates",
+            -35.58,
+            24.6,
+            44.83,
+            84.73,
+        ),
+    )
+    .with_axis(AxisOrder::NorthEast),
+    EPSG::projected(
+        3067,
+        "ETRS89 / TM35FIN(E,N)",
+        Datum::ETRS89,
+        AreaOfUse::new("Finland", 19.08, 58.84, 31.59, 70.09),
+    ),
+    EPSG::projected(
+        3395,
+        "WGS 84 / World Mercator",
+        Datum::WGS84,
+        AreaOfUse::new("World between 80°S and 84°N", -180.0, -80.0, 180.0, 84.0),
+    ),
+    EPSG::WEB_MERCATOR,
+    EPSG::geodetic(
+        4258,
+        "ETRS89",
+        Datum::ETRS89,
+        AreaOfUse::new("Europe - ETRF by country", -16.1, 32.88, 40.18, 84.73),
+    ),
+    EPSG::geodetic(
+        4269,
+        "NAD83",
+        Datum::NAD83,
+        AreaOfUse::new("North America - NAD83", -167.65, 14.92, -40.73, 86.45),
+    ),
+    EPSG::geodetic(
+        4277,
+        "OSGB36",
+        Datum::OSGB36,
+        AreaOfUse::new(
+            "UK - Britain and UKCS 49°45'N to 61°N, 9°W to 2°E",
+            -9.01,
+            49.75,
+            2.01,
+            61.01,
+        ),
+    ),
+    EPSG::geodetic(
+        4283,
+        "GDA94",
+        Datum::GDA94,
+        AreaOfUse::new("Australia - GDA", 93.41, -60.56, 173.35, -8.47),
+    ),
+    EPSG::WGS84,
+    EPSG::projected(
+        5070,
+        "NAD83 / Conus Albers",
+        Datum::NAD83,
+        AreaOfUse::new("USA - CONUS - onshore", -124.79, 24.41, -66.91, 49.38),
+    ),
+    EPSG::geodetic(
+        7844,
+        "GDA2020",
+        Datum::GDA2020,
+        AreaOfUse::new(
+            "Australia including Lord Howe Island, Macquarie Island, Ashmore and Cartier Islands, Christmas Island, Cocos (Keeling) Islands, Norfolk Island",
+            93.41,
+            -60.55,
+            173.34,
+            -8.47,
+        ),
+    ),
+    EPSG::projected(
+        27700,
+        "OSGB36 / British National Grid",
+        Datum::OSGB36,
+        AreaOfUse::new(
+            "UK - Britain and UKCS 49°45'N to 61°N, 9°W to 2°E",
+            -9.01,
+            49.75,
+            2.01,
+            61.01,
+        ),
+    ),
+    EPSG::projected(
+        28992,
+        "Amersfoort / RD New",
+        Datum::Amersfoort,
+        AreaOfUse::new("Netherlands - onshore", 3.2, 50.75, 7.22, 53.7),
+    ),
+];
+
+macro_rules! utm_zones {
+    ($name: ident, $base: literal, $prefix: literal, $suffix: literal, $datum: expr, $south: literal; $($zone: literal)*) => {
+        static $name: [EPSG; [$($zone),*].len()] = [
+            $(
+                EPSG::utm(
+                    $base + $zone,
+                    concat!($prefix, stringify!($zone), $suffix),
+                    $datum,
+                    $zone,
+                    $south,
+                )
+            ),*
+        ];
     };
 }
 
+utm_zones!(WGS84_UTM_NORTH, 32600, "WGS 84 / UTM zone ", "N", Datum::WGS84, false;
+    1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30
+    31 32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60
+);
+
+utm_zones!(WGS84_UTM_SOUTH, 32700, "WGS 84 / UTM zone ", "S", Datum::WGS84, true;
+    1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30
+    31 32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60
+);
+
+utm_zones!(ETRS89_UTM, 25800, "ETRS89 / UTM zone ", "N", Datum::ETRS89, false;
+    28 29 30 31 32 33 34 35 36 37 38
+);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
 pub struct SRID(u32);
@@ -82,6 +454,16 @@ impl SRID {
     pub const UNKNOWN: SRID = SRID(0);
     pub const WEB_MERCATOR: SRID = SRID(EPSG::WEB_MERCATOR.id);
     pub const WGS84: SRID = SRID(EPSG::WGS84.id);
+
+    /// The registry entry for this SRID, if known
+    pub fn epsg(&self) -> Option<&'static EPSG> {
+        EPSG::get(self.0)
+    }
+
+    /// True if the coordinates are longitude/latitude on an ellipsoid
+    pub fn is_geodetic(&self) -> bool {
+        self.epsg().is_some_and(EPSG::is_geodetic)
+    }
 }
 
 impl fmt::Display for SRID {
@@ -99,3 +481,38 @@ impl ToBytes for SRID {
         self.0.write(output, endian)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{EPSG, SRID, Unit};
+
+    #[test]
+    fn registry_is_sorted() {
+        let mut ids = EPSG::all().map(EPSG::id).collect::<alloc::vec::Vec<_>>();
+        let len = ids.len();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(len, ids.len());
+
+        for epsg in EPSG::all() {
+            assert_eq!(EPSG::get(epsg.id()), Some(epsg));
+        }
+    }
+
+    #[test]
+    fn lookup() {
+        assert!(SRID::WGS84.is_geodetic());
+        assert!(!SRID::WEB_MERCATOR.is_geodetic());
+        assert_eq!(SRID::WGS84.epsg().unwrap().unit(), Unit::Degree);
+
+        let utm = SRID::from(32633).epsg().unwrap();
+        assert_eq!(utm.name(), "WGS 84 / UTM zone 33N");
+        assert!(utm.area_of_use().contains(12.5, 55.6));
+
+        assert_eq!(
+            SRID::from(25832).epsg().unwrap().name(),
+            "ETRS89 / UTM zone 32N"
+        );
+        assert_eq!(SRID::UNKNOWN.epsg(), None);
+    }
+}
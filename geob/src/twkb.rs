@@ -0,0 +1,325 @@
+use alloc::vec::Vec;
+use libm::{pow, round};
+
+use crate::{
+    GeoType, Geob, SRID,
+    builder::GeobBuilder,
+    types::{CoordSeqRef, GeometryRef, PolygonRef},
+    util::{unzigzag, write_varint, zigzag},
+    wkb::{Cursor, WkbError},
+};
+
+const FLAG_BBOX: u8 = 0b1;
+const FLAG_SIZE: u8 = 0b10;
+const FLAG_ID_LIST: u8 = 0b100;
+const FLAG_EXTENDED: u8 = 0b1000;
+const FLAG_EMPTY: u8 = 0b1_0000;
+
+impl Geob {
+    /// Encode as TWKB with `precision` decimal digits, clamped to `-8..=7`.
+    ///
+    /// Coordinates are rounded to the precision and delta encoded as varints.
+    pub fn to_twkb(&self, precision: i8) -> Vec<u8> {
+        let mut output = Vec::new();
+        write_geometry(&self.geometry(), precision.clamp(-8, 7), &mut output);
+        output
+    }
+
+    /// Decode a TWKB geometry. Z and M ordinates are dropped.
+    pub fn from_twkb(bytes: &[u8], srid: SRID) -> Result<Geob, WkbError> {
+        let mut cursor = Cursor::new(bytes);
+
+        let mut builder = Geob::builder(srid);
+        read_geometry(&mut cursor, &mut builder)?;
+
+        if !cursor.is_empty() {
+            return Err(WkbError::TrailingBytes);
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+fn read_varint(cursor: &mut Cursor<'_>) -> Result<u64, WkbError> {
+    let mut n = 0u64;
+
+    for shift in (0..64).step_by(7) {
+        let byte = cursor.u8()?;
+        n |= ((byte & 0x7f) as u64) << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok(n);
+        }
+    }
+
+    Err(WkbError::InvalidHeader("varint overflow"))
+}
+
+struct Encoder<'a> {
+    output: &'a mut Vec<u8>,
+    scale: f64,
+    prev: [i64; 2],
+}
+
+impl<'a> Encoder<'a> {
+    fn coord(&mut self, x: f64, y: f64) {
+        for (idx, n) in [x, y].into_iter().enumerate() {
+            let n = round(n * self.scale) as i64;
+            write_varint(self.output, zigzag(n.wrapping_sub(self.prev[idx])));
+            self.prev[idx] = n;
+        }
+    }
+
+    fn coords(&mut self, coords: CoordSeqRef<'_>) {
+        write_varint(self.output, coords.len() as u64);
+        for coord in coords.iter() {
+            self.coord(coord.x(), coord.y());
+        }
+    }
+
+    fn polygon(&mut self, polygon: PolygonRef<'_>) {
+        write_varint(self.output, polygon.len() as u64);
+        for ring in polygon.iter() {
+            self.coords(ring);
+        }
+    }
+}
+
+fn write_geometry(geo: &GeometryRef<'_>, precision: i8, output: &mut Vec<u8>) {
+    let kind = match geo {
+        GeometryRef::Point(_) => GeoType::Point,
+        GeometryRef::LineString(_) => GeoType::LineString,
+        GeometryRef::Polygon(_) => GeoType::Polygon,
+        GeometryRef::MultiPoint(_) => GeoType::MultiPoint,
+        GeometryRef::MultiLineString(_) => GeoType::MultiLineString,
+        GeometryRef::MultiPolygon(_) => GeoType::MultiPolygon,
+        GeometryRef::Collection(_) => GeoType::Collection,
+    };
+
+    let empty = match geo {
+        GeometryRef::Point(point) => point.x().is_nan() && point.y().is_nan(),
+        GeometryRef::Collection(collection) => collection.is_empty(),
+        _ => geo.num_points() == 0,
+    };
+
+    output.push(kind as u8 | (zigzag(precision as i64) as u8) << 4);
+    output.push(if empty { FLAG_EMPTY } else { 0 });
+
+    if empty {
+        return;
+    }
+
+    let mut encoder = Encoder {
+        output,
+        scale: pow(10.0, precision as f64),
+        prev: [0; 2],
+    };
+
+    match geo {
+        GeometryRef::Point(point) => encoder.coord(point.x(), point.y()),
+        GeometryRef::LineString(line) => encoder.coords(line.0),
+        GeometryRef::Polygon(polygon) => encoder.polygon(*polygon),
+        GeometryRef::MultiPoint(points) => encoder.coords(points.0),
+        GeometryRef::MultiLineString(lines) => {
+            write_varint(encoder.output, lines.len() as u64);
+            for line in lines.iter() {
+                encoder.coords(line);
+            }
+        }
+        GeometryRef::MultiPolygon(polygons) => {
+            write_varint(encoder.output, polygons.len() as u64);
+            for polygon in polygons.iter() {
+                encoder.polygon(polygon);
+            }
+        }
+        GeometryRef::Collection(collection) => {
+            write_varint(encoder.output, collection.len() as u64);
+            for geo in collection.iter() {
+                write_geometry(&geo, precision, encoder.output);
+            }
+        }
+    }
+}
+
+struct Decoder<'a, 'b> {
+    cursor: &'a mut Cursor<'b>,
+    scale: f64,
+    /// Number of ordinates per coordinate
+    dims: usize,
+    prev: [i64; 4],
+}
+
+impl<'a, 'b> Decoder<'a, 'b> {
+    fn coord(&mut self) -> Result<(f64, f64), WkbError> {
+        for idx in 0..self.dims {
+            let delta = unzigzag(read_varint(self.cursor)?);
+            self.prev[idx] = self.prev[idx].wrapping_add(delta);
+        }
+
+        Ok((
+            self.prev[0] as f64 / self.scale,
+            self.prev[1] as f64 / self.scale,
+        ))
+    }
+
+    fn count(&mut self) -> Result<u64, WkbError> {
+        read_varint(self.cursor)
+    }
+
+    fn coords(&mut self, builder: &mut GeobBuilder) -> Result<(), WkbError> {
+        for _ in 0..self.count()? {
+            let (x, y) = self.coord()?;
+            builder.coord(x, y)?;
+        }
+
+        Ok(())
+    }
+
+    fn polygon(&mut self, builder: &mut GeobBuilder) -> Result<(), WkbError> {
+        builder.begin_polygon()?;
+        for _ in 0..self.count()? {
+            builder.begin_ring()?;
+            self.coords(builder)?;
+            builder.end()?;
+        }
+        builder.end()?;
+
+        Ok(())
+    }
+
+    /// Number of parts of a multi geometry, skipping the id list
+    fn parts(&mut self, flags: u8) -> Result<u64, WkbError> {
+        let count = self.count()?;
+
+        if flags & FLAG_ID_LIST != 0 {
+            for _ in 0..count {
+                read_varint(self.cursor)?;
+            }
+        }
+
+        Ok(count)
+    }
+}
+
+fn read_geometry(cursor: &mut Cursor<'_>, builder: &mut GeobBuilder) -> Result<(), WkbError> {
+    let header = cursor.u8()?;
+    let flags = cursor.u8()?;
+
+    let kind = GeoType::from_u8(header & 0x0f).ok_or(WkbError::UnknownType(header as u32))?;
+    let precision = unzigzag((header >> 4) as u64);
+
+    let mut dims = 2;
+    if flags & FLAG_EXTENDED != 0 {
+        let extended = cursor.u8()?;
+        dims += (extended & 0b1) as usize + ((extended >> 1) & 0b1) as usize;
+    }
+
+    if flags & FLAG_SIZE != 0 {
+        read_varint(cursor)?;
+    }
+
+    if flags & FLAG_BBOX != 0 {
+        for _ in 0..dims * 2 {
+            read_varint(cursor)?;
+        }
+    }
+
+    let empty = flags & FLAG_EMPTY != 0;
+
+    let mut decoder = Decoder {
+        cursor,
+        scale: pow(10.0, precision as f64),
+        dims,
+        prev: [0; 4],
+    };
+
+    match kind {
+        GeoType::Point if empty => builder.point(f64::NAN, f64::NAN)?,
+        GeoType::Point => {
+            let (x, y) = decoder.coord()?;
+            builder.point(x, y)?;
+        }
+        GeoType::LineString => {
+            builder.begin_line_string()?;
+            if !empty {
+                decoder.coords(builder)?;
+            }
+            builder.end()?;
+        }
+        GeoType::Polygon if empty => {
+            builder.begin_polygon()?;
+            builder.end()?;
+        }
+        GeoType::Polygon => decoder.polygon(builder)?,
+        GeoType::MultiPoint => {
+            builder.begin_multi_point()?;
+            if !empty {
+                for _ in 0..decoder.parts(flags)? {
+                    let (x, y) = decoder.coord()?;
+                    builder.point(x, y)?;
+                }
+            }
+            builder.end()?;
+        }
+        GeoType::MultiLineString => {
+            builder.begin_multi_line_string()?;
+            if !empty {
+                for _ in 0..decoder.parts(flags)? {
+                    builder.begin_line_string()?;
+                    decoder.coords(builder)?;
+                    builder.end()?;
+                }
+            }
+            builder.end()?;
+        }
+        GeoType::MultiPolygon => {
+            builder.begin_multi_polygon()?;
+            if !empty {
+                for _ in 0..decoder.parts(flags)? {
+                    decoder.polygon(builder)?;
+                }
+            }
+            builder.end()?;
+        }
+        GeoType::Collection => {
+            builder.begin_collection()?;
+            if !empty {
+                for _ in 0..decoder.parts(flags)? {
+                    read_geometry(decoder.cursor, builder)?;
+                }
+            }
+            builder.end()?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Geob, SRID};
+
+    #[test]
+    fn round_trip() {
+        let geo = Geob::from_text(
+            "SRID=4326;LINESTRING(10.12345 59.12345, 10.12346 59.12347, 10.12350 59.12351)",
+        )
+        .unwrap();
+
+        let twkb = geo.to_twkb(5);
+        assert!(twkb.len() < geo.len() / 2);
+        assert_eq!(Geob::from_twkb(&twkb, SRID::WGS84).unwrap(), geo);
+
+        // POINT(1 2) at precision 0, from the TWKB spec
+        let point = Geob::from_twkb(&[0x01, 0x00, 0x02, 0x04], SRID::UNKNOWN).unwrap();
+        assert_eq!(point.as_point().map(|p| (p.x(), p.y())), Some((1.0, 2.0)));
+        assert_eq!(point.to_twkb(0), [0x01, 0x00, 0x02, 0x04]);
+
+        let collection = Geob::from_text(
+            "SRID=0;GEOMETRYCOLLECTION(POINT(1.5 2.5), POLYGON((0.0 0.0, 10.0 0.0, 10.0 10.0, 0.0 0.0)))",
+        )
+        .unwrap();
+        let twkb = collection.to_twkb(1);
+        assert_eq!(Geob::from_twkb(&twkb, SRID::UNKNOWN).unwrap(), collection);
+    }
+}
@@ -1,24 +1,32 @@
 use alloc::fmt;
 use udled::{
-    AsSlice, Input, TokenizerExt,
-    bytes::{Endian, FromBytes, FromBytesExt},
+    AsSlice, Input,
+    bytes::{Endian, FromBytes},
 };
 
 use crate::{
-    GeoType,
-    types::{geometry::GeometryRef, types::TYPE_LEN},
+    types::{
+        coords::counted,
+        geometry::GeometryRef,
+        layout::{Decode, Layout},
+    },
     util::read_u32,
 };
 
+// Bytecode: len(u32) 0..len(type(u8) geometry)
 #[derive(Clone, Copy)]
 pub struct CollectionRef<'a> {
     bytes: &'a [u8],
-    endian: Endian,
+    pub(super) layout: Layout,
 }
 
 impl<'a> CollectionRef<'a> {
     pub fn len(&self) -> usize {
-        read_u32(&self.bytes[TYPE_LEN..], self.endian) as _
+        read_u32(self.bytes, self.layout.endian) as _
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
     pub fn get(&self, idx: usize) -> Option<GeometryRef<'a>> {
@@ -26,55 +34,43 @@ impl<'a> CollectionRef<'a> {
             return None;
         }
 
-        let mut input = Input::new(self.bytes);
-
-        if idx == 0 {
-            input
-                .parse(GeometryRef::byteorder(self.endian))
-                .map(|m| m.value)
-                .ok()
-        } else {
-            for i in 0..=idx {
-                if i == idx {
-                    return input
-                        .parse(GeometryRef::byteorder(self.endian))
-                        .map(|m| m.value)
-                        .ok();
-                } else {
-                    input.parse(GeometryRef::byteorder(self.endian)).ok();
-                }
-            }
-
-            None
+        let mut input = Input::new(&self.bytes[4..]);
+
+        for _ in 0..idx {
+            input.eat(GeometryRef::layout(self.layout)).ok()?;
+        }
+
+        input
+            .parse(GeometryRef::layout(self.layout))
+            .map(|m| m.value)
+            .ok()
+    }
+
+    pub fn iter(&self) -> CollectionIter<'a> {
+        CollectionIter {
+            input: &self.bytes[4..],
+            len: self.len(),
+            idx: 0,
+            layout: self.layout,
         }
     }
 }
 
 impl<'a> fmt::Debug for CollectionRef<'a> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        todo!()
+        f.debug_list().entries(self.iter()).finish()
     }
 }
 
 impl<'a> PartialEq for CollectionRef<'a> {
     fn eq(&self, other: &Self) -> bool {
-        todo!()
-    }
-}
-
-struct CollectionType;
-
-impl<'input> FromBytes<'input, &'input [u8]> for CollectionType {
-    fn parse(
-        reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
-        byteorder: Endian,
-    ) -> udled::Result<Self> {
-        let ty = reader.parse(GeoType::byteorder(byteorder))?;
-        if ty.value != GeoType::Collection {
-            return Err(reader.error("Expected a collection"))?;
+        if self.len() != other.len() {
+            return false;
         }
 
-        Ok(Self)
+        self.iter()
+            .zip(other.iter())
+            .all(|(left, right)| left == right)
     }
 }
 
@@ -83,19 +79,42 @@ impl<'a> FromBytes<'a, &'a [u8]> for CollectionRef<'a> {
         reader: &mut udled::Reader<'_, 'a, &'a [u8]>,
         byteorder: Endian,
     ) -> udled::Result<Self> {
-        let start = reader.parse(CollectionType::byteorder(byteorder).spanned())?;
-        let len = reader.parse(u32::byteorder(byteorder))?;
-        let coords = reader.parse(
-            GeometryRef::byteorder(byteorder)
-                .repeat(len.value as _)
-                .spanned(),
-        )?;
+        Self::decode(reader, Layout::new(byteorder))
+    }
+}
 
-        let span = start + coords;
+impl<'a> Decode<'a> for CollectionRef<'a> {
+    fn decode(reader: &mut udled::Reader<'_, 'a, &'a [u8]>, layout: Layout) -> udled::Result<Self> {
+        let span = counted(reader, GeometryRef::layout(layout), layout.endian)?;
 
         Ok(CollectionRef {
             bytes: reader.buffer().sliced(span).unwrap(),
-            endian: byteorder,
+            layout,
         })
     }
 }
+
+pub struct CollectionIter<'a> {
+    input: &'a [u8],
+    len: usize,
+    idx: usize,
+    layout: Layout,
+}
+
+impl<'a> Iterator for CollectionIter<'a> {
+    type Item = GeometryRef<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.idx == self.len {
+            return None;
+        }
+
+        let mut input = Input::new(self.input);
+        let geo = input.parse(GeometryRef::layout(self.layout)).ok()?;
+
+        self.input = &self.input[geo.span.end..];
+        self.idx += 1;
+
+        Some(geo.value)
+    }
+}
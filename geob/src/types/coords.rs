@@ -1,27 +1,31 @@
 use core::fmt;
 
 use udled::{
-    AsSlice, Input, TokenizerExt,
+    AsSlice, Input, Span, Tokenizer, TokenizerExt,
     bytes::{Endian, FromBytes, FromBytesExt},
 };
 
-use crate::util::{read_f64, read_u32};
+use crate::{
+    types::layout::{Decode, Layout},
+    util::read_u32,
+};
 
 #[derive(Clone, Copy)]
 pub struct CoordRef<'a> {
     data: &'a [u8],
-    endian: Endian,
+    pub(super) layout: Layout,
 }
 
 impl<'a> CoordRef<'a> {
+    /// Compact coordinates are decoded on every call
     #[inline]
     pub fn x(&self) -> f64 {
-        read_f64(self.data, self.endian)
+        self.layout.x(self.data)
     }
 
     #[inline]
     pub fn y(&self) -> f64 {
-        read_f64(&self.data[8..], self.endian)
+        self.layout.y(self.data)
     }
 }
 
@@ -45,10 +49,24 @@ impl<'input> FromBytes<'input, &'input [u8]> for CoordRef<'input> {
         reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
         endian: Endian,
     ) -> udled::Result<Self> {
-        let x = reader.parse((f64::byteorder(endian), f64::byteorder(endian)).slice())?;
+        Self::decode(reader, Layout::new(endian))
+    }
+}
+
+impl<'input> Decode<'input> for CoordRef<'input> {
+    fn decode(
+        reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
+        layout: Layout,
+    ) -> udled::Result<Self> {
+        let endian = layout.endian;
+        let x = match layout.compact {
+            None => reader.parse((f64::byteorder(endian), f64::byteorder(endian)).slice())?,
+            Some(_) => reader.parse((u32::byteorder(endian), u32::byteorder(endian)).slice())?,
+        };
+
         Ok(CoordRef {
             data: x.value,
-            endian,
+            layout,
         })
     }
 }
@@ -57,12 +75,16 @@ impl<'input> FromBytes<'input, &'input [u8]> for CoordRef<'input> {
 #[derive(Clone, Copy)]
 pub struct CoordSeqRef<'a> {
     data: &'a [u8],
-    endian: Endian,
+    pub(super) layout: Layout,
 }
 
 impl<'a> CoordSeqRef<'a> {
     pub fn len(&self) -> usize {
-        read_u32(self.data, self.endian) as _
+        read_u32(self.data, self.layout.endian) as _
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
     pub fn get(&self, idx: usize) -> Option<CoordRef<'a>> {
@@ -70,9 +92,9 @@ impl<'a> CoordSeqRef<'a> {
             return None;
         }
 
-        let byte_idx = size_of::<u32>() + idx * 16;
+        let byte_idx = size_of::<u32>() + idx * self.layout.coord_len();
         Input::new(&self.data[byte_idx..])
-            .parse(CoordRef::byteorder(self.endian))
+            .parse(CoordRef::layout(self.layout))
             .ok()
             .map(|m| m.value)
     }
@@ -117,23 +139,43 @@ impl<'input> FromBytes<'input, &'input [u8]> for CoordSeqRef<'input> {
         reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
         byteorder: Endian,
     ) -> udled::Result<Self> {
-        let len = reader.parse(u32::byteorder(byteorder))?;
-
-        let coords = reader.parse(
-            CoordRef::byteorder(byteorder)
-                .repeat(len.value as _)
-                .spanned(),
-        )?;
+        Self::decode(reader, Layout::new(byteorder))
+    }
+}
 
-        let span = len.span + coords;
+impl<'input> Decode<'input> for CoordSeqRef<'input> {
+    fn decode(
+        reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
+        layout: Layout,
+    ) -> udled::Result<Self> {
+        let span = counted(reader, CoordRef::layout(layout), layout.endian)?;
 
         Ok(CoordSeqRef {
             data: reader.buffer().sliced(span).unwrap(),
-            endian: byteorder,
+            layout,
         })
     }
 }
 
+/// Span of a `u32` count followed by that many elements. Unlike
+/// [`TokenizerExt::repeat`] a count of zero matches no elements.
+pub(crate) fn counted<'input, T>(
+    reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
+    tokenizer: T,
+    byteorder: Endian,
+) -> udled::Result<Span>
+where
+    T: Tokenizer<'input, &'input [u8]>,
+{
+    let len = reader.parse(u32::byteorder(byteorder))?;
+
+    for _ in 0..len.value {
+        reader.eat(&tokenizer)?;
+    }
+
+    Ok(Span::new(len.span.start, reader.position()))
+}
+
 pub struct CoordSeqIter<'a> {
     seg: CoordSeqRef<'a>,
     len: usize,
@@ -158,12 +200,16 @@ impl<'a> Iterator for CoordSeqIter<'a> {
 #[derive(Clone, Copy)]
 pub struct MultiCoordSeqRef<'a> {
     data: &'a [u8],
-    endian: Endian,
+    pub(super) layout: Layout,
 }
 
 impl<'a> MultiCoordSeqRef<'a> {
     pub fn len(&self) -> usize {
-        read_u32(self.data, self.endian) as _
+        read_u32(self.data, self.layout.endian) as _
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
     pub fn get(&self, idx: usize) -> Option<CoordSeqRef<'a>> {
@@ -175,18 +221,18 @@ impl<'a> MultiCoordSeqRef<'a> {
 
         if idx == 0 {
             input
-                .parse(CoordSeqRef::byteorder(self.endian))
+                .parse(CoordSeqRef::layout(self.layout))
                 .map(|m| m.value)
                 .ok()
         } else {
             for i in 0..=idx {
                 if i == idx {
                     return input
-                        .parse(CoordSeqRef::byteorder(self.endian))
+                        .parse(CoordSeqRef::layout(self.layout))
                         .map(|m| m.value)
                         .ok();
                 } else {
-                    input.eat(CoordSeqRef::byteorder(self.endian)).ok();
+                    input.eat(CoordSeqRef::layout(self.layout)).ok();
                 }
             }
 
@@ -196,16 +242,17 @@ impl<'a> MultiCoordSeqRef<'a> {
 
     pub fn iter(&self) -> MultiCoordSeqIter<'a> {
         MultiCoordSeqIter {
-            seg: *self,
+            input: &self.data[4..],
             len: self.len(),
             idx: 0,
+            layout: self.layout,
         }
     }
 }
 
 impl<'a> MultiCoordSeqRef<'a> {
-    pub(crate) const fn new(data: &'a [u8], endian: Endian) -> MultiCoordSeqRef<'a> {
-        MultiCoordSeqRef { data, endian }
+    pub(crate) const fn new(data: &'a [u8], layout: Layout) -> MultiCoordSeqRef<'a> {
+        MultiCoordSeqRef { data, layout }
     }
 }
 
@@ -240,27 +287,29 @@ impl<'input> FromBytes<'input, &'input [u8]> for MultiCoordSeqRef<'input> {
         reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
         byteorder: Endian,
     ) -> udled::Result<Self> {
-        let len = reader.parse(u32::byteorder(byteorder))?;
-
-        let coords = reader.parse(
-            CoordSeqRef::byteorder(byteorder)
-                .repeat(len.value as _)
-                .spanned(),
-        )?;
+        Self::decode(reader, Layout::new(byteorder))
+    }
+}
 
-        let span = len.span + coords;
+impl<'input> Decode<'input> for MultiCoordSeqRef<'input> {
+    fn decode(
+        reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
+        layout: Layout,
+    ) -> udled::Result<Self> {
+        let span = counted(reader, CoordSeqRef::layout(layout), layout.endian)?;
 
         Ok(MultiCoordSeqRef {
             data: reader.buffer().sliced(span).unwrap(),
-            endian: byteorder,
+            layout,
         })
     }
 }
 
 pub struct MultiCoordSeqIter<'a> {
-    seg: MultiCoordSeqRef<'a>,
+    input: &'a [u8],
     len: usize,
     idx: usize,
+    layout: Layout,
 }
 
 impl<'a> Iterator for MultiCoordSeqIter<'a> {
@@ -270,23 +319,45 @@ impl<'a> Iterator for MultiCoordSeqIter<'a> {
             return None;
         }
 
-        let next = self.seg.get(self.idx);
+        let size = coord_seq_len(self.input, self.layout);
+        let (data, rest) = self.input.split_at(size);
 
+        self.input = rest;
         self.idx += 1;
 
-        next
+        Some(CoordSeqRef {
+            data,
+            layout: self.layout,
+        })
+    }
+}
+
+/// Byte length of the encoded coordinate sequence at the start of `buf`
+fn coord_seq_len(buf: &[u8], layout: Layout) -> usize {
+    size_of::<u32>() + read_u32(buf, layout.endian) as usize * layout.coord_len()
+}
+
+/// Byte length of the encoded sequence of coordinate sequences at the start of `buf`
+fn multi_coord_seq_len(buf: &[u8], layout: Layout) -> usize {
+    let num = read_u32(buf, layout.endian) as usize;
+    let mut size = size_of::<u32>();
+
+    for _ in 0..num {
+        size += coord_seq_len(&buf[size..], layout);
     }
+
+    size
 }
 
 #[derive(Clone, Copy)]
 pub struct CoordSegSegSegRef<'a> {
     data: &'a [u8],
-    endian: Endian,
+    pub(super) layout: Layout,
 }
 
 impl<'a> CoordSegSegSegRef<'a> {
     pub fn len(&self) -> usize {
-        read_u32(self.data, self.endian) as _
+        read_u32(self.data, self.layout.endian) as _
     }
 
     pub fn get(&self, idx: usize) -> Option<MultiCoordSeqRef<'a>> {
@@ -298,18 +369,18 @@ impl<'a> CoordSegSegSegRef<'a> {
 
         if idx == 0 {
             input
-                .parse(MultiCoordSeqRef::byteorder(self.endian))
+                .parse(MultiCoordSeqRef::layout(self.layout))
                 .map(|m| m.value)
                 .ok()
         } else {
             for i in 0..=idx {
                 if i == idx {
                     return input
-                        .parse(MultiCoordSeqRef::byteorder(self.endian))
+                        .parse(MultiCoordSeqRef::layout(self.layout))
                         .map(|m| m.value)
                         .ok();
                 } else {
-                    input.parse(MultiCoordSeqRef::byteorder(self.endian)).ok();
+                    input.parse(MultiCoordSeqRef::layout(self.layout)).ok();
                 }
             }
 
@@ -319,9 +390,10 @@ impl<'a> CoordSegSegSegRef<'a> {
 
     pub fn iter(&self) -> CoordSegSegSegIter<'a> {
         CoordSegSegSegIter {
-            seg: *self,
+            input: &self.data[4..],
             len: self.len(),
             idx: 0,
+            layout: self.layout,
         }
     }
 }
@@ -357,26 +429,29 @@ impl<'input> FromBytes<'input, &'input [u8]> for CoordSegSegSegRef<'input> {
         reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
         byteorder: Endian,
     ) -> udled::Result<Self> {
-        let len = reader.parse(u32::byteorder(byteorder))?;
-        let coords = reader.parse(
-            MultiCoordSeqRef::byteorder(byteorder)
-                .repeat(len.value as _)
-                .spanned(),
-        )?;
+        Self::decode(reader, Layout::new(byteorder))
+    }
+}
 
-        let span = len.span + coords;
+impl<'input> Decode<'input> for CoordSegSegSegRef<'input> {
+    fn decode(
+        reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
+        layout: Layout,
+    ) -> udled::Result<Self> {
+        let span = counted(reader, MultiCoordSeqRef::layout(layout), layout.endian)?;
 
         Ok(CoordSegSegSegRef {
             data: reader.buffer().sliced(span).unwrap(),
-            endian: byteorder,
+            layout,
         })
     }
 }
 
 pub struct CoordSegSegSegIter<'a> {
-    seg: CoordSegSegSegRef<'a>,
+    input: &'a [u8],
     len: usize,
     idx: usize,
+    layout: Layout,
 }
 
 impl<'a> Iterator for CoordSegSegSegIter<'a> {
@@ -386,10 +461,15 @@ impl<'a> Iterator for CoordSegSegSegIter<'a> {
             return None;
         }
 
-        let next = self.seg.get(self.idx);
+        let size = multi_coord_seq_len(self.input, self.layout);
+        let (data, rest) = self.input.split_at(size);
 
+        self.input = rest;
         self.idx += 1;
 
-        next
+        Some(MultiCoordSeqRef {
+            data,
+            layout: self.layout,
+        })
     }
 }
@@ -1,14 +1,14 @@
-use udled::{
-    Input,
-    bytes::{Endian, FromBytes, FromBytesExt},
-};
+use udled::bytes::{Endian, FromBytes, FromBytesExt};
 
 use crate::{
-    GeoType,
+    Error, GeoType,
     types::{
-        LineStringRef, MultiLineStringRef, MultiPointRef, MultiPolygonRef, Point, PointRef,
-        PolygonRef, collection::CollectionRef,
+        LineStringRef, MultiLineStringRef, MultiPointRef, MultiPolygonRef, PointRef, PolygonRef,
+        TYPE_LEN,
+        collection::CollectionRef,
+        layout::{Decode, Layout},
     },
+    validate::validate_geometry,
 };
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -23,52 +23,116 @@ pub enum GeometryRef<'a> {
 }
 
 impl<'a> GeometryRef<'a> {
-    pub fn validate(bytes: &[u8], endian: Endian) -> Result<(), udled::Error> {
-        Input::new(bytes).eat(GeometryRef::byteorder(endian))
+    pub fn kind(&self) -> GeoType {
+        match self {
+            GeometryRef::Point(_) => GeoType::Point,
+            GeometryRef::LineString(_) => GeoType::LineString,
+            GeometryRef::MultiPoint(_) => GeoType::MultiPoint,
+            GeometryRef::MultiLineString(_) => GeoType::MultiLineString,
+            GeometryRef::Polygon(_) => GeoType::Polygon,
+            GeometryRef::MultiPolygon(_) => GeoType::MultiPolygon,
+            GeometryRef::Collection(_) => GeoType::Collection,
+        }
+    }
+
+    pub(crate) fn coord_layout(&self) -> Layout {
+        match self {
+            GeometryRef::Point(point) => point.0.layout,
+            GeometryRef::LineString(line) => line.0.layout,
+            GeometryRef::MultiPoint(points) => points.0.layout,
+            GeometryRef::MultiLineString(lines) => lines.layout,
+            GeometryRef::Polygon(polygon) => polygon.0.layout,
+            GeometryRef::MultiPolygon(polygons) => polygons.0.layout,
+            GeometryRef::Collection(collection) => collection.layout,
+        }
+    }
+
+    /// Number of bytes of the encoded geometry, starting at the type byte
+    pub fn encoded_len(&self) -> usize {
+        const COUNT: usize = 4;
+        let coord = self.coord_layout().coord_len();
+
+        let coords = |len: usize| COUNT + len * coord;
+
+        TYPE_LEN
+            + match self {
+                GeometryRef::Point(_) => coord,
+                GeometryRef::LineString(line) => coords(line.len()),
+                GeometryRef::MultiPoint(points) => coords(points.len()),
+                GeometryRef::Polygon(polygon) => {
+                    COUNT + polygon.iter().map(|ring| coords(ring.len())).sum::<usize>()
+                }
+                GeometryRef::MultiLineString(lines) => {
+                    COUNT + lines.iter().map(|line| coords(line.len())).sum::<usize>()
+                }
+                GeometryRef::MultiPolygon(polygons) => {
+                    COUNT
+                        + polygons
+                            .iter()
+                            .map(|polygon| {
+                                COUNT + polygon.iter().map(|ring| coords(ring.len())).sum::<usize>()
+                            })
+                            .sum::<usize>()
+                }
+                GeometryRef::Collection(collection) => {
+                    COUNT
+                        + collection
+                            .iter()
+                            .map(|geo| geo.encoded_len())
+                            .sum::<usize>()
+                }
+            }
+    }
+
+    /// Check that `bytes` hold exactly one geometry, starting at the type byte
+    pub fn validate(bytes: &[u8], endian: Endian) -> Result<(), Error> {
+        validate_geometry(bytes, endian)
     }
 }
 
 impl<'a> FromBytes<'a, &'a [u8]> for GeometryRef<'a> {
     fn parse(
         reader: &mut udled::Reader<'_, 'a, &'a [u8]>,
-        byteorder: udled::bytes::Endian,
+        byteorder: Endian,
     ) -> udled::Result<Self> {
-        let ty = reader.parse(GeoType::byteorder(byteorder))?.value;
+        Self::decode(reader, Layout::new(byteorder))
+    }
+}
+
+impl<'a> Decode<'a> for GeometryRef<'a> {
+    fn decode(reader: &mut udled::Reader<'_, 'a, &'a [u8]>, layout: Layout) -> udled::Result<Self> {
+        let ty = reader.parse(GeoType::byteorder(layout.endian))?.value;
 
         let geo = match ty {
-            GeoType::Point => GeometryRef::Point(
-                reader
-                    .parse(PointRef::byteorder(byteorder))
-                    .map(|i| i.value)?,
-            ),
+            GeoType::Point => {
+                GeometryRef::Point(reader.parse(PointRef::layout(layout)).map(|i| i.value)?)
+            }
             GeoType::LineString => GeometryRef::LineString(
                 reader
-                    .parse(LineStringRef::byteorder(byteorder))
-                    .map(|i| i.value)?,
-            ),
-            GeoType::Polygon => GeometryRef::Polygon(
-                reader
-                    .parse(PolygonRef::byteorder(byteorder))
+                    .parse(LineStringRef::layout(layout))
                     .map(|i| i.value)?,
             ),
+            GeoType::Polygon => {
+                GeometryRef::Polygon(reader.parse(PolygonRef::layout(layout)).map(|i| i.value)?)
+            }
             GeoType::MultiPoint => GeometryRef::MultiPoint(
                 reader
-                    .parse(MultiPointRef::byteorder(byteorder))
+                    .parse(MultiPointRef::layout(layout))
                     .map(|i| i.value)?,
             ),
             GeoType::MultiLineString => GeometryRef::MultiLineString(
                 reader
-                    .parse(MultiLineStringRef::byteorder(byteorder))
+                    .parse(MultiLineStringRef::layout(layout))
                     .map(|i| i.value)?,
             ),
             GeoType::MultiPolygon => GeometryRef::MultiPolygon(
                 reader
-                    .parse(MultiPolygonRef::byteorder(byteorder))
+                    .parse(MultiPolygonRef::layout(layout))
                     .map(|i| i.value)?,
             ),
             GeoType::Collection => GeometryRef::Collection(
                 reader
-                    .parse(CollectionRef::byteorder(byteorder))
+                    .parse(CollectionRef::layout(layout))
                     .map(|i| i.value)?,
             ),
         };
@@ -76,8 +140,3 @@ impl<'a> FromBytes<'a, &'a [u8]> for GeometryRef<'a> {
         Ok(geo)
     }
 }
-
-#[derive(Clone)]
-pub enum Geometry {
-    Point(Point),
-}
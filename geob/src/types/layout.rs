@@ -0,0 +1,145 @@
+use core::marker::PhantomData;
+
+use udled::{Item, Span, Tokenizer, bytes::Endian};
+
+use crate::{
+    compact::Compact,
+    types::{FLAG_F32, FLAG_QUANTIZED, GRID_LEN, header_len},
+    util::{get_endian, read_f32, read_f64, read_u32, write_f32, write_f64, write_u32},
+};
+
+/// Byte order and coordinate encoding of a geob, given by the header
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Layout {
+    pub endian: Endian,
+    pub compact: Option<Compact>,
+}
+
+impl Layout {
+    /// Coordinates as two f64
+    pub const fn new(endian: Endian) -> Layout {
+        Layout {
+            endian,
+            compact: None,
+        }
+    }
+
+    /// Layout of the geob starting at `bytes`, `None` if the flags are
+    /// invalid or the header is truncated
+    pub fn read(bytes: &[u8]) -> Option<Layout> {
+        let flags = *bytes.first()?;
+        let endian = get_endian(flags)?;
+
+        let compact = if flags & FLAG_F32 != 0 {
+            Some(Compact::F32)
+        } else if flags & FLAG_QUANTIZED != 0 {
+            let end = header_len(flags);
+            let grid = bytes.get(end - GRID_LEN..end)?;
+            Some(Compact::Quantized {
+                scale: read_f64(grid, endian),
+                origin: (read_f64(&grid[8..], endian), read_f64(&grid[16..], endian)),
+            })
+        } else {
+            None
+        };
+
+        Some(Layout { endian, compact })
+    }
+
+    /// Bytes per coordinate
+    pub const fn coord_len(&self) -> usize {
+        match self.compact {
+            None => 16,
+            Some(_) => 8,
+        }
+    }
+
+    #[inline]
+    pub fn x(&self, buf: &[u8]) -> f64 {
+        match self.compact {
+            None => read_f64(buf, self.endian),
+            Some(Compact::F32) => read_f32(buf, self.endian) as f64,
+            Some(Compact::Quantized { scale, origin }) => {
+                origin.0 + read_u32(buf, self.endian) as i32 as f64 * scale
+            }
+        }
+    }
+
+    #[inline]
+    pub fn y(&self, buf: &[u8]) -> f64 {
+        match self.compact {
+            None => read_f64(&buf[8..], self.endian),
+            Some(Compact::F32) => read_f32(&buf[4..], self.endian) as f64,
+            Some(Compact::Quantized { scale, origin }) => {
+                origin.1 + read_u32(&buf[4..], self.endian) as i32 as f64 * scale
+            }
+        }
+    }
+
+    /// Write a coordinate in place. Compact values are rounded to the
+    /// encoding, callers check the range first.
+    pub fn write(&self, buf: &mut [u8], x: f64, y: f64) {
+        match self.compact {
+            None => {
+                write_f64(buf, x, self.endian);
+                write_f64(&mut buf[8..], y, self.endian);
+            }
+            Some(Compact::F32) => {
+                write_f32(buf, x as f32, self.endian);
+                write_f32(&mut buf[4..], y as f32, self.endian);
+            }
+            Some(Compact::Quantized { scale, origin }) => {
+                let step = |n: f64, origin: f64| libm::round((n - origin) / scale) as i32 as u32;
+                write_u32(buf, step(x, origin.0), self.endian);
+                write_u32(&mut buf[4..], step(y, origin.1), self.endian);
+            }
+        }
+    }
+}
+
+/// Decode a borrowed geometry part laid out as a [`Layout`]. The
+/// [`FromBytes`](udled::bytes::FromBytes) impls decode f64 coordinates.
+pub(crate) trait Decode<'input>: Sized {
+    fn decode(
+        reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
+        layout: Layout,
+    ) -> udled::Result<Self>;
+
+    fn layout(layout: Layout) -> Decoder<Self> {
+        Decoder {
+            layout,
+            ph: PhantomData,
+        }
+    }
+}
+
+/// Tokenizer for a [`Decode`] type, the layout counterpart of
+/// [`Binary`](udled::bytes::Binary)
+pub(crate) struct Decoder<T> {
+    layout: Layout,
+    ph: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for Decoder<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Decoder<T> {}
+
+impl<'input, T> Tokenizer<'input, &'input [u8]> for Decoder<T>
+where
+    T: Decode<'input>,
+{
+    type Token = Item<T>;
+
+    fn to_token(
+        &self,
+        reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
+    ) -> Result<Self::Token, udled::Error> {
+        let start = reader.position();
+        let item = T::decode(reader, self.layout)?;
+        Ok(Item::new(Span::new(start, reader.position()), item))
+    }
+}
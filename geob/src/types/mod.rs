@@ -1,26 +1,32 @@
 mod collection;
 mod coords;
 mod geometry;
+mod layout;
 mod line_string;
 mod muli_line_string;
 mod multi_point;
 mod multi_polygon;
 mod point;
 mod polygon;
+#[allow(clippy::module_inception)]
 mod types;
 
 use alloc::fmt;
 use udled::{
-    AsSlice, Input, Tokenizer,
+    AsSlice, Input, Tokenizer, TokenizerExt,
     bytes::{Endian, FromBytesExt},
 };
 
 use crate::{
-    Geob,
+    Error, Geob,
+    compact::Compact,
+    envelope::Envelope,
     util::{get_endian, read_u32},
+    validate::validate,
     wkt,
 };
 
+pub(crate) use self::layout::{Decode, Layout};
 pub use self::{
     collection::CollectionRef,
     coords::CoordRef,
@@ -49,21 +55,63 @@ impl<'a> GeobRef<'a> {
         unsafe { Geob::from_bytes_unchecked(self.bytes) }
     }
 
-    pub fn from_bytes(bytes: &'a [u8]) -> udled::Result<GeobRef<'a>> {
-        let mut input = Input::new(bytes.as_ref());
-        input.parse(GeobParser)
+    /// Borrow `bytes` after checking that they hold exactly one geometry
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<GeobRef<'a>, Error> {
+        validate(bytes)?;
+        Ok(GeobRef { bytes })
     }
 }
 
 impl<'a> GeobRef<'a> {
+    /// Panics if the bytes are corrupt, see [`GeobRef::try_geometry`]
     pub fn geometry(&self) -> GeometryRef<'a> {
-        let endian = get_endian(self.bytes[0]).unwrap();
-        Input::new(&self.bytes[5..])
-            .parse(GeometryRef::byteorder(endian))
+        Input::new(self.body())
+            .parse(GeometryRef::layout(self.layout()))
             .map(|m| m.value)
             .unwrap()
     }
 
+    pub fn kind(&self) -> GeoType {
+        GeoType::from_u8(self.body()[0]).unwrap()
+    }
+
+    /// Returns true if the header carries a cached bounding box
+    pub fn has_envelope(&self) -> bool {
+        self.bytes[0] & FLAG_ENVELOPE != 0
+    }
+
+    /// Bounding box of the geometry, `None` if it has no coordinates.
+    ///
+    /// O(1) when the header carries a cached bounding box, otherwise every
+    /// coordinate is visited.
+    pub fn envelope(&self) -> Option<Envelope> {
+        if self.has_envelope() {
+            Envelope::read(&self.bytes[GEOB_HEADER..], self.endian())
+        } else {
+            Envelope::compute(self.body(), self.layout())
+        }
+    }
+
+    /// Compact encoding of the coordinates, `None` for f64
+    pub fn compact(&self) -> Option<Compact> {
+        self.layout().compact
+    }
+
+    /// Number of bytes needed to write this geob, header included. Use to
+    /// size the buffer of a [`SliceWriter`](crate::writer::SliceWriter).
+    pub fn encoded_len(&self) -> usize {
+        self.header_len() + self.geometry().encoded_len()
+    }
+
+    pub fn header_len(&self) -> usize {
+        header_len(self.bytes[0])
+    }
+
+    /// The encoded geometry, starting at the type byte
+    pub(crate) fn body(&self) -> &'a [u8] {
+        &self.bytes[self.header_len()..]
+    }
+
     pub fn srid(&self) -> u32 {
         read_u32(&self.bytes[1..], self.endian())
     }
@@ -71,6 +119,10 @@ impl<'a> GeobRef<'a> {
     pub fn endian(&self) -> Endian {
         get_endian(self.bytes[0]).unwrap()
     }
+
+    pub(crate) fn layout(&self) -> Layout {
+        Layout::read(self.bytes).unwrap()
+    }
 }
 
 impl<'a> fmt::Debug for GeobRef<'a> {
@@ -85,12 +137,6 @@ impl<'a> fmt::Display for GeobRef<'a> {
     }
 }
 
-impl<'a> PartialEq for GeobRef<'a> {
-    fn eq(&self, other: &Self) -> bool {
-        self.srid() == other.srid() && self.geometry() == other.geometry()
-    }
-}
-
 pub struct GeobParser;
 
 impl<'input> Tokenizer<'input, &'input [u8]> for GeobParser {
@@ -101,12 +147,31 @@ impl<'input> Tokenizer<'input, &'input [u8]> for GeobParser {
         reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
     ) -> Result<Self::Token, udled::Error> {
         let start = reader.position();
-        let endian = get_endian(reader.read()?)
-            .ok_or_else(|| reader.error("Expected 0 (big) or 1 (little) endian"))?;
+        let flags = reader.read()?;
+        let endian =
+            get_endian(flags).ok_or_else(|| reader.error("Expected valid header flags"))?;
 
         reader.eat(u32::byteorder(endian))?;
 
-        let geo = reader.parse(GeometryRef::byteorder(endian))?;
+        if flags & FLAG_ENVELOPE != 0 {
+            reader.eat(f64::byteorder(endian).repeat(4))?;
+        }
+
+        let compact = if flags & FLAG_F32 != 0 {
+            Some(Compact::F32)
+        } else if flags & FLAG_QUANTIZED != 0 {
+            let scale = reader.parse(f64::byteorder(endian))?.value;
+            let x = reader.parse(f64::byteorder(endian))?.value;
+            let y = reader.parse(f64::byteorder(endian))?.value;
+            Some(Compact::Quantized {
+                scale,
+                origin: (x, y),
+            })
+        } else {
+            None
+        };
+
+        let geo = reader.parse(GeometryRef::layout(Layout { endian, compact }))?;
 
         let span = geo.span.with_start(start);
 
@@ -1,59 +1,75 @@
 use alloc::fmt;
 use udled::{
     TokenizerExt,
-    bytes::{Endian, FromBytes, FromBytesExt},
+    bytes::{Endian, FromBytes},
 };
 
 use crate::{
-    types::coords::{CoordSeqRef, MultiCoordSeqIter, MultiCoordSeqRef},
+    types::{
+        coords::{CoordSeqRef, MultiCoordSeqIter, MultiCoordSeqRef},
+        layout::{Decode, Layout},
+    },
     util::read_u32,
 };
 
 #[derive(Clone, Copy)]
 pub struct MultiLineStringRef<'a> {
     bytes: &'a [u8],
-    endian: Endian,
+    pub(super) layout: Layout,
 }
 
 impl<'a> MultiLineStringRef<'a> {
     pub fn len(&self) -> usize {
-        read_u32(self.bytes, self.endian) as _
+        read_u32(self.bytes, self.layout.endian) as _
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
     pub fn get(&self, idx: usize) -> Option<CoordSeqRef<'a>> {
-        MultiCoordSeqRef::new(self.bytes, self.endian).get(idx)
+        MultiCoordSeqRef::new(self.bytes, self.layout).get(idx)
     }
 
     pub fn iter(&self) -> MultiCoordSeqIter<'a> {
-        MultiCoordSeqRef::new(self.bytes, self.endian).iter()
+        MultiCoordSeqRef::new(self.bytes, self.layout).iter()
     }
 }
 
 impl<'a> fmt::Debug for MultiLineStringRef<'a> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("MultiLineStringRef")
-            .field("lines", &MultiCoordSeqRef::new(self.bytes, self.endian))
+            .field("lines", &MultiCoordSeqRef::new(self.bytes, self.layout))
             .finish()
     }
 }
 
 impl<'a> PartialEq for MultiLineStringRef<'a> {
     fn eq(&self, other: &Self) -> bool {
-        MultiCoordSeqRef::new(self.bytes, self.endian)
-            == MultiCoordSeqRef::new(&other.bytes, other.endian)
+        MultiCoordSeqRef::new(self.bytes, self.layout)
+            == MultiCoordSeqRef::new(other.bytes, other.layout)
     }
 }
 
 impl<'input> FromBytes<'input, &'input [u8]> for MultiLineStringRef<'input> {
     fn parse(
         reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
-        byteorder: udled::bytes::Endian,
+        byteorder: Endian,
+    ) -> udled::Result<Self> {
+        Self::decode(reader, Layout::new(byteorder))
+    }
+}
+
+impl<'input> Decode<'input> for MultiLineStringRef<'input> {
+    fn decode(
+        reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
+        layout: Layout,
     ) -> udled::Result<Self> {
-        let bytes = reader.parse(MultiCoordSeqRef::byteorder(byteorder).slice())?;
+        let bytes = reader.parse(MultiCoordSeqRef::layout(layout).slice())?;
 
         Ok(Self {
             bytes: bytes.value,
-            endian: byteorder,
+            layout,
         })
     }
 }
@@ -1,20 +1,25 @@
 use alloc::fmt;
-use udled::bytes::{Endian, FromBytes, FromBytesExt};
+use udled::bytes::{Endian, FromBytes};
 
 use crate::types::{
     CoordRef,
     coords::{CoordSeqIter, CoordSeqRef},
+    layout::{Decode, Layout},
 };
 
 #[derive(Clone, Copy, PartialEq)]
 #[repr(transparent)]
-pub struct MultiPointRef<'a>(CoordSeqRef<'a>);
+pub struct MultiPointRef<'a>(pub(crate) CoordSeqRef<'a>);
 
 impl<'a> MultiPointRef<'a> {
     pub fn len(&self) -> usize {
         self.0.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     pub fn get(&self, idx: usize) -> Option<CoordRef<'a>> {
         self.0.get(idx)
     }
@@ -37,7 +42,16 @@ impl<'input> FromBytes<'input, &'input [u8]> for MultiPointRef<'input> {
         reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
         byteorder: Endian,
     ) -> udled::Result<Self> {
-        let bytes = reader.parse(CoordSeqRef::byteorder(byteorder))?;
+        Self::decode(reader, Layout::new(byteorder))
+    }
+}
+
+impl<'input> Decode<'input> for MultiPointRef<'input> {
+    fn decode(
+        reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
+        layout: Layout,
+    ) -> udled::Result<Self> {
+        let bytes = reader.parse(CoordSeqRef::layout(layout))?;
 
         Ok(Self(bytes.value))
     }
@@ -1,20 +1,32 @@
 use alloc::fmt;
-use udled::bytes::{FromBytes, FromBytesExt};
+use udled::bytes::{Endian, FromBytes};
 
-use crate::types::coords::{CoordSegSegSegRef, MultiCoordSeqRef};
+use crate::types::{
+    PolygonRef,
+    coords::{CoordSegSegSegRef, MultiCoordSeqRef},
+    layout::{Decode, Layout},
+};
 
 #[derive(Clone, Copy, PartialEq)]
 #[repr(transparent)]
-pub struct MultiPolygonRef<'a>(CoordSegSegSegRef<'a>);
+pub struct MultiPolygonRef<'a>(pub(crate) CoordSegSegSegRef<'a>);
 
 impl<'a> MultiPolygonRef<'a> {
     pub fn len(&self) -> usize {
         self.0.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     pub fn get(&self, idx: usize) -> Option<MultiCoordSeqRef<'a>> {
         self.0.get(idx)
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = PolygonRef<'a>> + 'a {
+        self.0.iter().map(PolygonRef)
+    }
 }
 
 impl<'a> fmt::Debug for MultiPolygonRef<'a> {
@@ -28,9 +40,18 @@ impl<'a> fmt::Debug for MultiPolygonRef<'a> {
 impl<'input> FromBytes<'input, &'input [u8]> for MultiPolygonRef<'input> {
     fn parse(
         reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
-        byteorder: udled::bytes::Endian,
+        byteorder: Endian,
+    ) -> udled::Result<Self> {
+        Self::decode(reader, Layout::new(byteorder))
+    }
+}
+
+impl<'input> Decode<'input> for MultiPolygonRef<'input> {
+    fn decode(
+        reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
+        layout: Layout,
     ) -> udled::Result<Self> {
-        let bytes = reader.parse(CoordSegSegSegRef::byteorder(byteorder))?;
+        let bytes = reader.parse(CoordSegSegSegRef::layout(layout))?;
 
         Ok(Self(bytes.value))
     }
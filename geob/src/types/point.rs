@@ -1,9 +1,12 @@
 use alloc::fmt;
-use udled::bytes::{Endian, FromBytes, FromBytesExt};
+use udled::bytes::{Endian, FromBytes};
 
 use crate::{
     GeoType,
-    types::coords::CoordRef,
+    types::{
+        coords::CoordRef,
+        layout::{Decode, Layout},
+    },
     util::{get_endian, read_f64, write_f64},
     writer::ToBytes,
 };
@@ -40,7 +43,16 @@ impl<'input> FromBytes<'input, &'input [u8]> for PointRef<'input> {
         reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
         byteorder: Endian,
     ) -> udled::Result<Self> {
-        let bytes = reader.parse(CoordRef::byteorder(byteorder))?;
+        Self::decode(reader, Layout::new(byteorder))
+    }
+}
+
+impl<'input> Decode<'input> for PointRef<'input> {
+    fn decode(
+        reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
+        layout: Layout,
+    ) -> udled::Result<Self> {
+        let bytes = reader.parse(CoordRef::layout(layout))?;
 
         Ok(Self(bytes.value))
     }
@@ -1,6 +1,9 @@
-use crate::types::coords::{CoordSeqRef, MultiCoordSeqRef};
+use crate::types::{
+    coords::{CoordSeqRef, MultiCoordSeqIter, MultiCoordSeqRef},
+    layout::{Decode, Layout},
+};
 use alloc::fmt;
-use udled::bytes::{FromBytes, FromBytesExt};
+use udled::bytes::{Endian, FromBytes};
 
 #[derive(Clone, Copy, PartialEq)]
 #[repr(transparent)]
@@ -11,6 +14,10 @@ impl<'a> PolygonRef<'a> {
         self.0.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     pub fn get(&self, idx: usize) -> Option<CoordSeqRef<'a>> {
         self.0.get(idx)
     }
@@ -22,6 +29,11 @@ impl<'a> PolygonRef<'a> {
     pub fn interior(&self, idx: usize) -> Option<CoordSeqRef<'a>> {
         self.0.get(1 + idx)
     }
+
+    /// Iterate over the rings, starting with the exterior
+    pub fn iter(&self) -> MultiCoordSeqIter<'a> {
+        self.0.iter()
+    }
 }
 
 impl<'a> fmt::Debug for PolygonRef<'a> {
@@ -35,9 +47,18 @@ impl<'a> fmt::Debug for PolygonRef<'a> {
 impl<'input> FromBytes<'input, &'input [u8]> for PolygonRef<'input> {
     fn parse(
         reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
-        byteorder: udled::bytes::Endian,
+        byteorder: Endian,
+    ) -> udled::Result<Self> {
+        Self::decode(reader, Layout::new(byteorder))
+    }
+}
+
+impl<'input> Decode<'input> for PolygonRef<'input> {
+    fn decode(
+        reader: &mut udled::Reader<'_, 'input, &'input [u8]>,
+        layout: Layout,
     ) -> udled::Result<Self> {
-        let bytes = reader.parse(MultiCoordSeqRef::byteorder(byteorder))?;
+        let bytes = reader.parse(MultiCoordSeqRef::layout(layout))?;
 
         Ok(Self(bytes.value))
     }
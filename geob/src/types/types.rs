@@ -15,6 +15,41 @@ pub const SRID_LEN: usize = size_of::<u32>();
 
 pub const GEOB_HEADER: usize = ENDIAN_LEN + SRID_LEN;
 
+pub const ENVELOPE_LEN: usize = 4 * size_of::<f64>();
+
+/// Length of the quantized grid: scale, origin x and origin y
+pub const GRID_LEN: usize = 3 * size_of::<f64>();
+
+/// Header flag: set for little endian, cleared for big endian
+pub const FLAG_LITTLE_ENDIAN: u8 = 0b01;
+
+/// Header flag: a bounding box (min x, min y, max x, max y) follows the SRID
+pub const FLAG_ENVELOPE: u8 = 0b10;
+
+/// Header flag: coordinates are two f32
+pub const FLAG_F32: u8 = 0b0100;
+
+/// Header flag: coordinates are two i32 steps on a grid that follows the
+/// envelope
+pub const FLAG_QUANTIZED: u8 = 0b1000;
+
+pub(crate) const FLAGS: u8 = FLAG_LITTLE_ENDIAN | FLAG_ENVELOPE | FLAG_F32 | FLAG_QUANTIZED;
+
+/// Length of the header, up to the geometry type byte
+pub const fn header_len(flags: u8) -> usize {
+    let mut len = GEOB_HEADER;
+
+    if flags & FLAG_ENVELOPE != 0 {
+        len += ENVELOPE_LEN;
+    }
+
+    if flags & FLAG_QUANTIZED != 0 {
+        len += GRID_LEN;
+    }
+
+    len
+}
+
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(u8)]
@@ -1,6 +1,9 @@
+use alloc::vec::Vec;
 use byteorder::{BigEndian, ByteOrder, LittleEndian};
 use udled::bytes::Endian;
 
+use crate::types::{FLAG_F32, FLAG_LITTLE_ENDIAN, FLAG_QUANTIZED, FLAGS};
+
 pub fn read_f64(buf: &[u8], endian: Endian) -> f64 {
     match endian {
         Endian::Big => BigEndian::read_f64(buf),
@@ -8,6 +11,13 @@ pub fn read_f64(buf: &[u8], endian: Endian) -> f64 {
     }
 }
 
+pub fn read_f32(buf: &[u8], endian: Endian) -> f32 {
+    match endian {
+        Endian::Big => BigEndian::read_f32(buf),
+        Endian::Lt => LittleEndian::read_f32(buf),
+    }
+}
+
 pub fn read_u32(buf: &[u8], endian: Endian) -> u32 {
     match endian {
         Endian::Big => BigEndian::read_u32(buf),
@@ -22,6 +32,13 @@ pub fn write_u32(buf: &mut [u8], n: u32, endian: Endian) {
     }
 }
 
+pub fn write_f32(buf: &mut [u8], n: f32, endian: Endian) {
+    match endian {
+        Endian::Big => BigEndian::write_f32(buf, n),
+        Endian::Lt => LittleEndian::write_f32(buf, n),
+    }
+}
+
 pub fn write_f64(buf: &mut [u8], n: f64, endian: Endian) {
     match endian {
         Endian::Big => BigEndian::write_f64(buf, n),
@@ -29,10 +46,32 @@ pub fn write_f64(buf: &mut [u8], n: f64, endian: Endian) {
     }
 }
 
+/// Endian from the header flags, `None` if unknown flags are set
 pub fn get_endian(i: u8) -> Option<Endian> {
-    match i {
-        0 => Some(Endian::Big),
-        1 => Some(Endian::Lt),
-        _ => return None,
+    if i & !FLAGS != 0 || i & (FLAG_F32 | FLAG_QUANTIZED) == FLAG_F32 | FLAG_QUANTIZED {
+        return None;
+    }
+
+    if i & FLAG_LITTLE_ENDIAN != 0 {
+        Some(Endian::Lt)
+    } else {
+        Some(Endian::Big)
+    }
+}
+
+pub fn zigzag(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+pub fn unzigzag(n: u64) -> i64 {
+    ((n >> 1) as i64) ^ -((n & 1) as i64)
+}
+
+/// Write `n` as a LEB128 varint
+pub fn write_varint(output: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        output.push((n as u8) | 0x80);
+        n >>= 7;
     }
+    output.push(n as u8);
 }
@@ -0,0 +1,287 @@
+use udled::bytes::Endian;
+
+use crate::{
+    Error, GeoType, Geob, GeobRef,
+    builder::MAX_DEPTH,
+    types::{GEOB_HEADER, GeometryRef, Layout, SRID_LEN, header_len},
+    util::{get_endian, read_u32},
+};
+
+const COUNT: usize = 4;
+
+/// Check that `bytes` hold exactly one geob encoded geometry
+pub(crate) fn validate(bytes: &[u8]) -> Result<(), Error> {
+    header(bytes)?;
+
+    let offset = header_len(bytes[0]);
+    if offset > bytes.len() {
+        return Err(Error::Truncated {
+            needed: offset - GEOB_HEADER,
+            offset: GEOB_HEADER,
+        });
+    }
+
+    let layout = Layout::read(bytes).unwrap();
+    let mut reader = Reader {
+        bytes,
+        offset,
+        endian: layout.endian,
+        coord: layout.coord_len(),
+    };
+
+    reader.geometry(0)?;
+    reader.finish()
+}
+
+/// Check that `bytes` hold exactly one encoded geometry, starting at the type
+/// byte
+pub(crate) fn validate_geometry(bytes: &[u8], endian: Endian) -> Result<(), Error> {
+    let mut reader = Reader {
+        bytes,
+        offset: 0,
+        endian,
+        coord: Layout::new(endian).coord_len(),
+    };
+
+    reader.geometry(0)?;
+    reader.finish()
+}
+
+fn header(bytes: &[u8]) -> Result<Endian, Error> {
+    let Some(&flags) = bytes.first() else {
+        return Err(Error::Truncated {
+            needed: GEOB_HEADER,
+            offset: 0,
+        });
+    };
+
+    let endian = get_endian(flags).ok_or(Error::InvalidEndian(flags))?;
+
+    if bytes.len() < GEOB_HEADER {
+        return Err(Error::Truncated {
+            needed: SRID_LEN,
+            offset: 1,
+        });
+    }
+
+    Ok(endian)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    endian: Endian,
+    /// Bytes per coordinate
+    coord: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, needed: usize) -> Result<&'a [u8], Error> {
+        let remaining = self.bytes.len() - self.offset;
+        if needed > remaining {
+            return Err(Error::Truncated {
+                needed,
+                offset: self.offset,
+            });
+        }
+
+        let bytes = &self.bytes[self.offset..self.offset + needed];
+        self.offset += needed;
+        Ok(bytes)
+    }
+
+    /// Read an element count, failing with `error` if the remaining input
+    /// can't hold `count` elements of at least `min_len` bytes
+    fn count(&mut self, min_len: usize, error: fn(u32, usize) -> Error) -> Result<u32, Error> {
+        let offset = self.offset;
+        let count = read_u32(self.take(COUNT)?, self.endian);
+
+        if (count as usize).saturating_mul(min_len) > self.bytes.len() - self.offset {
+            return Err(error(count, offset));
+        }
+
+        Ok(count)
+    }
+
+    fn coords(&mut self) -> Result<(), Error> {
+        let offset = self.offset;
+        let count = read_u32(self.take(COUNT)?, self.endian) as usize;
+
+        self.take(count.saturating_mul(self.coord))
+            .map_err(|_| Error::Truncated {
+                needed: COUNT + count.saturating_mul(self.coord),
+                offset,
+            })?;
+
+        Ok(())
+    }
+
+    fn polygon(&mut self) -> Result<(), Error> {
+        let rings = self.count(COUNT, |count, offset| Error::InvalidRingCount {
+            count,
+            offset,
+        })?;
+        for _ in 0..rings {
+            self.coords()?;
+        }
+        Ok(())
+    }
+
+    fn members(&mut self, min_len: usize) -> Result<u32, Error> {
+        self.count(min_len, |count, offset| Error::InvalidCount {
+            count,
+            offset,
+        })
+    }
+
+    fn geometry(&mut self, depth: usize) -> Result<(), Error> {
+        let offset = self.offset;
+        if depth > MAX_DEPTH {
+            return Err(Error::TooDeep { offset });
+        }
+
+        let tag = self.take(1)?[0];
+        let Some(kind) = GeoType::from_u8(tag) else {
+            return Err(Error::UnknownType { tag, offset });
+        };
+
+        match kind {
+            GeoType::Point => self.take(self.coord).map(|_| ()),
+            GeoType::LineString | GeoType::MultiPoint => self.coords(),
+            GeoType::Polygon => self.polygon(),
+            GeoType::MultiLineString => {
+                for _ in 0..self.members(COUNT)? {
+                    self.coords()?;
+                }
+                Ok(())
+            }
+            GeoType::MultiPolygon => {
+                for _ in 0..self.members(COUNT)? {
+                    self.polygon()?;
+                }
+                Ok(())
+            }
+            GeoType::Collection => {
+                for _ in 0..self.members(1 + COUNT)? {
+                    self.geometry(depth + 1)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn finish(&self) -> Result<(), Error> {
+        if self.offset != self.bytes.len() {
+            return Err(Error::TrailingBytes {
+                offset: self.offset,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<'a> GeobRef<'a> {
+    /// Endian of the header, fails if the flags are invalid
+    pub fn try_endian(&self) -> Result<Endian, Error> {
+        header(self.bytes)
+    }
+
+    /// Type of the root geometry, without validating the rest of the blob
+    pub fn try_kind(&self) -> Result<GeoType, Error> {
+        header(self.bytes)?;
+
+        let offset = header_len(self.bytes[0]);
+        let Some(&tag) = self.bytes.get(offset) else {
+            return Err(Error::Truncated { needed: 1, offset });
+        };
+
+        GeoType::from_u8(tag).ok_or(Error::UnknownType { tag, offset })
+    }
+
+    /// Validate the whole blob and return the root geometry. Use for bytes
+    /// that did not come through [`GeobRef::from_bytes`].
+    pub fn try_geometry(&self) -> Result<GeometryRef<'a>, Error> {
+        validate(self.bytes)?;
+        Ok(self.geometry())
+    }
+}
+
+impl Geob {
+    /// See [`GeobRef::try_endian`]
+    pub fn try_endian(&self) -> Result<Endian, Error> {
+        self.as_ref().try_endian()
+    }
+
+    /// See [`GeobRef::try_kind`]
+    pub fn try_kind(&self) -> Result<GeoType, Error> {
+        self.as_ref().try_kind()
+    }
+
+    /// See [`GeobRef::try_geometry`]
+    pub fn try_geometry(&self) -> Result<GeometryRef<'_>, Error> {
+        self.as_ref().try_geometry()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use crate::{Error, GeoType, Geob, GeobRef};
+
+    #[test]
+    fn errors() {
+        let geo = Geob::from_text(
+            "SRID=4326;GEOMETRYCOLLECTION(POINT(1.0 2.0), POLYGON((0.0 0.0, 1.0 0.0, 1.0 1.0, 0.0 0.0)))",
+        )
+        .unwrap();
+        let bytes = geo.as_ref().bytes;
+
+        assert!(GeobRef::from_bytes(bytes).is_ok());
+        assert_eq!(
+            GeobRef::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(Error::Truncated {
+                needed: 4 + 4 * 16,
+                offset: 32
+            })
+        );
+        assert_eq!(
+            GeobRef::from_bytes(&[bytes, &[0][..]].concat()),
+            Err(Error::TrailingBytes {
+                offset: bytes.len()
+            })
+        );
+        assert_eq!(GeobRef::from_bytes(&[16]), Err(Error::InvalidEndian(16)));
+        assert_eq!(GeobRef::from_bytes(&[12]), Err(Error::InvalidEndian(12)));
+        assert_eq!(
+            GeobRef::from_bytes(&[1, 0, 0]),
+            Err(Error::Truncated {
+                needed: 4,
+                offset: 1
+            })
+        );
+
+        let mut corrupt: Vec<u8> = bytes.to_vec();
+        corrupt[10] = 9;
+        assert_eq!(
+            Geob::from_bytes(corrupt.clone()),
+            Err(Error::UnknownType { tag: 9, offset: 10 })
+        );
+
+        // Ring count of the polygon
+        corrupt[10] = 1;
+        corrupt[28..32].copy_from_slice(&u32::MAX.to_ne_bytes());
+        assert_eq!(
+            Geob::from_bytes(corrupt.clone()),
+            Err(Error::InvalidRingCount {
+                count: u32::MAX,
+                offset: 28
+            })
+        );
+
+        let corrupt = GeobRef { bytes: &corrupt };
+        assert_eq!(corrupt.try_kind(), Ok(GeoType::Collection));
+        assert!(corrupt.try_geometry().is_err());
+        assert!(geo.try_geometry().is_ok());
+    }
+}
@@ -0,0 +1,410 @@
+use alloc::vec::Vec;
+use core::{convert::Infallible, fmt};
+use udled::bytes::Endian;
+
+use crate::{
+    GeoType, Geob, SRID,
+    builder::{BuilderError, GeobBuilder},
+    types::{CoordSeqRef, GeometryRef, PolygonRef},
+    util::{get_endian, read_f64, read_u32},
+    writer::{BinaryWriter, ToBytes},
+};
+
+// EWKB flags
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WkbError {
+    UnexpectedEof,
+    InvalidByteOrder(u8),
+    UnknownType(u32),
+    /// A multi geometry contains a child of the wrong type
+    UnexpectedType {
+        expected: GeoType,
+        found: GeoType,
+    },
+    TrailingBytes,
+    /// Invalid container header, like the GeoPackage or SpatiaLite header
+    InvalidHeader(&'static str),
+    Builder(BuilderError<Infallible>),
+}
+
+impl fmt::Display for WkbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WkbError::UnexpectedEof => write!(f, "unexpected end of input"),
+            WkbError::InvalidByteOrder(n) => write!(f, "invalid byte order: {n}"),
+            WkbError::UnknownType(n) => write!(f, "unknown geometry type: {n}"),
+            WkbError::UnexpectedType { expected, found } => {
+                write!(f, "expected {expected}, found {found}")
+            }
+            WkbError::TrailingBytes => write!(f, "trailing bytes after geometry"),
+            WkbError::InvalidHeader(msg) => write!(f, "invalid header: {msg}"),
+            WkbError::Builder(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl core::error::Error for WkbError {}
+
+impl From<BuilderError<Infallible>> for WkbError {
+    fn from(value: BuilderError<Infallible>) -> Self {
+        WkbError::Builder(value)
+    }
+}
+
+impl Geob {
+    /// Decode ISO WKB or PostGIS EWKB. Z and M ordinates are dropped.
+    ///
+    /// An SRID embedded in EWKB takes precedence over `srid`.
+    pub fn from_wkb(bytes: &[u8], srid: SRID) -> Result<Geob, WkbError> {
+        let mut cursor = Cursor::new(bytes);
+        let geo = read_wkb(&mut cursor, srid)?;
+
+        if !cursor.is_empty() {
+            return Err(WkbError::TrailingBytes);
+        }
+
+        Ok(geo)
+    }
+
+    /// Encode as 2D ISO WKB in the byte order of the geometry
+    pub fn to_wkb(&self) -> Vec<u8> {
+        let mut output = Vec::new();
+        write_wkb(&self.geometry(), &mut output, self.endian()).unwrap();
+        output
+    }
+}
+
+/// Decode a WKB geometry from the cursor, leaving any trailing bytes
+pub(crate) fn read_wkb(cursor: &mut Cursor<'_>, srid: SRID) -> Result<Geob, WkbError> {
+    let header = Header::read(cursor)?;
+
+    let srid = header.srid.map(SRID::from).unwrap_or(srid);
+
+    let mut builder = Geob::builder(srid);
+    read_body(cursor, &header, &mut builder)?;
+
+    Ok(builder.build()?)
+}
+
+pub(crate) struct Cursor<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Cursor<'a> {
+    pub(crate) fn new(buf: &'a [u8]) -> Cursor<'a> {
+        Cursor { buf }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    pub(crate) fn take(&mut self, n: usize) -> Result<&'a [u8], WkbError> {
+        if self.buf.len() < n {
+            return Err(WkbError::UnexpectedEof);
+        }
+
+        let (head, tail) = self.buf.split_at(n);
+        self.buf = tail;
+
+        Ok(head)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, WkbError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u32(&mut self, endian: Endian) -> Result<u32, WkbError> {
+        Ok(read_u32(self.take(4)?, endian))
+    }
+
+    pub(crate) fn f64(&mut self, endian: Endian) -> Result<f64, WkbError> {
+        Ok(read_f64(self.take(8)?, endian))
+    }
+
+    pub(crate) fn endian(&mut self) -> Result<Endian, WkbError> {
+        match self.u8()? {
+            n @ (0 | 1) => Ok(get_endian(n).unwrap()),
+            n => Err(WkbError::InvalidByteOrder(n)),
+        }
+    }
+}
+
+struct Header {
+    endian: Endian,
+    ty: GeoType,
+    /// Number of ordinates per coordinate
+    dims: usize,
+    srid: Option<u32>,
+}
+
+impl Header {
+    fn read(cursor: &mut Cursor<'_>) -> Result<Header, WkbError> {
+        let endian = cursor.endian()?;
+        let raw = cursor.u32(endian)?;
+
+        let srid = if raw & EWKB_SRID != 0 {
+            Some(cursor.u32(endian)?)
+        } else {
+            None
+        };
+
+        let code = raw & 0x0fff_ffff;
+        let (base, iso) = (code % 1000, code / 1000);
+
+        let z = raw & EWKB_Z != 0 || iso == 1 || iso == 3;
+        let m = raw & EWKB_M != 0 || iso == 2 || iso == 3;
+
+        let ty = u8::try_from(base)
+            .ok()
+            .filter(|_| iso <= 3)
+            .and_then(GeoType::from_u8)
+            .ok_or(WkbError::UnknownType(raw))?;
+
+        Ok(Header {
+            endian,
+            ty,
+            dims: 2 + z as usize + m as usize,
+            srid,
+        })
+    }
+
+    fn coord(&self, cursor: &mut Cursor<'_>) -> Result<(f64, f64), WkbError> {
+        let x = cursor.f64(self.endian)?;
+        let y = cursor.f64(self.endian)?;
+        cursor.take((self.dims - 2) * 8)?;
+        Ok((x, y))
+    }
+}
+
+fn read_body(
+    cursor: &mut Cursor<'_>,
+    header: &Header,
+    builder: &mut GeobBuilder,
+) -> Result<(), WkbError> {
+    let endian = header.endian;
+
+    match header.ty {
+        GeoType::Point => {
+            let (x, y) = header.coord(cursor)?;
+            builder.point(x, y)?;
+        }
+        GeoType::LineString => {
+            builder.begin_line_string()?;
+            read_coords(cursor, header, builder)?;
+            builder.end()?;
+        }
+        GeoType::Polygon => {
+            builder.begin_polygon()?;
+            for _ in 0..cursor.u32(endian)? {
+                builder.begin_ring()?;
+                read_coords(cursor, header, builder)?;
+                builder.end()?;
+            }
+            builder.end()?;
+        }
+        GeoType::MultiPoint => {
+            builder.begin_multi_point()?;
+            read_children(cursor, header, builder, Some(GeoType::Point))?;
+            builder.end()?;
+        }
+        GeoType::MultiLineString => {
+            builder.begin_multi_line_string()?;
+            read_children(cursor, header, builder, Some(GeoType::LineString))?;
+            builder.end()?;
+        }
+        GeoType::MultiPolygon => {
+            builder.begin_multi_polygon()?;
+            read_children(cursor, header, builder, Some(GeoType::Polygon))?;
+            builder.end()?;
+        }
+        GeoType::Collection => {
+            builder.begin_collection()?;
+            read_children(cursor, header, builder, None)?;
+            builder.end()?;
+        }
+    }
+
+    Ok(())
+}
+
+fn read_coords(
+    cursor: &mut Cursor<'_>,
+    header: &Header,
+    builder: &mut GeobBuilder,
+) -> Result<(), WkbError> {
+    for _ in 0..cursor.u32(header.endian)? {
+        let (x, y) = header.coord(cursor)?;
+        builder.coord(x, y)?;
+    }
+
+    Ok(())
+}
+
+fn read_children(
+    cursor: &mut Cursor<'_>,
+    header: &Header,
+    builder: &mut GeobBuilder,
+    expected: Option<GeoType>,
+) -> Result<(), WkbError> {
+    for _ in 0..cursor.u32(header.endian)? {
+        let child = Header::read(cursor)?;
+
+        if let Some(expected) = expected
+            && expected != child.ty
+        {
+            return Err(WkbError::UnexpectedType {
+                expected,
+                found: child.ty,
+            });
+        }
+
+        read_body(cursor, &child, builder)?;
+    }
+
+    Ok(())
+}
+
+/// Write the geometry as 2D ISO WKB
+pub fn write_wkb<W: BinaryWriter>(
+    geo: &GeometryRef<'_>,
+    output: &mut W,
+    endian: Endian,
+) -> Result<(), W::Error> {
+    endian.write(output, endian)?;
+
+    match geo {
+        GeometryRef::Point(point) => {
+            (GeoType::Point as u32).write(output, endian)?;
+            point.x().write(output, endian)?;
+            point.y().write(output, endian)?;
+        }
+        GeometryRef::LineString(line) => {
+            (GeoType::LineString as u32).write(output, endian)?;
+            write_coords(line.0, output, endian)?;
+        }
+        GeometryRef::Polygon(polygon) => {
+            (GeoType::Polygon as u32).write(output, endian)?;
+            write_polygon(*polygon, output, endian)?;
+        }
+        GeometryRef::MultiPoint(points) => {
+            (GeoType::MultiPoint as u32).write(output, endian)?;
+            (points.len() as u32).write(output, endian)?;
+            for coord in points.iter() {
+                endian.write(output, endian)?;
+                (GeoType::Point as u32).write(output, endian)?;
+                coord.x().write(output, endian)?;
+                coord.y().write(output, endian)?;
+            }
+        }
+        GeometryRef::MultiLineString(lines) => {
+            (GeoType::MultiLineString as u32).write(output, endian)?;
+            (lines.len() as u32).write(output, endian)?;
+            for line in lines.iter() {
+                endian.write(output, endian)?;
+                (GeoType::LineString as u32).write(output, endian)?;
+                write_coords(line, output, endian)?;
+            }
+        }
+        GeometryRef::MultiPolygon(polygons) => {
+            (GeoType::MultiPolygon as u32).write(output, endian)?;
+            (polygons.len() as u32).write(output, endian)?;
+            for polygon in polygons.iter() {
+                endian.write(output, endian)?;
+                (GeoType::Polygon as u32).write(output, endian)?;
+                write_polygon(polygon, output, endian)?;
+            }
+        }
+        GeometryRef::Collection(collection) => {
+            (GeoType::Collection as u32).write(output, endian)?;
+            (collection.len() as u32).write(output, endian)?;
+            for geo in collection.iter() {
+                write_wkb(&geo, output, endian)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn write_coords<W: BinaryWriter>(
+    coords: CoordSeqRef<'_>,
+    output: &mut W,
+    endian: Endian,
+) -> Result<(), W::Error> {
+    (coords.len() as u32).write(output, endian)?;
+
+    for coord in coords.iter() {
+        coord.x().write(output, endian)?;
+        coord.y().write(output, endian)?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn write_polygon<W: BinaryWriter>(
+    polygon: PolygonRef<'_>,
+    output: &mut W,
+    endian: Endian,
+) -> Result<(), W::Error> {
+    (polygon.len() as u32).write(output, endian)?;
+
+    for ring in polygon.iter() {
+        write_coords(ring, output, endian)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use super::WkbError;
+    use crate::{Geob, SRID};
+
+    #[test]
+    fn round_trip() {
+        let geo = Geob::from_text(
+            "SRID=4326;GEOMETRYCOLLECTION(POINT(1.0 2.0), MULTILINESTRING((0.0 0.0, 1.0 1.0), (2.0 2.0, 3.0 3.0)), POLYGON((0.0 0.0, 1.0 0.0, 1.0 1.0, 0.0 0.0)))",
+        )
+        .unwrap();
+
+        let wkb = geo.to_wkb();
+
+        assert_eq!(Geob::from_wkb(&wkb, SRID::WGS84).unwrap(), geo);
+        assert_eq!(
+            Geob::from_wkb(&wkb[..wkb.len() - 1], SRID::WGS84),
+            Err(WkbError::UnexpectedEof)
+        );
+    }
+
+    #[test]
+    fn ewkb_and_z() {
+        // SRID=3857;POINT Z(1 2 3) as big endian EWKB
+        let mut ewkb = Vec::from([0u8, 0xa0, 0, 0, 1, 0, 0, 0x0f, 0x11]);
+        for n in [1.0f64, 2.0, 3.0] {
+            ewkb.extend_from_slice(&n.to_be_bytes());
+        }
+
+        let geo = Geob::from_wkb(&ewkb, SRID::UNKNOWN).unwrap();
+
+        assert_eq!(geo.srid(), SRID::WEB_MERCATOR);
+        assert_eq!(geo.as_point().map(|p| (p.x(), p.y())), Some((1.0, 2.0)));
+
+        // ISO LINESTRING M, little endian
+        let mut wkb = Vec::from([1u8]);
+        wkb.extend_from_slice(&2002u32.to_le_bytes());
+        wkb.extend_from_slice(&1u32.to_le_bytes());
+        for n in [4.0f64, 5.0, 6.0] {
+            wkb.extend_from_slice(&n.to_le_bytes());
+        }
+
+        let geo = Geob::from_wkb(&wkb, SRID::UNKNOWN).unwrap();
+        assert_eq!(geo.as_line_string().unwrap().len(), 1);
+    }
+}
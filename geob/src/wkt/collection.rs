@@ -13,6 +13,7 @@ pub fn parse_collection<'input, B, W>(
     out: &mut W,
     endian: Endian,
     write_type: bool,
+    depth: usize,
 ) -> udled::Result<()>
 where
     W: BinaryWriter,
@@ -25,11 +26,6 @@ where
     let ws_opt = ws.optional();
 
     input.eat(("GEOMETRYCOLLECTION", &ws_opt, '('))?;
-    let mut count = 0u32;
-
-    let pos = out.position();
-
-    count.write(out, endian).map_err(|err| input.error(err))?;
 
     if write_type {
         GeoType::Collection
@@ -37,8 +33,14 @@ where
             .map_err(|err| input.error(err))?;
     }
 
+    let mut count = 0u32;
+
+    let pos = out.position();
+
+    count.write(out, endian).map_err(|err| input.error(err))?;
+
     loop {
-        input.eat(&ws_opt)?;
+        input.eat(ws_opt)?;
         if input.is(')') {
             break;
         }
@@ -49,7 +51,7 @@ where
 
         count += 1;
 
-        parse_geometry(input, out, endian)?;
+        parse_geometry(input, out, endian, depth + 1)?;
     }
     input.eat((ws_opt, ')'))?;
 
@@ -1,4 +1,7 @@
-use udled::{tokenizers::AsciiWhiteSpace, AsChar, Buffer, Reader, TokenizerExt};
+use udled::{
+    AsBytes, AsChar, Buffer, Item, Reader, Tokenizer, TokenizerExt,
+    tokenizers::{AsciiWhiteSpace, Digit, Peek, opt},
+};
 
 pub fn ws<'input, B>(reader: &mut Reader<'_, 'input, B>) -> udled::Result<()>
 where
@@ -8,3 +11,89 @@ where
     reader.eat(AsciiWhiteSpace.many())?;
     Ok(())
 }
+
+/// A decimal number with optional fraction and exponent, like `-1`, `2.5` or
+/// `1.5e-7`, or `NaN`, `inf` and `-inf` as displayed for f64. Decimal values
+/// that overflow to infinity are rejected.
+pub struct Number;
+
+impl<'input, B> Tokenizer<'input, B> for Number
+where
+    B: Buffer<'input>,
+    B::Item: AsChar,
+    B::Source: AsBytes<'input>,
+{
+    type Token = Item<f64>;
+
+    fn to_token(&self, reader: &mut Reader<'_, 'input, B>) -> udled::Result<Self::Token> {
+        if reader.is("NaN") {
+            let span = reader.parse("NaN".spanned())?;
+            return Ok(Item::new(span, f64::NAN));
+        }
+
+        if reader.is(Peek((opt('-'), "inf"))) {
+            let (sign, inf) = reader.parse((opt('-'), "inf"))?;
+            let number = match sign {
+                Some(_) => f64::NEG_INFINITY,
+                None => f64::INFINITY,
+            };
+            return Ok(Item::new(
+                sign.map_or(inf.span, |sign| sign.span + inf.span),
+                number,
+            ));
+        }
+
+        let span = reader.parse(
+            (
+                opt('-'),
+                Digit(10).many(),
+                ('.', Digit(10).many()).optional(),
+                ('e'.or('E'), opt('-'.or('+')), Digit(10).many()).optional(),
+            )
+                .spanned(),
+        )?;
+
+        let bytes = &reader.buffer().source().as_bytes()[span.start..span.end];
+        let number = core::str::from_utf8(bytes)
+            .ok()
+            .and_then(|number| number.parse::<f64>().ok())
+            .filter(|number| number.is_finite())
+            .ok_or_else(|| reader.error("Number"))?;
+
+        Ok(Item::new(span, number))
+    }
+
+    fn peek(&self, reader: &mut Reader<'_, 'input, B>) -> bool {
+        reader.is(Peek((opt('-'), Digit(10))))
+            || reader.is("NaN")
+            || reader.is(Peek((opt('-'), "inf")))
+    }
+}
+
+/// An SRID, a decimal integer that fits a `u32`
+pub struct Srid;
+
+impl<'input, B> Tokenizer<'input, B> for Srid
+where
+    B: Buffer<'input>,
+    B::Item: AsChar,
+    B::Source: AsBytes<'input>,
+{
+    type Token = Item<u32>;
+
+    fn to_token(&self, reader: &mut Reader<'_, 'input, B>) -> udled::Result<Self::Token> {
+        let span = reader.parse(Digit(10).many().spanned())?;
+
+        let bytes = &reader.buffer().source().as_bytes()[span.start..span.end];
+        let srid = core::str::from_utf8(bytes)
+            .ok()
+            .and_then(|srid| srid.parse().ok())
+            .ok_or_else(|| reader.error("SRID"))?;
+
+        Ok(Item::new(span, srid))
+    }
+
+    fn peek(&self, reader: &mut Reader<'_, 'input, B>) -> bool {
+        reader.is(Digit(10))
+    }
+}
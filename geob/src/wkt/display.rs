@@ -1,80 +1,75 @@
 use core::fmt;
 
-use udled::{
-    Input,
-    bytes::{Endian, FromBytesExt},
-};
+use udled::{Input, bytes::FromBytesExt};
 
 type FmtResult = Result<usize, fmt::Error>;
 
 use crate::{
     GeoType,
-    types::GeobRef,
-    util::{read_f64, read_u32},
+    types::{GeobRef, Layout},
+    util::read_u32,
 };
 
 pub fn display_geometry(geo: GeobRef<'_>, f: &mut fmt::Formatter) -> fmt::Result {
     let output = geo.bytes;
 
-    let endian = match output[0] {
-        0 => Endian::Big,
-        1 => Endian::Lt,
-        _ => return Err(fmt::Error),
-    };
+    let layout = Layout::read(output).ok_or(fmt::Error)?;
 
-    let srid = read_u32(&output[1..], endian);
+    let srid = read_u32(&output[1..], layout.endian);
 
     write!(f, "SRID={srid};")?;
 
-    display_inner(&output[5..], endian, f)?;
+    display_inner(geo.body(), layout, f)?;
 
     Ok(())
 }
 
-fn display_inner(out: &[u8], endian: Endian, f: &mut fmt::Formatter) -> FmtResult {
-    let ty = Input::new(&*out).parse(GeoType::byteorder(endian)).unwrap();
+fn display_inner(out: &[u8], layout: Layout, f: &mut fmt::Formatter) -> FmtResult {
+    let ty = Input::new(out)
+        .parse(GeoType::byteorder(layout.endian))
+        .unwrap();
 
     let len = match ty.value {
         GeoType::Point => {
             write!(f, "POINT(")?;
-            let ret = display_coords(&out[1..], endian, f)?;
+            let ret = display_coords(&out[1..], layout, f)?;
             write!(f, ")")?;
             ret
         }
         GeoType::LineString => {
             write!(f, "LINESTRING(")?;
-            let ret = display_line_string(&out[1..], endian, f)?;
+            let ret = display_line_string(&out[1..], layout, f)?;
             write!(f, ")")?;
             ret
         }
         GeoType::Polygon => {
             write!(f, "POLYGON(")?;
-            let ret = display_polygon(&out[1..], endian, f)?;
+            let ret = display_polygon(&out[1..], layout, f)?;
             write!(f, ")")?;
             ret
         }
         GeoType::MultiPoint => {
             write!(f, "MULTIPOINT(")?;
-            let ret = display_line_string(&out[1..], endian, f)?;
+            let ret = display_line_string(&out[1..], layout, f)?;
             write!(f, ")")?;
             ret
         }
         GeoType::MultiLineString => {
             write!(f, "MULTILINESTRING(")?;
-            let ret = display_polygon(&out[1..], endian, f)?;
+            let ret = display_polygon(&out[1..], layout, f)?;
             write!(f, ")")?;
             ret
         }
 
         GeoType::MultiPolygon => {
             write!(f, "MULTIPOLYGON(")?;
-            let ret = display_multipolygon(&out[1..], endian, f)?;
+            let ret = display_multipolygon(&out[1..], layout, f)?;
             write!(f, ")")?;
             ret
         }
         GeoType::Collection => {
             write!(f, "GEOMETRYCOLLECTION(")?;
-            let ret = display_collection(&out[1..], endian, f)?;
+            let ret = display_collection(&out[1..], layout, f)?;
             write!(f, ")")?;
             ret
         }
@@ -83,32 +78,29 @@ fn display_inner(out: &[u8], endian: Endian, f: &mut fmt::Formatter) -> FmtResul
     Ok(1 + len)
 }
 
-fn display_coords(buf: &[u8], endian: Endian, f: &mut fmt::Formatter) -> FmtResult {
-    let x = read_f64(&*buf, endian);
-    let y = read_f64(&buf[8..], endian);
-
-    write!(f, "{} {}", x, y)?;
+fn display_coords(buf: &[u8], layout: Layout, f: &mut fmt::Formatter) -> FmtResult {
+    write!(f, "{} {}", layout.x(buf), layout.y(buf))?;
 
-    Ok(16)
+    Ok(layout.coord_len())
 }
 
-pub fn display_line_string(buf: &[u8], endian: Endian, f: &mut fmt::Formatter) -> FmtResult {
-    let num = read_u32(buf, endian) as usize;
+pub fn display_line_string(buf: &[u8], layout: Layout, f: &mut fmt::Formatter) -> FmtResult {
+    let num = read_u32(buf, layout.endian) as usize;
     let offset = 4;
 
     for i in 0..num {
         if i > 0 {
             write!(f, ", ")?;
         }
-        let offset = offset + (i * 16);
-        display_coords(&buf[offset..(offset + 16)], endian, f)?;
+        let offset = offset + (i * layout.coord_len());
+        display_coords(&buf[offset..], layout, f)?;
     }
 
-    Ok(offset + num * 16)
+    Ok(offset + num * layout.coord_len())
 }
 
-fn display_polygon(buf: &[u8], endian: Endian, f: &mut fmt::Formatter) -> FmtResult {
-    let num = read_u32(buf, endian) as usize;
+fn display_polygon(buf: &[u8], layout: Layout, f: &mut fmt::Formatter) -> FmtResult {
+    let num = read_u32(buf, layout.endian) as usize;
     let offset = 4;
 
     let mut size = offset;
@@ -118,15 +110,15 @@ fn display_polygon(buf: &[u8], endian: Endian, f: &mut fmt::Formatter) -> FmtRes
             write!(f, ", ")?;
         }
         write!(f, "(")?;
-        size += display_line_string(&buf[size..], endian, f)?;
+        size += display_line_string(&buf[size..], layout, f)?;
         write!(f, ")")?;
     }
 
     Ok(size)
 }
 
-fn display_multipolygon(buf: &[u8], endian: Endian, f: &mut fmt::Formatter) -> FmtResult {
-    let num = read_u32(buf, endian) as usize;
+fn display_multipolygon(buf: &[u8], layout: Layout, f: &mut fmt::Formatter) -> FmtResult {
+    let num = read_u32(buf, layout.endian) as usize;
     let offset = 4;
 
     let mut size = offset;
@@ -136,15 +128,15 @@ fn display_multipolygon(buf: &[u8], endian: Endian, f: &mut fmt::Formatter) -> F
             write!(f, ", ")?;
         }
         write!(f, "(")?;
-        size += display_polygon(&buf[size..], endian, f)?;
+        size += display_polygon(&buf[size..], layout, f)?;
         write!(f, ")")?;
     }
 
     Ok(size)
 }
 
-fn display_collection(buf: &[u8], endian: Endian, f: &mut fmt::Formatter) -> FmtResult {
-    let num = read_u32(buf, endian) as usize;
+fn display_collection(buf: &[u8], layout: Layout, f: &mut fmt::Formatter) -> FmtResult {
+    let num = read_u32(buf, layout.endian) as usize;
     let offset = 4;
 
     let mut size = offset;
@@ -153,7 +145,7 @@ fn display_collection(buf: &[u8], endian: Endian, f: &mut fmt::Formatter) -> Fmt
         if i > 0 {
             write!(f, ", ")?;
         }
-        size += display_inner(&buf[size..], endian, f)?;
+        size += display_inner(&buf[size..], layout, f)?;
     }
 
     Ok(size)
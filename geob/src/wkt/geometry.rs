@@ -2,9 +2,11 @@ use alloc::boxed::Box;
 use udled::{bytes::Endian, AsBytes, AsChar, Buffer, Reader};
 
 use crate::{
+    builder::MAX_DEPTH,
     wkt::{
         collection::parse_collection, line_string::parse_line_string,
-        multi_line_string::parse_multi_line_string, point::parse_point, polygon::parse_polyon,
+        multi_line_string::parse_multi_line_string, multi_point::parse_multi_point,
+        multi_polygon::parse_multi_polygon, point::parse_point, polygon::parse_polyon,
     },
     writer::BinaryWriter,
 };
@@ -13,6 +15,7 @@ pub fn parse_geometry<'input, B, W>(
     reader: &mut Reader<'_, 'input, B>,
     output: &mut W,
     endian: Endian,
+    depth: usize,
 ) -> udled::Result<()>
 where
     W: BinaryWriter,
@@ -21,16 +24,24 @@ where
     B::Item: AsChar,
     B::Source: AsBytes<'input>,
 {
+    if depth > MAX_DEPTH {
+        return Err(reader.error("Collections nested too deep"));
+    }
+
     if reader.is("POINT") {
         parse_point(reader, output, endian, true)?;
     } else if reader.is("LINESTRING") {
         parse_line_string(reader, output, endian, true)?;
     } else if reader.is("POLYGON") {
         parse_polyon(reader, output, endian, true)?;
+    } else if reader.is("MULTIPOINT") {
+        parse_multi_point(reader, output, endian, true)?;
     } else if reader.is("MULTILINESTRING") {
         parse_multi_line_string(reader, output, endian, true)?;
+    } else if reader.is("MULTIPOLYGON") {
+        parse_multi_polygon(reader, output, endian, true)?;
     } else if reader.is("GEOMETRYCOLLECTION") {
-        parse_collection(reader, output, endian, true)?;
+        parse_collection(reader, output, endian, true, depth)?;
     } else {
         return Err(reader.error("Geometry"));
     }
@@ -1,8 +1,11 @@
 use alloc::vec::Vec;
 use udled::{AsBytes, AsChar, Buffer, Input, Tokenizer, bytes::Endian};
-use udled_tokenizers::Integer;
 
-use crate::{Geob, wkt::geometry::parse_geometry, writer::ToBytes};
+use crate::{
+    Geob,
+    wkt::{common::Srid, geometry::parse_geometry},
+    writer::ToBytes,
+};
 
 mod collection;
 mod common;
@@ -10,6 +13,8 @@ mod display;
 mod geometry;
 mod line_string;
 mod multi_line_string;
+mod multi_point;
+mod multi_polygon;
 mod point;
 mod polygon;
 
@@ -48,14 +53,71 @@ where
             }
         }
 
-        let (_, srid, _) = reader.parse((("SRID", "="), Integer, ";"))?;
+        let (_, srid, _) = reader.parse((("SRID", "="), Srid, ";"))?;
 
-        (srid.value as u32)
+        srid.value
             .write(&mut output, endian)
             .map_err(|err| reader.error(err))?;
 
-        parse_geometry(reader, &mut output, endian)?;
+        parse_geometry(reader, &mut output, endian, 0)?;
 
         Ok(output)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use alloc::{format, string::ToString};
+    use proptest::prop_assert_eq;
+    use udled::bytes::Endian;
+
+    use crate::{Geob, arbitrary};
+
+    #[test]
+    fn round_trip() {
+        arbitrary::runner()
+            .run(&arbitrary::geob(), |geo| {
+                for geo in [geo.clone(), geo.to_endian(Endian::Big)] {
+                    let text = geo.to_string();
+                    let parsed = Geob::from_text(&text).unwrap();
+
+                    prop_assert_eq!(&parsed, &geo);
+                    prop_assert_eq!(parsed.to_string(), text);
+                    prop_assert_eq!(Geob::from_bytes(parsed.as_ref().bytes), Ok(parsed));
+                }
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn parse() {
+        let geo = Geob::from_text("SRID=0;MULTIPOINT((1 -2), (3.5 4e2))").unwrap();
+        assert_eq!(geo.to_string(), "SRID=0;MULTIPOINT(1 -2, 3.5 400)");
+
+        let geo = Geob::from_text("SRID=0;MULTIPOLYGON(((0 0, 1 0, 1 1, 0 0)), ())").unwrap();
+        assert_eq!(
+            geo.to_string(),
+            "SRID=0;MULTIPOLYGON(((0 0, 1 0, 1 1, 0 0)), ())"
+        );
+
+        assert!(Geob::from_text("SRID=4294967296;POINT(1 2)").is_err());
+        assert!(Geob::from_text("SRID=0;POINT(1e 2)").is_err());
+        assert!(Geob::from_text("SRID=0;POINT(1e400 2)").is_err());
+
+        let geo = Geob::from_text("SRID=0;LINESTRING(NaN inf, -inf 1)").unwrap();
+        assert_eq!(geo.to_string(), "SRID=0;LINESTRING(NaN inf, -inf 1)");
+        assert!(Geob::from_text("SRID=0;POINT(-NaN 1)").is_err());
+        assert!(Geob::from_text("SRID=0;POINT(infinity 1)").is_err());
+
+        let nested = |depth: usize| {
+            format!(
+                "SRID=0;{}POINT(1 2){}",
+                "GEOMETRYCOLLECTION(".repeat(depth),
+                ")".repeat(depth)
+            )
+        };
+        assert!(Geob::from_text(&nested(16)).is_ok());
+        assert!(Geob::from_text(&nested(17)).is_err());
+    }
+}
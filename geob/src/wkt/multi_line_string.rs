@@ -58,7 +58,7 @@ where
     count.write(out, endian).map_err(|err| input.error(err))?;
 
     loop {
-        input.eat(&ws_opt)?;
+        input.eat(ws_opt)?;
         if input.is(')') {
             break;
         }
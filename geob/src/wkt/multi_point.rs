@@ -0,0 +1,72 @@
+use alloc::boxed::Box;
+use byteorder::{BigEndian, LittleEndian};
+use udled::{AsBytes, AsChar, Buffer, IntoTokenizer, Reader, TokenizerExt, bytes::Endian};
+
+use crate::{
+    GeoType,
+    wkt::{common::ws, point::parse_coord},
+    writer::{BinaryWriter, ToBytes},
+};
+
+/// Points may be written bare, `MULTIPOINT(1 2, 3 4)`, or in parentheses,
+/// `MULTIPOINT((1 2), (3 4))`
+pub fn parse_multi_point<'input, B, W>(
+    input: &mut Reader<'_, 'input, B>,
+    out: &mut W,
+    endian: Endian,
+    write_type: bool,
+) -> udled::Result<()>
+where
+    W: BinaryWriter,
+    W::Error: Into<Box<dyn core::error::Error + Send + Sync>>,
+    B: Buffer<'input>,
+    B::Item: AsChar,
+    B::Source: AsBytes<'input>,
+{
+    let ws = ws.into_tokenizer();
+    let ws_opt = ws.optional();
+
+    input.eat(("MULTIPOINT", &ws_opt, '('))?;
+
+    if write_type {
+        GeoType::MultiPoint
+            .write(out, endian)
+            .map_err(|err| input.error(err))?;
+    }
+
+    let mut count = 0u32;
+
+    let pos = out.position();
+
+    count.write(out, endian).map_err(|err| input.error(err))?;
+
+    loop {
+        input.eat(ws_opt)?;
+        if input.is(')') {
+            break;
+        }
+
+        if count > 0 {
+            input.eat((',', &ws_opt))?;
+        }
+
+        if input.is('(') {
+            input.eat(('(', &ws_opt))?;
+            parse_coord(input, out, endian)?;
+            input.eat((&ws_opt, ')'))?;
+        } else {
+            parse_coord(input, out, endian)?;
+        }
+
+        count += 1;
+    }
+    input.eat((ws_opt, ')'))?;
+
+    match endian {
+        Endian::Big => out.write_u32_at::<BigEndian>(pos, count),
+        Endian::Lt => out.write_u32_at::<LittleEndian>(pos, count),
+    }
+    .map_err(|err| input.error(err))?;
+
+    Ok(())
+}
@@ -1,11 +1,10 @@
 use alloc::boxed::Box;
 use byteorder::{BigEndian, LittleEndian};
 use udled::{AsBytes, AsChar, Buffer, IntoTokenizer, Reader, TokenizerExt, bytes::Endian};
-use udled_tokenizers::Float;
 
 use crate::{
     GeoType,
-    wkt::common::ws,
+    wkt::common::{Number, ws},
     writer::{BinaryWriter, ToBytes},
 };
 
@@ -26,7 +25,7 @@ where
     let ws_opt = ws.optional();
 
     input.eat(("POINT", ws_opt, '('))?;
-    let (x, y) = input.parse((Float, &ws, Float).map_ok(|(x, _, y)| (x.value, y.value)))?;
+    let (x, y) = input.parse((Number, &ws, Number).map_ok(|(x, _, y)| (x.value, y.value)))?;
     input.eat((ws_opt, ')'))?;
 
     if write_type {
@@ -55,7 +54,7 @@ where
 {
     let ws = ws.into_tokenizer();
 
-    let (x, y) = input.parse((Float, &ws, Float).map_ok(|(x, _, y)| (x.value, y.value)))?;
+    let (x, y) = input.parse((Number, &ws, Number).map_ok(|(x, _, y)| (x.value, y.value)))?;
 
     x.write(out, endian).map_err(|err| input.error(err))?;
     y.write(out, endian).map_err(|err| input.error(err))?;
@@ -87,7 +86,7 @@ where
     let mut first = 0u32;
 
     loop {
-        input.eat(&ws_opt)?;
+        input.eat(ws_opt)?;
         if input.is(')') {
             break;
         }
@@ -100,7 +99,7 @@ where
 
         parse_coord(input, out, endian)?;
 
-        input.eat(&ws_opt)?;
+        input.eat(ws_opt)?;
     }
     input.eat((ws_opt, ')'))?;
 
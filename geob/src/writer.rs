@@ -1,4 +1,4 @@
-use core::convert::Infallible;
+use core::{convert::Infallible, fmt};
 
 use alloc::vec::Vec;
 use byteorder::{BigEndian, ByteOrder, LittleEndian};
@@ -49,6 +49,8 @@ pub trait BinaryWriter {
 
     fn write_all(&mut self, bytes: &[u8]) -> Result<(), Self::Error>;
 
+    /// Overwrite bytes that were already written, starting at `idx`. Writing
+    /// past [`BinaryWriter::position`] is a bug in the caller.
     fn write_all_at(&mut self, bytes: &[u8], idx: usize) -> Result<(), Self::Error>;
 
     fn write_u8(&mut self, n: u8) -> Result<(), Self::Error> {
@@ -130,3 +132,234 @@ impl BinaryWriter for Vec<u8> {
         Ok(())
     }
 }
+
+/// The buffer of a [`SliceWriter`] is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError {
+    pub capacity: usize,
+}
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "buffer capacity of {} bytes exceeded", self.capacity)
+    }
+}
+
+impl core::error::Error for CapacityError {}
+
+/// Writes into a fixed buffer, like `&mut [u8]` or `[u8; N]`, without
+/// allocating.
+///
+/// ```
+/// use geob::{GeobRef, SRID, builder::GeometryWriter, writer::SliceWriter};
+/// use udled::bytes::Endian;
+///
+/// let mut buffer = [0; 64];
+/// let mut writer = GeometryWriter::new(SliceWriter::new(&mut buffer[..]), SRID::WGS84, Endian::Lt).unwrap();
+/// writer.point(10.0, 59.0).unwrap();
+///
+/// let output = writer.finish().unwrap();
+/// let geob = GeobRef::from_bytes(output.written()).unwrap();
+///
+/// assert_eq!(geob.to_string(), "SRID=4326;POINT(10 59)");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SliceWriter<B> {
+    buffer: B,
+    position: usize,
+}
+
+impl<B> SliceWriter<B> {
+    pub fn new(buffer: B) -> SliceWriter<B> {
+        SliceWriter {
+            buffer,
+            position: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> B {
+        self.buffer
+    }
+}
+
+impl<B: AsRef<[u8]>> SliceWriter<B> {
+    /// The bytes written so far
+    pub fn written(&self) -> &[u8] {
+        &self.buffer.as_ref()[..self.position]
+    }
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> BinaryWriter for SliceWriter<B> {
+    type Error = CapacityError;
+
+    fn position(&self) -> usize {
+        self.position
+    }
+
+    fn write_all(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        let end = self.position + bytes.len();
+        let buffer = self.buffer.as_mut();
+
+        if end > buffer.len() {
+            return Err(CapacityError {
+                capacity: buffer.len(),
+            });
+        }
+
+        buffer[self.position..end].copy_from_slice(bytes);
+        self.position = end;
+
+        Ok(())
+    }
+
+    fn write_all_at(&mut self, bytes: &[u8], idx: usize) -> Result<(), Self::Error> {
+        let end = idx + bytes.len();
+        debug_assert!(
+            end <= self.position,
+            "back-patch of {idx}..{end} past the written {} bytes",
+            self.position
+        );
+
+        self.buffer.as_mut()[idx..end].copy_from_slice(bytes);
+
+        Ok(())
+    }
+}
+
+/// Adapter for [`std::io::Write`]. Element counts are written once a geometry
+/// is complete, so the geometry is buffered and only written to `inner` by
+/// [`IoWriter::into_inner`]. Any writer works, including pipes, sockets and
+/// stdout.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct IoWriter<W> {
+    inner: W,
+    buffer: Vec<u8>,
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> IoWriter<W> {
+    pub fn new(inner: W) -> IoWriter<W> {
+        IoWriter {
+            inner,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Write the buffered geometry to `inner` and return it
+    pub fn into_inner(mut self) -> std::io::Result<W> {
+        self.inner.write_all(&self.buffer)?;
+        Ok(self.inner)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> BinaryWriter for IoWriter<W> {
+    type Error = Infallible;
+
+    fn position(&self) -> usize {
+        self.buffer.position()
+    }
+
+    fn write_all(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.buffer.write_all(bytes)
+    }
+
+    fn write_all_at(&mut self, bytes: &[u8], idx: usize) -> Result<(), Self::Error> {
+        self.buffer.write_all_at(bytes, idx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use udled::bytes::Endian;
+
+    use super::{BinaryWriter, CapacityError, SliceWriter};
+    use crate::{Geob, GeobRef, builder::GeometryWriter};
+
+    #[test]
+    fn slice_writer() {
+        let geo = Geob::from_text(
+            "SRID=4326;GEOMETRYCOLLECTION(POINT(1.0 2.0), LINESTRING(0.0 0.0, 1.0 1.0))",
+        )
+        .unwrap();
+        let geo = geo.as_ref();
+
+        let mut buffer = [0u8; 128];
+        let mut writer = GeometryWriter::new(
+            SliceWriter::new(&mut buffer[..]),
+            geo.srid().into(),
+            geo.endian(),
+        )
+        .unwrap();
+        writer.geometry(&geo.geometry()).unwrap();
+        let output = writer.finish().unwrap();
+
+        assert_eq!(output.written().len(), geo.encoded_len());
+        assert_eq!(GeobRef::from_bytes(output.written()).unwrap(), geo);
+
+        let mut writer =
+            GeometryWriter::new(SliceWriter::new([0u8; 32]), geo.srid().into(), Endian::Lt)
+                .unwrap();
+        assert!(matches!(
+            writer.geometry(&geo.geometry()),
+            Err(crate::builder::BuilderError::Writer(CapacityError {
+                capacity: 32
+            }))
+        ));
+
+        let mut writer = SliceWriter::new([0u8; 4]);
+        writer.write_all(&[1, 2, 3]).unwrap();
+        assert!(writer.write_all(&[4, 5]).is_err());
+        writer.write_all_at(&[9], 1).unwrap();
+        assert_eq!(writer.written(), &[1, 9, 3]);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "back-patch")]
+    fn slice_writer_back_patch() {
+        let mut writer = SliceWriter::new([0u8; 4]);
+        writer.write_all(&[1]).unwrap();
+        let _ = writer.write_all_at(&[1, 2], 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io_writer() {
+        use super::IoWriter;
+
+        let geo = Geob::from_text("SRID=4326;POLYGON((0.0 0.0, 1.0 0.0, 1.0 1.0, 0.0 0.0))")
+            .unwrap()
+            .with_envelope();
+
+        // Write only, without Seek
+        struct Pipe(alloc::vec::Vec<u8>);
+
+        impl std::io::Write for Pipe {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.write(buf)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut writer = GeometryWriter::with_envelope(
+            IoWriter::new(Pipe(alloc::vec![0xff; 3])),
+            geo.srid(),
+            geo.endian(),
+        )
+        .unwrap();
+        writer.geometry(&geo.geometry()).unwrap();
+        let Pipe(bytes) = writer.finish().unwrap().into_inner().unwrap();
+
+        assert_eq!(&bytes[..3], &[0xff; 3]);
+        assert_eq!(Geob::from_bytes(&bytes[3..]).unwrap(), geo);
+        assert_eq!(
+            Geob::from_bytes(&bytes[3..]).unwrap().envelope(),
+            geo.envelope()
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use geo::{Coord, coord, point};
+use geo::point;
 use geob::{Geob, SRID};
 use rusqlite::Connection;
 
@@ -0,0 +1,105 @@
+use geob::{Geob, affine::Affine, types::GeometryRef};
+use rusqlite::{
+    Connection, Error, Result,
+    functions::{Context, FunctionFlags},
+};
+
+pub fn register_functions(conn: &Connection) -> Result<()> {
+    conn.create_scalar_function(
+        "ST_Translate",
+        3,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| transform(ctx, Affine::translate(ctx.get(1)?, ctx.get(2)?)),
+    )?;
+
+    conn.create_scalar_function("ST_Scale", 3, FunctionFlags::SQLITE_DETERMINISTIC, |ctx| {
+        transform(ctx, Affine::scale(ctx.get(1)?, ctx.get(2)?))
+    })?;
+
+    conn.create_scalar_function(
+        "ST_Rotate",
+        -1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let angle: f64 = ctx.get(1)?;
+
+            let affine = match ctx.len() {
+                2 => Affine::rotate(angle),
+                3 => {
+                    let origin: Geob = ctx.get(2)?;
+                    let GeometryRef::Point(point) = origin.geometry() else {
+                        return Err(Error::UserFunctionError("origin must be a point".into()));
+                    };
+                    Affine::rotate_around(angle, point.x(), point.y())
+                }
+                4 => Affine::rotate_around(angle, ctx.get(2)?, ctx.get(3)?),
+                _ => {
+                    return Err(Error::UserFunctionError(
+                        "expected geom, angle and an optional origin".into(),
+                    ));
+                }
+            };
+
+            transform(ctx, affine)
+        },
+    )?;
+
+    conn.create_scalar_function("ST_Affine", 7, FunctionFlags::SQLITE_DETERMINISTIC, |ctx| {
+        let affine = Affine::new(
+            ctx.get(1)?,
+            ctx.get(2)?,
+            ctx.get(3)?,
+            ctx.get(4)?,
+            ctx.get(5)?,
+            ctx.get(6)?,
+        );
+
+        transform(ctx, affine)
+    })?;
+
+    conn.create_scalar_function(
+        "ST_FlipCoordinates",
+        1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let mut geo: Geob = ctx.get(0)?;
+            geo.flip_coordinates();
+            Ok(geo)
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_SnapToGrid",
+        -1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let (origin, size): (_, (f64, f64)) = match ctx.len() {
+                2 => ((0.0, 0.0), (ctx.get(1)?, ctx.get(1)?)),
+                3 => ((0.0, 0.0), (ctx.get(1)?, ctx.get(2)?)),
+                5 => ((ctx.get(1)?, ctx.get(2)?), (ctx.get(3)?, ctx.get(4)?)),
+                _ => {
+                    return Err(Error::UserFunctionError(
+                        "expected geom and size, sizes or origin and sizes".into(),
+                    ));
+                }
+            };
+
+            if !(size.0 >= 0.0 && size.1 >= 0.0 && size.0.is_finite() && size.1.is_finite()) {
+                return Err(Error::UserFunctionError(
+                    format!("grid size must not be negative: {} {}", size.0, size.1).into(),
+                ));
+            }
+
+            let geo: Geob = ctx.get(0)?;
+            Ok(geo.snap_to_grid(origin, size))
+        },
+    )?;
+
+    Ok(())
+}
+
+fn transform(ctx: &Context<'_>, affine: Affine) -> Result<Geob> {
+    let mut geo: Geob = ctx.get(0)?;
+    geo.affine(&affine);
+    Ok(geo)
+}
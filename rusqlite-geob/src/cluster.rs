@@ -0,0 +1,568 @@
+use geob::{Geob, SRID, algorithm::geodesic::MEAN_RADIUS};
+use rstar::{RTree, primitives::GeomWithData};
+use rusqlite::{
+    Connection, Error, Result,
+    functions::{Aggregate, Context, FunctionFlags, WindowAggregate},
+};
+
+const MAX_ITERATIONS: usize = 100;
+
+pub fn register_functions(conn: &Connection) -> Result<()> {
+    conn.create_window_function(
+        "ST_ClusterDBSCAN",
+        3,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        ClusterDbscan,
+    )?;
+
+    conn.create_window_function(
+        "ST_ClusterKMeans",
+        2,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        ClusterKMeans,
+    )?;
+
+    Ok(())
+}
+
+type Entry = GeomWithData<[f64; 3], usize>;
+
+/// Geometries to cluster, reduced to their centroid and placed on a sphere in
+/// meters for geodetic SRIDs, or on the plane otherwise. NULL and empty
+/// geometries are kept as `None` so ids line up with the input rows.
+#[derive(Default)]
+struct Points {
+    srid: Option<SRID>,
+    points: Vec<Option<[f64; 3]>>,
+}
+
+impl Points {
+    fn push(&mut self, geo: Option<Geob>) -> Result<()> {
+        let Some(geo) = geo else {
+            self.points.push(None);
+            return Ok(());
+        };
+
+        let srid = *self.srid.get_or_insert(geo.srid());
+        if srid != geo.srid() {
+            return Err(Error::UserFunctionError(
+                format!("mixed SRIDs in partition: {} and {}", srid, geo.srid()).into(),
+            ));
+        }
+
+        let point = geo.geometry().centroid().map(|(x, y)| {
+            if srid.is_geodetic() {
+                let (lon, lat) = (x.to_radians(), y.to_radians());
+                [
+                    MEAN_RADIUS * lat.cos() * lon.cos(),
+                    MEAN_RADIUS * lat.cos() * lon.sin(),
+                    MEAN_RADIUS * lat.sin(),
+                ]
+            } else {
+                [x, y, 0.0]
+            }
+        });
+
+        self.points.push(point);
+
+        Ok(())
+    }
+
+    fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    fn is_geodetic(&self) -> bool {
+        self.srid.is_some_and(|srid| srid.is_geodetic())
+    }
+
+    fn entries(&self) -> impl Iterator<Item = Entry> + '_ {
+        self.points
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, point)| point.map(|point| Entry::new(point, idx)))
+    }
+}
+
+fn distance_2(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    (0..3).map(|i| (a[i] - b[i]) * (a[i] - b[i])).sum()
+}
+
+/// Rows of a window partition with the cluster arguments of its first row.
+///
+/// Every row is stepped in before the first call to `value`, which clusters
+/// the partition and then hands out the id of the n-th row on the n-th call.
+/// That needs a frame covering the whole partition with one value per row:
+///
+/// ```sql
+/// ST_ClusterDBSCAN(geom, eps, minpoints)
+///   OVER (ORDER BY id ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING)
+/// ```
+///
+/// The `ORDER BY` fixes the order rows are clustered in, and with it the
+/// numbering of the clusters. A bare `OVER ()` makes every row a peer, so
+/// SQLite asks for a single value and gives the first row's id to all rows.
+/// That can't be detected here, as SQLite ignores errors from `xFinal` of a
+/// window function. Growing and sliding frames fail with an error.
+struct Partition<P> {
+    params: Option<P>,
+    points: Points,
+    ids: Option<Vec<Option<i64>>>,
+    next: usize,
+}
+
+impl<P> Partition<P> {
+    fn new() -> Partition<P> {
+        Partition {
+            params: None,
+            points: Points::default(),
+            ids: None,
+            next: 0,
+        }
+    }
+
+    fn push(&mut self, ctx: &Context<'_>) -> Result<()> {
+        // A growing frame steps in more rows after handing out values
+        if self.ids.is_some() {
+            return Err(frame_error());
+        }
+
+        self.points.push(ctx.get(0)?)
+    }
+
+    fn value(
+        &mut self,
+        cluster: impl FnOnce(&Points, &P) -> Vec<Option<i64>>,
+    ) -> Result<Option<i64>> {
+        let Some(params) = &self.params else {
+            return Ok(None);
+        };
+
+        let ids = self
+            .ids
+            .get_or_insert_with(|| cluster(&self.points, params));
+        let Some(id) = ids.get(self.next) else {
+            return Err(frame_error());
+        };
+
+        self.next += 1;
+
+        Ok(*id)
+    }
+}
+
+fn frame_error() -> Error {
+    Error::UserFunctionError(
+        "clustering requires OVER (ORDER BY ... ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING)"
+            .into(),
+    )
+}
+
+/// `ST_ClusterDBSCAN(geom, eps, minpoints)`, with `eps` in meters for geodetic
+/// SRIDs. Noise and NULL geometries get a NULL cluster id.
+struct ClusterDbscan;
+
+impl Aggregate<Partition<(f64, usize)>, Option<i64>> for ClusterDbscan {
+    fn init(&self, _ctx: &mut Context<'_>) -> Result<Partition<(f64, usize)>> {
+        Ok(Partition::new())
+    }
+
+    fn step(&self, ctx: &mut Context<'_>, acc: &mut Partition<(f64, usize)>) -> Result<()> {
+        if acc.params.is_none() {
+            acc.params = Some(dbscan_params(ctx.get(1)?, ctx.get(2)?)?);
+        }
+
+        acc.push(ctx)
+    }
+
+    fn finalize(
+        &self,
+        _ctx: &mut Context<'_>,
+        _acc: Option<Partition<(f64, usize)>>,
+    ) -> Result<Option<i64>> {
+        Ok(None)
+    }
+}
+
+impl WindowAggregate<Partition<(f64, usize)>, Option<i64>> for ClusterDbscan {
+    fn value(&self, acc: Option<&mut Partition<(f64, usize)>>) -> Result<Option<i64>> {
+        let Some(acc) = acc else {
+            return Ok(None);
+        };
+
+        acc.value(|points, (eps, min_points)| dbscan(points, *eps, *min_points))
+    }
+
+    fn inverse(&self, _ctx: &mut Context<'_>, _acc: &mut Partition<(f64, usize)>) -> Result<()> {
+        Err(frame_error())
+    }
+}
+
+/// `ST_ClusterKMeans(geom, k)`. NULL geometries get a NULL cluster id.
+struct ClusterKMeans;
+
+impl Aggregate<Partition<usize>, Option<i64>> for ClusterKMeans {
+    fn init(&self, _ctx: &mut Context<'_>) -> Result<Partition<usize>> {
+        Ok(Partition::new())
+    }
+
+    fn step(&self, ctx: &mut Context<'_>, acc: &mut Partition<usize>) -> Result<()> {
+        if acc.params.is_none() {
+            acc.params = Some(kmeans_params(ctx.get(1)?)?);
+        }
+
+        acc.push(ctx)
+    }
+
+    fn finalize(
+        &self,
+        _ctx: &mut Context<'_>,
+        _acc: Option<Partition<usize>>,
+    ) -> Result<Option<i64>> {
+        Ok(None)
+    }
+}
+
+impl WindowAggregate<Partition<usize>, Option<i64>> for ClusterKMeans {
+    fn value(&self, acc: Option<&mut Partition<usize>>) -> Result<Option<i64>> {
+        let Some(acc) = acc else {
+            return Ok(None);
+        };
+
+        acc.value(|points, k| kmeans(points, *k))
+    }
+
+    fn inverse(&self, _ctx: &mut Context<'_>, _acc: &mut Partition<usize>) -> Result<()> {
+        Err(frame_error())
+    }
+}
+
+fn dbscan_params(eps: f64, min_points: i64) -> Result<(f64, usize)> {
+    if eps.is_nan() || eps < 0.0 || min_points < 0 {
+        return Err(Error::UserFunctionError(
+            "eps and minpoints must not be negative".into(),
+        ));
+    }
+
+    Ok((eps, min_points as usize))
+}
+
+fn kmeans_params(k: i64) -> Result<usize> {
+    if k < 1 {
+        return Err(Error::UserFunctionError(
+            format!("k must be positive: {k}").into(),
+        ));
+    }
+
+    Ok(k as usize)
+}
+
+fn dbscan(points: &Points, eps: f64, min_points: usize) -> Vec<Option<i64>> {
+    let eps = if points.is_geodetic() {
+        // Chord length of the arc
+        2.0 * MEAN_RADIUS
+            * (eps / (2.0 * MEAN_RADIUS))
+                .min(core::f64::consts::FRAC_PI_2)
+                .sin()
+    } else {
+        eps
+    };
+
+    let tree = RTree::bulk_load(points.entries().collect());
+    let neighbours = |point: &[f64; 3]| {
+        tree.locate_within_distance(*point, eps * eps)
+            .map(|entry| entry.data)
+            .collect::<Vec<_>>()
+    };
+
+    let mut ids = vec![None; points.len()];
+    let mut visited = vec![false; points.len()];
+    let mut cluster = 0;
+
+    for (idx, point) in points.points.iter().enumerate() {
+        let Some(point) = point else {
+            continue;
+        };
+
+        if visited[idx] {
+            continue;
+        }
+        visited[idx] = true;
+
+        let mut queue = neighbours(point);
+        if queue.len() < min_points {
+            continue;
+        }
+
+        ids[idx] = Some(cluster);
+
+        while let Some(other) = queue.pop() {
+            if ids[other].is_none() {
+                ids[other] = Some(cluster);
+            }
+
+            if visited[other] {
+                continue;
+            }
+            visited[other] = true;
+
+            // Only core points expand the cluster
+            let found = neighbours(points.points[other].as_ref().unwrap());
+            if found.len() >= min_points {
+                queue.extend(found);
+            }
+        }
+
+        cluster += 1;
+    }
+
+    ids
+}
+
+/// Seeds are picked farthest first from the first row, so results are
+/// deterministic for a given row order
+fn kmeans(points: &Points, k: usize) -> Vec<Option<i64>> {
+    let entries = points.entries().collect::<Vec<_>>();
+    let mut ids = vec![None; points.len()];
+
+    let Some(first) = entries.first() else {
+        return ids;
+    };
+
+    let mut centers = vec![*first.geom()];
+    while centers.len() < k.min(entries.len()) {
+        let farthest = entries
+            .iter()
+            .map(|entry| {
+                let nearest = centers
+                    .iter()
+                    .map(|center| distance_2(entry.geom(), center))
+                    .fold(f64::INFINITY, f64::min);
+                (nearest, entry.geom())
+            })
+            .fold((-1.0, first.geom()), |a, b| if b.0 > a.0 { b } else { a });
+
+        // Fewer distinct locations than clusters
+        if farthest.0 <= 0.0 {
+            break;
+        }
+
+        centers.push(*farthest.1);
+    }
+
+    for _ in 0..MAX_ITERATIONS {
+        let tree = RTree::bulk_load(
+            centers
+                .iter()
+                .enumerate()
+                .map(|(idx, center)| Entry::new(*center, idx))
+                .collect(),
+        );
+
+        let mut changed = false;
+        let mut sums = vec![([0.0; 3], 0usize); centers.len()];
+
+        for entry in &entries {
+            let cluster = tree.nearest_neighbor(entry.geom()).unwrap().data;
+            if ids[entry.data] != Some(cluster as i64) {
+                ids[entry.data] = Some(cluster as i64);
+                changed = true;
+            }
+
+            let (sum, count) = &mut sums[cluster];
+            (0..3).for_each(|i| sum[i] += entry.geom()[i]);
+            *count += 1;
+        }
+
+        if !changed {
+            break;
+        }
+
+        for (center, (sum, count)) in centers.iter_mut().zip(sums) {
+            // Empty clusters keep their center
+            if count == 0 {
+                continue;
+            }
+
+            *center = sum.map(|n| n / count as f64);
+
+            if points.is_geodetic() {
+                let norm = distance_2(center, &[0.0; 3]).sqrt();
+                if norm > 0.0 {
+                    *center = center.map(|n| n * MEAN_RADIUS / norm);
+                }
+            }
+        }
+    }
+
+    ids
+}
+
+#[cfg(test)]
+mod test {
+    use rusqlite::{Connection, Result};
+
+    const FRAME: &str = "ORDER BY id ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING";
+
+    fn clusters(conn: &Connection, call: &str) -> Result<Vec<(i64, Option<i64>)>> {
+        conn.prepare(&format!(
+            "SELECT id, {call} OVER ({FRAME}) FROM points ORDER BY id"
+        ))?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect()
+    }
+
+    fn setup(srid: u32, points: &[Option<(f64, f64)>]) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::register(&conn).unwrap();
+        conn.execute_batch("CREATE TABLE points(id INTEGER PRIMARY KEY, geom)")
+            .unwrap();
+
+        for point in points {
+            match point {
+                Some((x, y)) => conn.execute(
+                    "INSERT INTO points(geom) VALUES (ST_FromText(?1))",
+                    [format!("SRID={srid};POINT({x} {y})")],
+                ),
+                None => conn.execute("INSERT INTO points(geom) VALUES (NULL)", []),
+            }
+            .unwrap();
+        }
+
+        conn
+    }
+
+    #[test]
+    fn dbscan() {
+        let conn = setup(
+            0,
+            &[
+                Some((0.0, 0.0)),
+                Some((10.0, 10.0)),
+                Some((0.5, 0.0)),
+                None,
+                Some((10.5, 10.0)),
+                Some((50.0, 50.0)),
+            ],
+        );
+
+        assert_eq!(
+            clusters(&conn, "ST_ClusterDBSCAN(geom, 1.0, 2)").unwrap(),
+            [
+                (1, Some(0)),
+                (2, Some(1)),
+                (3, Some(0)),
+                (4, None),
+                (5, Some(1)),
+                // Noise
+                (6, None)
+            ]
+        );
+
+        // Every partition is clustered on its own
+        let partitioned = conn
+            .prepare(&format!(
+                "SELECT id, ST_ClusterDBSCAN(geom, 1.0, 1)
+                   OVER (PARTITION BY id % 2 {FRAME})
+                 FROM points ORDER BY id"
+            ))
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<Vec<(i64, Option<i64>)>>>()
+            .unwrap();
+        assert_eq!(
+            partitioned,
+            [
+                (1, Some(0)),
+                (2, Some(0)),
+                (3, Some(0)),
+                (4, None),
+                (5, Some(1)),
+                (6, Some(1))
+            ]
+        );
+
+        assert!(clusters(&conn, "ST_ClusterDBSCAN(geom, -1.0, 2)").is_err());
+    }
+
+    #[test]
+    fn geodetic_eps() {
+        // About 111 km apart along the equator
+        let points = [Some((0.0, 0.0)), Some((1.0, 0.0))];
+
+        let geodetic = setup(4326, &points);
+        assert_eq!(
+            clusters(&geodetic, "ST_ClusterDBSCAN(geom, 100000.0, 2)").unwrap(),
+            [(1, None), (2, None)]
+        );
+        assert_eq!(
+            clusters(&geodetic, "ST_ClusterDBSCAN(geom, 112000.0, 2)").unwrap(),
+            [(1, Some(0)), (2, Some(0))]
+        );
+
+        // The same coordinates on a plane are one unit apart
+        let planar = setup(3857, &points);
+        assert_eq!(
+            clusters(&planar, "ST_ClusterDBSCAN(geom, 1.0, 2)").unwrap(),
+            [(1, Some(0)), (2, Some(0))]
+        );
+        assert_eq!(
+            clusters(&planar, "ST_ClusterDBSCAN(geom, 0.5, 2)").unwrap(),
+            [(1, None), (2, None)]
+        );
+    }
+
+    #[test]
+    fn kmeans() {
+        let conn = setup(
+            0,
+            &[
+                Some((0.0, 0.0)),
+                Some((10.0, 10.0)),
+                Some((0.5, 0.0)),
+                None,
+                Some((10.5, 10.0)),
+            ],
+        );
+
+        assert_eq!(
+            clusters(&conn, "ST_ClusterKMeans(geom, 2)").unwrap(),
+            [
+                (1, Some(0)),
+                (2, Some(1)),
+                (3, Some(0)),
+                (4, None),
+                (5, Some(1))
+            ]
+        );
+
+        assert!(clusters(&conn, "ST_ClusterKMeans(geom, 0)").is_err());
+    }
+
+    #[test]
+    fn frames() {
+        let conn = setup(0, &[Some((0.0, 0.0)), Some((10.0, 10.0))]);
+        let query = |sql: &str| -> Result<Vec<Option<i64>>> {
+            conn.prepare(sql)?
+                .query_map([], |row| row.get(1))?
+                .collect()
+        };
+
+        // A growing frame
+        assert!(
+            query("SELECT id, ST_ClusterDBSCAN(geom, 1.0, 1) OVER (ORDER BY id) FROM points")
+                .is_err()
+        );
+
+        // A sliding frame
+        assert!(
+            query(
+                "SELECT id, ST_ClusterKMeans(geom, 2)
+                   OVER (ORDER BY id ROWS BETWEEN 1 PRECEDING AND CURRENT ROW)
+                 FROM points"
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,16 @@
+CREATE TRIGGER IF NOT EXISTS ${name}_encoding_insert
+  AFTER INSERT ON ${table}
+  WHEN ST_Encoding(new.${column}) <> ${encoding}
+BEGIN
+  UPDATE ${table}
+    SET ${column} = ST_AsCompact(new.${column}, ${encoding})
+    WHERE ${key};
+END;
+CREATE TRIGGER IF NOT EXISTS ${name}_encoding_update
+  AFTER UPDATE OF ${column} ON ${table}
+  WHEN ST_Encoding(new.${column}) <> ${encoding}
+BEGIN
+  UPDATE ${table}
+    SET ${column} = ST_AsCompact(new.${column}, ${encoding})
+    WHERE ${key};
+END;
@@ -1,16 +1,36 @@
-use core::fmt::{self, Write as _};
-
-use geo::{
-    BoundingRect, Centroid, ChamberlainDuquetteArea, Contains, Distance, GeodesicArea, Haversine,
-    Intersects, Within,
+use core::{
+    convert::Infallible,
+    fmt::{self, Write as _},
 };
+use std::cell::RefCell;
+
+use geo::{Contains, Distance, Euclidean, GeodesicArea, Haversine, Intersects, Within};
 use geo_traits::to_geo::{ToGeoGeometry, ToGeoPoint};
-use geob::{GeoType, Geob, types::GeometryRef};
-use rusqlite::{Connection, Error, Result, functions::FunctionFlags};
+use geob::{
+    Envelope, GeoType, Geob, SRID,
+    algorithm::Metric,
+    builder::BuilderError,
+    compact::{Compact, is_compact},
+    geohash::MAX_PRECISION,
+    projection::Transformer,
+    types::{GeometryRef, LineStringRef},
+};
+use rusqlite::{
+    Connection, Error, Result,
+    functions::{Aggregate, Context, FunctionFlags},
+    types::{Value, ValueRef},
+};
 
 use crate::template::{Lookup, replace};
 
+thread_local! {
+    /// Transforms used by ST_Transform. A proj handle is not `Send`, so the
+    /// cache lives per thread instead of in the function closure.
+    static TRANSFORMER: RefCell<Transformer> = RefCell::new(Transformer::new());
+}
+
 const COLUMN_TRIGGER: &str = include_str!("column_trigger.sql");
+const COMPACT_TRIGGER: &str = include_str!("compact_trigger.sql");
 
 pub fn register_functions(conn: &Connection) -> Result<bool> {
     conn.create_scalar_function(
@@ -60,7 +80,6 @@ pub fn register_functions(conn: &Connection) -> Result<bool> {
         },
     )?;
 
-    #[cfg(feature = "proj")]
     conn.create_scalar_function(
         "ST_Transform",
         2,
@@ -69,13 +88,9 @@ pub fn register_functions(conn: &Connection) -> Result<bool> {
             let mut text: Geob = ctx.get(0)?;
             let srid: u32 = ctx.get(1)?;
 
-            let text_srid: u32 = text.srid().into();
-
-            if srid == text_srid {
-                return Ok(text);
-            }
-
-            text.project(srid);
+            TRANSFORMER
+                .with_borrow_mut(|transformer| transformer.project(&mut text, srid.into()))
+                .map_err(|err| Error::UserFunctionError(err.into()))?;
 
             Ok(text)
         },
@@ -89,11 +104,9 @@ pub fn register_functions(conn: &Connection) -> Result<bool> {
             let a: Geob = ctx.get(0)?;
             let b: Geob = ctx.get(1)?;
 
-            if a.kind() != GeoType::Point {
-            } else if b.kind() != GeoType::Point {
-            }
+            ensure_same_srid(&a, &b)?;
 
-            if a.srid() != b.srid() {}
+            let geodetic = a.srid().is_geodetic();
 
             let a = a.geometry();
             let b = b.geometry();
@@ -103,11 +116,16 @@ pub fn register_functions(conn: &Connection) -> Result<bool> {
                     let a = a.to_point();
                     let b = b.to_point();
 
-                    let ditance = Haversine.distance(a, b);
-                    ditance
+                    if geodetic {
+                        Haversine.distance(a, b)
+                    } else {
+                        Euclidean.distance(a, b)
+                    }
                 }
                 _ => {
-                    todo!()
+                    return Err(Error::UserFunctionError(
+                        "ST_Distance only supports points".into(),
+                    ));
                 }
             };
 
@@ -119,7 +137,7 @@ pub fn register_functions(conn: &Connection) -> Result<bool> {
         let a: Geob = ctx.get(0)?;
         let b: Geob = ctx.get(1)?;
 
-        if a.srid() != b.srid() {}
+        ensure_same_srid(&a, &b)?;
 
         let a = a.geometry().to_geometry();
         let b = b.geometry().to_geometry();
@@ -135,7 +153,7 @@ pub fn register_functions(conn: &Connection) -> Result<bool> {
             let a: Geob = ctx.get(0)?;
             let b: Geob = ctx.get(1)?;
 
-            if a.srid() != b.srid() {}
+            ensure_same_srid(&a, &b)?;
 
             let a = a.geometry().to_geometry();
             let b = b.geometry().to_geometry();
@@ -152,9 +170,7 @@ pub fn register_functions(conn: &Connection) -> Result<bool> {
             let a: Geob = ctx.get(0)?;
             let b: Geob = ctx.get(1)?;
 
-            if a.srid() != b.srid() {
-                todo!("SRID")
-            }
+            ensure_same_srid(&a, &b)?;
 
             let a = a.geometry().to_geometry();
             let b = b.geometry().to_geometry();
@@ -170,46 +186,71 @@ pub fn register_functions(conn: &Connection) -> Result<bool> {
         |ctx| {
             let a: Geob = ctx.get(0)?;
 
-            let srid = a.srid();
-
-            let a = a.geometry().to_geometry();
-
-            let Some(out) = a.bounding_rect() else {
-                todo!()
+            let Some(envelope) = a.envelope() else {
+                return Ok(None);
             };
 
-            let geob = Geob::from_geo_type(&out.to_polygon(), srid);
-
-            Ok(geob)
+            Ok(Some(envelope.to_polygon(a.srid())))
         },
     )?;
 
-    conn.create_scalar_function("ST_AddColumn", 3, FunctionFlags::SQLITE_DIRECTONLY, |ctx| {
-        let table: String = ctx.get(0)?;
-        let column: String = ctx.get(1)?;
-        let srid: u32 = ctx.get(2)?;
+    conn.create_scalar_function(
+        "ST_AddColumn",
+        -1,
+        FunctionFlags::SQLITE_DIRECTONLY,
+        |ctx| {
+            if !(3..=4).contains(&ctx.len()) {
+                return Err(Error::UserFunctionError(
+                    "expected table, column, srid and an optional encoding".into(),
+                ));
+            }
+
+            let table: String = ctx.get(0)?;
+            let column: String = ctx.get(1)?;
+            let srid: u32 = ctx.get(2)?;
+            let compact = match ctx.len() {
+                4 => compact(ctx.get_raw(3))?,
+                _ => None,
+            };
+
+            let conn = unsafe { ctx.get_connection()? };
+
+            // Primary key columns locate the row to re-encode, as WITHOUT
+            // ROWID tables have no rowid
+            let key = conn
+                .prepare("SELECT name FROM pragma_table_info(?1) WHERE pk > 0 ORDER BY pk")?
+                .query_map([&table], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>>>()?;
 
-        let sql = replace(
-            COLUMN_TRIGGER,
-            &AddColumn {
+            let column = AddColumn {
                 table: &table,
                 column: &column,
                 srid,
-            },
-        )
-        .expect("Render");
+                compact,
+                key,
+            };
 
-        unsafe { ctx.get_connection()?.execute_batch(&sql) }?;
+            let mut sql = replace(COLUMN_TRIGGER, &column).expect("Render");
+            if compact.is_some() {
+                sql.push_str(&replace(COMPACT_TRIGGER, &column).expect("Render"));
+            }
 
-        Ok(true)
-    })?;
+            conn.execute_batch(&sql)?;
+
+            Ok(true)
+        },
+    )?;
 
     conn.create_scalar_function("ST_Area", 1, FunctionFlags::SQLITE_DETERMINISTIC, |ctx| {
         let a: Geob = ctx.get(0)?;
 
-        let a = a.geometry().to_geometry();
-
-        let area = a.geodesic_area_unsigned();
+        // geo's geodesic area is exact on the ellipsoid, geob's is an
+        // approximation on the authalic sphere
+        let area = if a.srid().is_geodetic() {
+            a.geometry().to_geometry().geodesic_area_unsigned()
+        } else {
+            a.geometry().area()
+        };
 
         Ok(area)
     })?;
@@ -218,12 +259,12 @@ pub fn register_functions(conn: &Connection) -> Result<bool> {
         let a: Geob = ctx.get(0)?;
         let accurate: bool = ctx.get(1)?;
 
-        let a = a.geometry().to_geometry();
-
-        let area = if accurate {
-            a.geodesic_area_signed()
+        let area = if !a.srid().is_geodetic() {
+            a.geometry().signed_area()
+        } else if accurate {
+            a.geometry().to_geometry().geodesic_area_signed()
         } else {
-            a.chamberlain_duquette_signed_area()
+            a.geometry().spherical_area_signed()
         };
 
         Ok(area)
@@ -236,11 +277,36 @@ pub fn register_functions(conn: &Connection) -> Result<bool> {
         |ctx| {
             let a: Geob = ctx.get(0)?;
 
-            let a = a.geometry().to_geometry();
+            let perimeter = if a.srid().is_geodetic() {
+                a.geometry().geodesic_perimeter()
+            } else {
+                a.geometry().perimeter()
+            };
+
+            Ok(perimeter)
+        },
+    )?;
+
+    conn.create_scalar_function("ST_Length", 1, FunctionFlags::SQLITE_DETERMINISTIC, |ctx| {
+        let a: Geob = ctx.get(0)?;
+
+        let length = if a.srid().is_geodetic() {
+            a.geometry().geodesic_length()
+        } else {
+            a.geometry().length()
+        };
 
-            let area = a.geodesic_perimeter();
+        Ok(length)
+    })?;
 
-            Ok(area)
+    conn.create_scalar_function(
+        "ST_NPoints",
+        1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let a: Geob = ctx.get(0)?;
+
+            Ok(a.geometry().num_points() as i64)
         },
     )?;
 
@@ -251,21 +317,558 @@ pub fn register_functions(conn: &Connection) -> Result<bool> {
         |ctx| {
             let geo: Geob = ctx.get(0)?;
 
-            let a = geo.geometry().to_geometry();
+            let centroid = geo.geometry().centroid();
+
+            Ok(centroid.map(|(x, y)| Geob::new_point(geo.srid(), x, y).unwrap()))
+        },
+    )?;
+
+    conn.create_scalar_function("ST_AsGPB", 1, FunctionFlags::SQLITE_DETERMINISTIC, |ctx| {
+        let geo: Geob = ctx.get(0)?;
+        Ok(geo.to_gpkg())
+    })?;
+
+    conn.create_scalar_function(
+        "ST_GeomFromGPB",
+        1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let blob: Vec<u8> = ctx.get(0)?;
+            Geob::from_gpkg(&blob).map_err(|err| Error::UserFunctionError(err.into()))
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "AsSpatiaLite",
+        1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let geo: Geob = ctx.get(0)?;
+            Ok(geo.to_spatialite())
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "GeomFromSpatiaLite",
+        1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let blob: Vec<u8> = ctx.get(0)?;
+            Geob::from_spatialite(&blob).map_err(|err| Error::UserFunctionError(err.into()))
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_AsTWKB",
+        -1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let geo: Geob = ctx.get(0)?;
+            let precision = match ctx.len() {
+                1 => 0,
+                2 => ctx.get::<i8>(1)?,
+                _ => {
+                    return Err(Error::UserFunctionError(
+                        "ST_AsTWKB expects 1 or 2 arguments".into(),
+                    ));
+                }
+            };
+
+            Ok(geo.to_twkb(precision))
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_GeomFromTWKB",
+        -1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let blob: Vec<u8> = ctx.get(0)?;
+            let srid = match ctx.len() {
+                1 => SRID::UNKNOWN,
+                2 => SRID::from(ctx.get::<u32>(1)?),
+                _ => {
+                    return Err(Error::UserFunctionError(
+                        "ST_GeomFromTWKB expects 1 or 2 arguments".into(),
+                    ));
+                }
+            };
+
+            Geob::from_twkb(&blob, srid).map_err(|err| Error::UserFunctionError(err.into()))
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_GeoHash",
+        -1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let geo: Geob = ctx.get(0)?;
+            let precision = match ctx.len() {
+                1 => MAX_PRECISION,
+                2 => ctx.get::<usize>(1)?,
+                _ => {
+                    return Err(Error::UserFunctionError(
+                        "ST_GeoHash expects 1 or 2 arguments".into(),
+                    ));
+                }
+            };
+
+            geo.geohash(precision)
+                .map_err(|err| Error::UserFunctionError(err.into()))
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_PointFromGeoHash",
+        -1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let hash: String = ctx.get(0)?;
+            let hash = match ctx.len() {
+                1 => &hash[..],
+                // A cut inside a multi-byte character leaves the invalid
+                // hash whole for from_geohash to reject
+                2 => hash.get(..ctx.get::<usize>(1)?).unwrap_or(&hash),
+                _ => {
+                    return Err(Error::UserFunctionError(
+                        "ST_PointFromGeoHash expects 1 or 2 arguments".into(),
+                    ));
+                }
+            };
+
+            Geob::from_geohash(hash).map_err(|err| Error::UserFunctionError(err.into()))
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_HilbertKey",
+        2,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let (geo, bounds) = curve_args(ctx)?;
+            Ok(geo.hilbert_key(&bounds).map(|key| key as i64))
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_MortonKey",
+        2,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let (geo, bounds) = curve_args(ctx)?;
+            Ok(geo.morton_key(&bounds).map(|key| key as i64))
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_AsBinary",
+        1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let geo: Geob = ctx.get(0)?;
+            Ok(geo.to_wkb())
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_GeomFromWKB",
+        -1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let blob: Vec<u8> = ctx.get(0)?;
+            let srid = match ctx.len() {
+                1 => SRID::UNKNOWN,
+                2 => SRID::from(ctx.get::<u32>(1)?),
+                _ => {
+                    return Err(Error::UserFunctionError(
+                        "ST_GeomFromWKB expects 1 or 2 arguments".into(),
+                    ));
+                }
+            };
+
+            Geob::from_wkb(&blob, srid).map_err(|err| Error::UserFunctionError(err.into()))
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_DelaunayTriangles",
+        1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let geo: Geob = ctx.get(0)?;
+            Ok(geo.delaunay_triangles())
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_VoronoiPolygons",
+        -1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let geo: Geob = ctx.get(0)?;
+            let extend_to = match ctx.len() {
+                1 => None,
+                2 => ctx.get::<Option<Geob>>(1)?,
+                _ => {
+                    return Err(Error::UserFunctionError(
+                        "ST_VoronoiPolygons expects 1 or 2 arguments".into(),
+                    ));
+                }
+            };
+
+            if let Some(extend_to) = &extend_to {
+                ensure_same_srid(&geo, extend_to)?;
+            }
+
+            let envelope = extend_to.and_then(|geo| geo.envelope());
+            Ok(geo.voronoi_polygons(envelope.as_ref()))
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_LineInterpolatePoint",
+        2,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let geo: Geob = ctx.get(0)?;
+            let fraction = fraction(ctx.get(1)?)?;
+            let line = line_string(&geo)?;
+
+            let point = line.interpolate_point(fraction, Metric::for_srid(geo.srid()));
+            Ok(point.map(|(x, y)| Geob::new_point(geo.srid(), x, y).unwrap()))
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_LineLocatePoint",
+        2,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let (geo, point): (Geob, Geob) = (ctx.get(0)?, ctx.get(1)?);
+            ensure_same_srid(&geo, &point)?;
+
+            let line = line_string(&geo)?;
+            let GeometryRef::Point(point) = point.geometry() else {
+                return Err(Error::UserFunctionError("expected a point".into()));
+            };
+
+            Ok(line.locate_point(point.x(), point.y(), Metric::for_srid(geo.srid())))
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_LineSubstring",
+        3,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let geo: Geob = ctx.get(0)?;
+            let (start, end) = (fraction(ctx.get(1)?)?, fraction(ctx.get(2)?)?);
+            let line = line_string(&geo)?;
+
+            if start > end {
+                return Err(Error::UserFunctionError(
+                    format!("start fraction is past the end: {start} > {end}").into(),
+                ));
+            }
+
+            let Some(coords) = line.substring(start, end, Metric::for_srid(geo.srid())) else {
+                return Ok(None);
+            };
+
+            if let [(x, y)] = coords[..] {
+                return Ok(Some(Geob::new_point(geo.srid(), x, y).unwrap()));
+            }
+
+            let mut builder = Geob::builder(geo.srid());
+            builder.begin_line_string().map_err(builder_error)?;
+            for (x, y) in coords {
+                builder.coord(x, y).map_err(builder_error)?;
+            }
+            builder.end().map_err(builder_error)?;
+
+            builder.build().map(Some).map_err(builder_error)
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_ClosestPoint",
+        2,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let (a, b): (Geob, Geob) = (ctx.get(0)?, ctx.get(1)?);
+            ensure_same_srid(&a, &b)?;
+
+            let points = a
+                .geometry()
+                .closest_points(&b.geometry(), Metric::for_srid(a.srid()));
+
+            Ok(points.map(|((x, y), _)| Geob::new_point(a.srid(), x, y).unwrap()))
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_ShortestLine",
+        2,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let (a, b): (Geob, Geob) = (ctx.get(0)?, ctx.get(1)?);
+            ensure_same_srid(&a, &b)?;
+
+            let Some((from, to)) = a
+                .geometry()
+                .closest_points(&b.geometry(), Metric::for_srid(a.srid()))
+            else {
+                return Ok(None);
+            };
+
+            let mut builder = Geob::builder(a.srid());
+            builder.begin_line_string().map_err(builder_error)?;
+            builder.coord(from.0, from.1).map_err(builder_error)?;
+            builder.coord(to.0, to.1).map_err(builder_error)?;
+            builder.end().map_err(builder_error)?;
+
+            builder.build().map(Some).map_err(builder_error)
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_Azimuth",
+        2,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let (a, b): (Geob, Geob) = (ctx.get(0)?, ctx.get(1)?);
+            ensure_same_srid(&a, &b)?;
+
+            let metric = Metric::for_srid(a.srid());
+            let (GeometryRef::Point(a), GeometryRef::Point(b)) = (a.geometry(), b.geometry())
+            else {
+                return Err(Error::UserFunctionError("expected two points".into()));
+            };
+
+            Ok(metric.azimuth((a.x(), a.y()), (b.x(), b.y())))
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_Project",
+        3,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let geo: Geob = ctx.get(0)?;
+            let (distance, azimuth): (f64, f64) = (ctx.get(1)?, ctx.get(2)?);
+
+            let GeometryRef::Point(point) = geo.geometry() else {
+                return Err(Error::UserFunctionError("expected a point".into()));
+            };
+
+            let (x, y) =
+                Metric::for_srid(geo.srid()).destination((point.x(), point.y()), distance, azimuth);
+
+            Ok(Geob::new_point(geo.srid(), x, y).unwrap())
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_Segmentize",
+        2,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let geo: Geob = ctx.get(0)?;
+            let max_length: f64 = ctx.get(1)?;
+
+            if !(max_length.is_finite() && max_length > 0.0) {
+                return Err(Error::UserFunctionError(
+                    format!("max segment length must be positive: {max_length}").into(),
+                ));
+            }
+
+            geo.segmentize(max_length)
+                .map_err(|err| Error::UserFunctionError(err.into()))
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_AsCompact",
+        -1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let geo: Geob = ctx.get(0)?;
+            let compact = match ctx.len() {
+                1 => Some(Compact::F32),
+                2 => compact(ctx.get_raw(1))?,
+                _ => {
+                    return Err(Error::UserFunctionError(
+                        "ST_AsCompact expects 1 or 2 arguments".into(),
+                    ));
+                }
+            };
+
+            let Some(compact) = compact else {
+                return Ok(Value::from(geo));
+            };
+
+            geo.to_compact(compact)
+                .map(Value::from)
+                .map_err(|err| Error::UserFunctionError(err.into()))
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_IsCompact",
+        1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| match ctx.get_raw(0) {
+            ValueRef::Null => Ok(None),
+            ValueRef::Blob(blob) => Ok(Some(is_compact(blob))),
+            _ => Ok(Some(false)),
+        },
+    )?;
 
-            let area = a.centroid();
+    conn.create_scalar_function(
+        "ST_Encoding",
+        1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let geo: Option<Geob> = ctx.get(0)?;
 
-            Ok(area.map(|point| Geob::new_point(geo.srid(), point.x(), point.y()).unwrap()))
+            Ok(geo.map(|geo| match geo.compact() {
+                None => Value::Text("f64".into()),
+                Some(Compact::F32) => Value::Text("f32".into()),
+                Some(Compact::Quantized { scale, .. }) => Value::Real(scale),
+            }))
         },
     )?;
 
+    conn.create_aggregate_function(
+        "ST_Collect",
+        1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        Collect,
+    )?;
+
     Ok(true)
 }
 
+pub(crate) fn ensure_same_srid(a: &Geob, b: &Geob) -> Result<()> {
+    if a.srid() != b.srid() {
+        return Err(Error::UserFunctionError(
+            format!("SRID mismatch: {} and {}", a.srid(), b.srid()).into(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// `ST_Collect(geom)` gathers the rows into a multi geometry when they share
+/// a type, or a geometry collection otherwise. NULL rows are skipped.
+struct Collect;
+
+impl Aggregate<Vec<Geob>, Option<Geob>> for Collect {
+    fn init(&self, _ctx: &mut Context<'_>) -> Result<Vec<Geob>> {
+        Ok(Vec::new())
+    }
+
+    fn step(&self, ctx: &mut Context<'_>, acc: &mut Vec<Geob>) -> Result<()> {
+        let Some(geo) = ctx.get::<Option<Geob>>(0)? else {
+            return Ok(());
+        };
+
+        if let Some(first) = acc.first() {
+            ensure_same_srid(first, &geo)?;
+        }
+
+        acc.push(geo);
+
+        Ok(())
+    }
+
+    fn finalize(&self, _ctx: &mut Context<'_>, acc: Option<Vec<Geob>>) -> Result<Option<Geob>> {
+        let Some(first) = acc.as_ref().and_then(|acc| acc.first()) else {
+            return Ok(None);
+        };
+        let acc = acc.as_deref().unwrap_or_default();
+
+        let kind = first.kind();
+        let kind = if acc.iter().all(|geo| geo.kind() == kind) {
+            kind
+        } else {
+            GeoType::Collection
+        };
+
+        let mut builder = Geob::builder(first.srid());
+        match kind {
+            GeoType::Point => builder.begin_multi_point(),
+            GeoType::LineString => builder.begin_multi_line_string(),
+            GeoType::Polygon => builder.begin_multi_polygon(),
+            _ => builder.begin_collection(),
+        }
+        .map_err(builder_error)?;
+
+        for geo in acc {
+            builder.geometry(&geo.geometry()).map_err(builder_error)?;
+        }
+
+        builder.end().map_err(builder_error)?;
+
+        builder.build().map(Some).map_err(builder_error)
+    }
+}
+
+/// Coordinate encoding: `'f64'`, `'f32'` or the scale of a quantized grid
+fn compact(value: ValueRef<'_>) -> Result<Option<Compact>> {
+    match value {
+        ValueRef::Text(b"f64") => Ok(None),
+        ValueRef::Text(b"f32") => Ok(Some(Compact::F32)),
+        ValueRef::Integer(scale) => Ok(Some(Compact::quantized(scale as f64))),
+        ValueRef::Real(scale) => Ok(Some(Compact::quantized(scale))),
+        _ => Err(Error::UserFunctionError(
+            "encoding must be 'f64', 'f32' or a grid scale".into(),
+        )),
+    }
+}
+
+/// `(geom, bounds)` of `ST_HilbertKey` and `ST_MortonKey`
+fn curve_args(ctx: &Context<'_>) -> Result<(Geob, Envelope)> {
+    let (geo, bounds): (Geob, Geob) = (ctx.get(0)?, ctx.get(1)?);
+    ensure_same_srid(&geo, &bounds)?;
+
+    let Some(envelope) = bounds.envelope() else {
+        return Err(Error::UserFunctionError("bounds must not be empty".into()));
+    };
+
+    Ok((geo, envelope))
+}
+
+fn line_string(geo: &Geob) -> Result<LineStringRef<'_>> {
+    match geo.geometry() {
+        GeometryRef::LineString(line) => Ok(line),
+        _ => Err(Error::UserFunctionError(
+            format!("expected a linestring: {}", geo.kind()).into(),
+        )),
+    }
+}
+
+fn fraction(fraction: f64) -> Result<f64> {
+    if (0.0..=1.0).contains(&fraction) {
+        Ok(fraction)
+    } else {
+        Err(Error::UserFunctionError(
+            format!("fraction must be between 0 and 1: {fraction}").into(),
+        ))
+    }
+}
+
+fn builder_error(err: BuilderError<Infallible>) -> Error {
+    Error::UserFunctionError(err.into())
+}
+
 struct AddColumn<'a> {
     table: &'a str,
     column: &'a str,
     srid: u32,
+    compact: Option<Compact>,
+    key: Vec<String>,
 }
 
 impl<'a> Lookup for AddColumn<'a> {
@@ -283,9 +886,158 @@ impl<'a> Lookup for AddColumn<'a> {
             "srid" => {
                 write!(output, "{}", self.srid)?;
             }
+            "encoding" => match self.compact {
+                Some(Compact::Quantized { scale, .. }) => write!(output, "{scale:?}")?,
+                Some(Compact::F32) => output.push_str("'f32'"),
+                None => output.push_str("'f64'"),
+            },
+            "key" if self.key.is_empty() => {
+                output.push_str("rowid = new.rowid");
+            }
+            "key" => {
+                for (idx, key) in self.key.iter().enumerate() {
+                    if idx > 0 {
+                        output.push_str(" AND ");
+                    }
+                    write!(output, "\"{key}\" = new.\"{key}\"")?;
+                }
+            }
             _ => return Err(fmt::Error),
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use geob::{Geob, compact::Compact};
+    use rusqlite::{Connection, Result};
+
+    fn setup(encoding: &str) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::register(&conn).unwrap();
+        conn.execute_batch(&format!(
+            "CREATE TABLE places(id INTEGER PRIMARY KEY, geom) WITHOUT ROWID;
+             SELECT ST_AddColumn('places', 'geom', 4326, {encoding});"
+        ))
+        .unwrap();
+        conn
+    }
+
+    fn insert(conn: &Connection, id: i64, geom: &str) -> Result<usize> {
+        conn.execute(
+            &format!("INSERT INTO places VALUES (?1, {geom})"),
+            (id, "SRID=4326;POINT(10.5 59.25)"),
+        )
+    }
+
+    #[test]
+    fn compact_column() {
+        let conn = setup("'f32'");
+        let encoding = |conn: &Connection, id: i64| -> Result<(Geob, String)> {
+            conn.query_row(
+                "SELECT geom, ST_Encoding(geom) FROM places WHERE id = ?1",
+                [id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+        };
+
+        let changes = conn.total_changes();
+        insert(&conn, 1, "ST_AsCompact(ST_FromText(?2), 'f32')").unwrap();
+        // Already in the column encoding, so written once
+        assert_eq!(conn.total_changes() - changes, 1);
+
+        let (geo, name) = encoding(&conn, 1).unwrap();
+        assert_eq!(name, "f32");
+        assert_eq!(geo.compact(), Some(Compact::F32));
+        assert_eq!(geo.to_string(), "SRID=4326;POINT(10.5 59.25)");
+
+        // Other encodings are converted on write
+        insert(&conn, 2, "ST_FromText(?2)").unwrap();
+        insert(&conn, 3, "ST_AsCompact(ST_FromText(?2), 1e-7)").unwrap();
+        assert_eq!(encoding(&conn, 2).unwrap().1, "f32");
+        assert_eq!(encoding(&conn, 3).unwrap().1, "f32");
+
+        let geo = Geob::from_text("SRID=4326;POINT(1.5 2.5)").unwrap();
+        conn.execute("UPDATE places SET geom = ?1 WHERE id = 3", [&geo])
+            .unwrap();
+        assert_eq!(
+            encoding(&conn, 3).unwrap(),
+            (geo.to_compact(Compact::F32).unwrap(), "f32".into())
+        );
+
+        // Out of range of f32
+        assert!(
+            conn.execute(
+                "INSERT INTO places VALUES (4, ST_FromText('SRID=4326;POINT(1e300 0)'))",
+                []
+            )
+            .is_err()
+        );
+        assert!(
+            conn.query_row(
+                "SELECT ST_AsCompact(ST_FromText('SRID=0;POINT(1e300 0)'), 'f32')",
+                [],
+                |row| row.get::<_, Geob>(0)
+            )
+            .is_err()
+        );
+
+        let quantized = setup("1e-7");
+        insert(&quantized, 1, "ST_FromText(?2)").unwrap();
+        insert(&quantized, 2, "ST_AsCompact(ST_FromText(?2), 'f32')").unwrap();
+        insert(&quantized, 3, "ST_AsCompact(ST_FromText(?2), 1e-6)").unwrap();
+        for id in 1..=3 {
+            let scale: f64 = quantized
+                .query_row(
+                    "SELECT ST_Encoding(geom) FROM places WHERE id = ?1",
+                    [id],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            assert_eq!(scale, 1e-7);
+        }
+
+        // Tables with a rowid
+        let conn = Connection::open_in_memory().unwrap();
+        crate::register(&conn).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE places(name TEXT, geom);
+             SELECT ST_AddColumn('places', 'geom', 4326, 'f32');
+             INSERT INTO places VALUES ('a', ST_FromText('SRID=4326;POINT(1 2)'));
+             INSERT INTO places VALUES ('b', ST_AsCompact(ST_FromText('SRID=4326;POINT(3 4)'), 'f32'));",
+        )
+        .unwrap();
+        let encodings = conn
+            .prepare("SELECT ST_Encoding(geom) FROM places ORDER BY name")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<String>>>()
+            .unwrap();
+        assert_eq!(encodings, ["f32", "f32"]);
+    }
+
+    #[test]
+    fn point_from_geohash() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::register(&conn).unwrap();
+
+        let point = |args: &str| {
+            conn.query_row(
+                &format!("SELECT ST_ToText(ST_PointFromGeoHash({args}))"),
+                [],
+                |row| row.get::<_, String>(0),
+            )
+        };
+
+        assert_eq!(
+            point("'u4pruydqqvj', 100").unwrap(),
+            point("'u4pruydqqvj'").unwrap()
+        );
+        assert_eq!(point("'u4pruydqqvj', 1").unwrap(), point("'u'").unwrap());
+        assert!(point("'é', 1").is_err());
+        assert!(point("'ué', 2").is_err());
+    }
+}
@@ -0,0 +1,339 @@
+use std::{ffi::c_int, marker::PhantomData};
+
+use geob::{
+    Geob, SRID,
+    grid::{Grid, GridKind},
+    types::GeometryRef,
+};
+use rusqlite::{
+    Connection, Error, Result, ffi,
+    functions::{Context, FunctionFlags},
+    vtab::{
+        self, Filters, IndexConstraintOp, IndexInfo, VTab, VTabConfig, VTabConnection, VTabCursor,
+    },
+};
+
+const SIZE_IDX: c_int = 3;
+const BOUNDS_IDX: c_int = 4;
+
+pub fn register_functions(conn: &Connection) -> Result<()> {
+    conn.create_module(
+        "ST_SquareGrid",
+        vtab::eponymous_only_module::<GridTab>(),
+        Some(GridKind::Square),
+    )?;
+
+    conn.create_module(
+        "ST_HexagonGrid",
+        vtab::eponymous_only_module::<GridTab>(),
+        Some(GridKind::Hexagon),
+    )?;
+
+    conn.create_scalar_function(
+        "ST_Square",
+        -1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| cell(ctx, GridKind::Square),
+    )?;
+
+    conn.create_scalar_function(
+        "ST_Hexagon",
+        -1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| cell(ctx, GridKind::Hexagon),
+    )?;
+
+    Ok(())
+}
+
+/// `ST_Square(size, i, j[, origin])` and `ST_Hexagon(size, i, j[, origin])`.
+/// The cell takes the SRID of the origin point.
+fn cell(ctx: &Context<'_>, kind: GridKind) -> Result<Geob> {
+    if !(3..=4).contains(&ctx.len()) {
+        return Err(Error::UserFunctionError(
+            "expected size, i, j and an optional origin".into(),
+        ));
+    }
+
+    let mut grid = Grid::new(kind, size(ctx.get(0)?)?);
+    let (i, j) = (ctx.get(1)?, ctx.get(2)?);
+
+    let mut srid = SRID::UNKNOWN;
+    if ctx.len() == 4 {
+        let origin: Geob = ctx.get(3)?;
+        let GeometryRef::Point(point) = origin.geometry() else {
+            return Err(Error::UserFunctionError("origin must be a point".into()));
+        };
+        grid = grid.with_origin(point.x(), point.y());
+        srid = origin.srid();
+    }
+
+    Ok(grid.cell(i, j, srid))
+}
+
+fn size(size: f64) -> Result<f64> {
+    if size.is_finite() && size > 0.0 {
+        Ok(size)
+    } else {
+        Err(Error::UserFunctionError(
+            format!("grid size must be positive: {size}").into(),
+        ))
+    }
+}
+
+/// Cells of a grid covering the envelope of `bounds`, as `(i, j, geom)` rows
+#[repr(C)]
+struct GridTab {
+    base: ffi::sqlite3_vtab,
+    kind: GridKind,
+}
+
+unsafe impl<'vtab> VTab<'vtab> for GridTab {
+    type Aux = GridKind;
+    type Cursor = GridCursor<'vtab>;
+
+    fn connect(
+        db: &mut VTabConnection,
+        aux: Option<&GridKind>,
+        _args: &[&[u8]],
+    ) -> Result<(String, Self)> {
+        let vtab = GridTab {
+            base: ffi::sqlite3_vtab::default(),
+            kind: aux.copied().unwrap_or(GridKind::Square),
+        };
+        db.config(VTabConfig::Innocuous)?;
+
+        Ok((
+            "CREATE TABLE x(i INTEGER, j INTEGER, geom, size HIDDEN, bounds HIDDEN)".to_owned(),
+            vtab,
+        ))
+    }
+
+    fn best_index(&self, info: &mut IndexInfo) -> Result<()> {
+        let mut a_idx: [Option<usize>; 2] = [None, None];
+
+        for (i, c) in info.constraints().enumerate() {
+            if !c.is_usable() || c.operator() != IndexConstraintOp::SQLITE_INDEX_CONSTRAINT_EQ {
+                continue;
+            }
+
+            match c.column() {
+                SIZE_IDX => a_idx[0] = Some(i),
+                BOUNDS_IDX => a_idx[1] = Some(i),
+                _ => {}
+            }
+        }
+
+        let [Some(size), Some(bounds)] = a_idx else {
+            // Without both arguments there is nothing to generate
+            info.set_idx_num(0);
+            info.set_estimated_cost(f64::MAX);
+            return Ok(());
+        };
+
+        for (n, idx) in [size, bounds].into_iter().enumerate() {
+            let mut usage = info.constraint_usage(idx);
+            usage.set_argv_index(n as c_int + 1);
+            usage.set_omit(true);
+        }
+
+        info.set_idx_num(1);
+        info.set_estimated_cost(1000.);
+        info.set_estimated_rows(1000);
+
+        Ok(())
+    }
+
+    fn open(&mut self) -> Result<GridCursor<'_>> {
+        Ok(GridCursor {
+            base: ffi::sqlite3_vtab_cursor::default(),
+            phantom: PhantomData,
+            grid: Grid::new(self.kind, 1.0),
+            srid: SRID::UNKNOWN,
+            iter: None,
+            next: None,
+            row_id: 0,
+        })
+    }
+}
+
+#[repr(C)]
+struct GridCursor<'vtab> {
+    base: ffi::sqlite3_vtab_cursor,
+    phantom: PhantomData<&'vtab GridTab>,
+    grid: Grid,
+    srid: SRID,
+    iter: Option<Box<dyn Iterator<Item = (i64, i64)>>>,
+    next: Option<(i64, i64)>,
+    row_id: i64,
+}
+
+unsafe impl VTabCursor for GridCursor<'_> {
+    fn filter(&mut self, idx_num: c_int, _idx_str: Option<&str>, args: &Filters<'_>) -> Result<()> {
+        self.iter = None;
+        self.next = None;
+        self.row_id = 0;
+
+        if idx_num == 0 {
+            return Ok(());
+        }
+
+        let size_arg = args.get::<Option<f64>>(0)?;
+        let bounds = args.get::<Option<Geob>>(1)?;
+        let (Some(size_arg), Some(bounds)) = (size_arg, bounds) else {
+            return Ok(());
+        };
+
+        self.grid.size = size(size_arg)?;
+        self.srid = bounds.srid();
+
+        if let Some(envelope) = bounds.envelope() {
+            let mut iter = Box::new(self.grid.cells(&envelope));
+            self.next = iter.next();
+            self.iter = Some(iter);
+        }
+
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<()> {
+        self.next = self.iter.as_mut().and_then(|m| m.next());
+        self.row_id += 1;
+        Ok(())
+    }
+
+    fn eof(&self) -> bool {
+        self.next.is_none()
+    }
+
+    fn column(&self, ctx: &mut vtab::Context, i: c_int) -> Result<()> {
+        let Some((ci, cj)) = self.next else {
+            return Ok(());
+        };
+
+        match i {
+            0 => ctx.set_result(&ci),
+            1 => ctx.set_result(&cj),
+            2 => ctx.set_result(&self.grid.cell(ci, cj, self.srid)),
+            SIZE_IDX => ctx.set_result(&self.grid.size),
+            _ => Ok(()),
+        }
+    }
+
+    fn rowid(&self) -> Result<i64> {
+        Ok(self.row_id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use geob::{
+        Geob, SRID,
+        grid::{Grid, GridKind},
+    };
+    use rusqlite::Connection;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::register(&conn).unwrap();
+        conn
+    }
+
+    fn rows(conn: &Connection, sql: &str) -> rusqlite::Result<Vec<(i64, i64, Geob)>> {
+        let mut stmt = conn.prepare(sql)?;
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect()
+    }
+
+    #[test]
+    fn grid_rows() {
+        let conn = setup();
+
+        let cells = rows(
+            &conn,
+            "SELECT i, j, geom FROM ST_SquareGrid(2, \
+             ST_FromText('SRID=3857;POLYGON((0 0, 4 0, 4 4, 0 4, 0 0))')) ORDER BY i, j",
+        )
+        .unwrap();
+
+        let grid = Grid::new(GridKind::Square, 2.0);
+        let expected: Vec<_> = [(0, 0), (0, 1), (1, 0), (1, 1)]
+            .into_iter()
+            .map(|(i, j)| (i, j, grid.cell(i, j, SRID::WEB_MERCATOR)))
+            .collect();
+        assert_eq!(cells, expected);
+        assert!(
+            cells
+                .iter()
+                .all(|(_, _, geo)| geo.srid() == SRID::WEB_MERCATOR)
+        );
+
+        let hexagons = rows(
+            &conn,
+            "SELECT i, j, geom FROM ST_HexagonGrid(1, ST_FromText('SRID=4326;POINT(0 0)'))",
+        )
+        .unwrap();
+        assert!(!hexagons.is_empty());
+        let grid = Grid::new(GridKind::Hexagon, 1.0);
+        for (i, j, geo) in hexagons {
+            assert_eq!(geo, grid.cell(i, j, SRID::WGS84));
+        }
+    }
+
+    #[test]
+    fn grid_missing_arguments() {
+        let conn = setup();
+
+        for sql in [
+            "SELECT i, j, geom FROM ST_SquareGrid",
+            "SELECT i, j, geom FROM ST_SquareGrid(2)",
+            "SELECT i, j, geom FROM ST_HexagonGrid WHERE size = 2",
+            "SELECT i, j, geom FROM ST_SquareGrid(2, NULL)",
+        ] {
+            assert_eq!(rows(&conn, sql).unwrap(), [], "{sql}");
+        }
+
+        assert!(
+            rows(
+                &conn,
+                "SELECT i, j, geom FROM ST_SquareGrid(0, ST_FromText('SRID=0;POINT(0 0)'))"
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn cell_functions() {
+        let conn = setup();
+
+        let cell: Geob = conn
+            .query_row(
+                "SELECT ST_Square(2, 1, -1, ST_FromText('SRID=3857;POINT(10 20)'))",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let grid = Grid::new(GridKind::Square, 2.0).with_origin(10.0, 20.0);
+        assert_eq!(cell, grid.cell(1, -1, SRID::WEB_MERCATOR));
+
+        let cell: Geob = conn
+            .query_row("SELECT ST_Hexagon(1, 2, 3)", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(
+            cell,
+            Grid::new(GridKind::Hexagon, 1.0).cell(2, 3, SRID::UNKNOWN)
+        );
+
+        for sql in [
+            "SELECT ST_Square(2, 0, 0, ST_FromText('SRID=0;LINESTRING(0 0, 1 1)'))",
+            "SELECT ST_Hexagon(-1, 0, 0)",
+            "SELECT ST_Square(2, 0)",
+        ] {
+            assert!(
+                conn.query_row(sql, [], |row| row.get::<_, Geob>(0))
+                    .is_err(),
+                "{sql}"
+            );
+        }
+    }
+}
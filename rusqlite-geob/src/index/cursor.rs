@@ -65,10 +65,15 @@ unsafe impl<'vtab> VTabCursor for SpartialIndexCursor<'vtab> {
 
         if idx_num.contains(QueryPlanFlags::ID_EQ) {
             query.id_eq = Some(args.get(i)?);
-            i += 1;
         }
 
-        self.iter = Some(unsafe { transmute(self.vtab().tree.select(self.vtab().srid, query)?) });
+        let iter = self.vtab().tree.select(self.vtab().srid, query)?;
+        self.iter = Some(unsafe {
+            transmute::<
+                Box<dyn Iterator<Item = (u64, Geob)> + '_>,
+                Box<dyn Iterator<Item = (u64, Geob)> + 'vtab>,
+            >(iter)
+        });
         self.next = self.iter.as_mut().unwrap().next();
         Ok(())
     }
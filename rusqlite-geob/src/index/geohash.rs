@@ -0,0 +1,170 @@
+use geob::{
+    Envelope, Geob, SRID,
+    algorithm::geodesic::{MEAN_RADIUS, haversine},
+    geohash::{self, MAX_PRECISION},
+    types::GeometryRef,
+};
+use rusqlite::{Connection, Error, Result};
+
+/// Points stored in the shadow table `<index>_geohash` with an ordinary
+/// SQLite index on the geohash column, so queries become prefix range scans
+pub struct GeoHashTree {
+    conn: Connection,
+    table: String,
+}
+
+impl GeoHashTree {
+    /// Open the shadow table of the index, creating it if missing
+    pub fn open(conn: Connection, index: &str) -> Result<GeoHashTree> {
+        let table = format!("{index}_geohash");
+
+        conn.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS {table}(
+               id INTEGER PRIMARY KEY,
+               hash TEXT NOT NULL,
+               x REAL NOT NULL,
+               y REAL NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS {table}_hash ON {table}(hash);"
+        ))?;
+
+        Ok(GeoHashTree { conn, table })
+    }
+
+    /// Drop the shadow table
+    pub fn destroy(&self) -> Result<()> {
+        self.conn
+            .execute_batch(&format!("DROP TABLE IF EXISTS {}", self.table))
+    }
+
+    pub fn clear(&mut self) -> Result<()> {
+        self.conn
+            .execute(&format!("DELETE FROM {}", self.table), [])?;
+        Ok(())
+    }
+
+    pub fn insert(&mut self, id: u64, geo: &Geob) -> Result<()> {
+        let (x, y) = match geo.geometry() {
+            GeometryRef::Point(point) => (point.x(), point.y()),
+            _ => {
+                return Err(Error::ModuleError(
+                    "Index require as Point type".to_string(),
+                ));
+            }
+        };
+
+        let hash = geohash::encode(x, y, MAX_PRECISION)
+            .map_err(|err| Error::ModuleError(err.to_string()))?;
+
+        self.conn
+            .prepare_cached(&format!(
+                "INSERT OR REPLACE INTO {}(id, hash, x, y) VALUES (?1, ?2, ?3, ?4)",
+                self.table
+            ))?
+            .execute((id as i64, hash, x, y))?;
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: u64) -> Result<Option<u64>> {
+        let changes = self
+            .conn
+            .prepare_cached(&format!("DELETE FROM {} WHERE id = ?1", self.table))?
+            .execute([id as i64])?;
+
+        Ok((changes > 0).then_some(id))
+    }
+
+    pub fn len(&self) -> Result<usize> {
+        self.conn
+            .query_row(&format!("SELECT count(*) FROM {}", self.table), [], |row| {
+                row.get(0)
+            })
+    }
+
+    /// All points in geohash order
+    pub fn iter(&self) -> Result<Vec<(u64, (f64, f64))>> {
+        self.query(
+            &format!("SELECT id, x, y FROM {} ORDER BY hash", self.table),
+            [],
+        )
+    }
+
+    /// Points inside the envelope of the geometry
+    pub fn select_envelope(&self, srid: SRID, geo: &Geob) -> Result<Vec<(u64, Geob)>> {
+        let points = match geo.envelope() {
+            Some(envelope) => self.within_envelope(envelope)?,
+            None => Vec::new(),
+        };
+
+        Ok(points
+            .into_iter()
+            .map(|(id, (x, y))| (id, Geob::new_point(srid, x, y).unwrap()))
+            .collect())
+    }
+
+    /// Points inside the envelope
+    pub fn within_envelope(&self, envelope: Envelope) -> Result<Vec<(u64, (f64, f64))>> {
+        Ok(self
+            .scan(&envelope)?
+            .into_iter()
+            .filter(|(_, (x, y))| envelope.contains(*x, *y))
+            .collect())
+    }
+
+    /// Points within `distance` meters of the point. The search area does not
+    /// wrap around the antimeridian.
+    pub fn within_distance(&self, x: f64, y: f64, distance: f64) -> Result<Vec<(u64, (f64, f64))>> {
+        let d_lat = (distance / MEAN_RADIUS).to_degrees();
+        let d_lon = d_lat / y.to_radians().cos().max(f64::EPSILON);
+
+        let envelope = if d_lon >= 180.0 {
+            Envelope::new(-180.0, y - d_lat, 180.0, y + d_lat)
+        } else {
+            Envelope::new(x - d_lon, y - d_lat, x + d_lon, y + d_lat)
+        };
+
+        Ok(self
+            .scan(&envelope)?
+            .into_iter()
+            .filter(|(_, (px, py))| haversine(x, y, *px, *py) < distance)
+            .collect())
+    }
+
+    /// Candidates from the cells covering the envelope, at the finest
+    /// precision where a cell is at least as large as the envelope
+    fn scan(&self, envelope: &Envelope) -> Result<Vec<(u64, (f64, f64))>> {
+        let precision = (1..=MAX_PRECISION)
+            .take_while(|p| {
+                let (width, height) = geohash::cell_size(*p);
+                width >= envelope.width() && height >= envelope.height()
+            })
+            .last()
+            .unwrap_or(1);
+
+        let Ok(prefixes) = geohash::cover(envelope, precision) else {
+            return Ok(Vec::new());
+        };
+
+        // '~' sorts after every character of the geohash alphabet
+        let sql = format!(
+            "SELECT id, x, y FROM {} WHERE hash >= ?1 AND hash < ?1 || '~'",
+            self.table
+        );
+
+        let mut output = Vec::new();
+        for prefix in prefixes {
+            output.extend(self.query(&sql, [prefix])?);
+        }
+
+        Ok(output)
+    }
+
+    fn query<P: rusqlite::Params>(&self, sql: &str, params: P) -> Result<Vec<(u64, (f64, f64))>> {
+        let mut stmt = self.conn.prepare_cached(sql)?;
+        let rows = stmt.query_map(params, |row| {
+            Ok((row.get::<_, i64>(0)? as u64, (row.get(1)?, row.get(2)?)))
+        })?;
+        rows.collect()
+    }
+}
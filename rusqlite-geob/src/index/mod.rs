@@ -9,6 +9,7 @@ use rusqlite::{
 };
 
 mod cursor;
+mod geohash;
 mod tree;
 mod types;
 
@@ -17,7 +18,7 @@ use crate::{
     template::{Lookup, replace},
 };
 
-use self::{cursor::SpartialIndexCursor, tree::RStarTree};
+use self::{cursor::SpartialIndexCursor, geohash::GeoHashTree, tree::RStarTree};
 
 use self::types::GeometryType;
 
@@ -31,13 +32,22 @@ pub fn register_module(conn: &Connection) -> Result<()> {
     )
 }
 
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum IndexKind {
+    #[default]
+    RTree,
+    Disabled,
+    /// Points in an ordinary SQLite index on a geohash column
+    GeoHash,
+}
+
 #[derive(Default)]
 struct Options<'a> {
     table: Option<&'a str>,
     column: Option<&'a str>,
     srid: Option<u32>,
     ty: Option<GeometryType>,
-    index: bool,
+    index: IndexKind,
 }
 
 #[repr(C)]
@@ -66,7 +76,6 @@ unsafe impl<'vtab> VTab<'vtab> for SpartialIndex {
         }
 
         let mut opts = Options::default();
-        opts.index = true;
 
         let name = str::from_utf8(args[2])
             .map_err(|err| Error::ModuleError(err.to_string()))?
@@ -108,15 +117,18 @@ unsafe impl<'vtab> VTab<'vtab> for SpartialIndex {
                 "index" => {
                     //
                     match value {
-                        "true" => {
-                            opts.index = true;
+                        "true" | "rtree" => {
+                            opts.index = IndexKind::RTree;
                         }
                         "false" => {
-                            opts.index = false;
+                            opts.index = IndexKind::Disabled;
+                        }
+                        "geohash" => {
+                            opts.index = IndexKind::GeoHash;
                         }
                         _ => {
                             return Err(Error::ModuleError(format!(
-                                "unrecognized geometry type '{param}'"
+                                "unrecognized index kind '{value}'"
                             )));
                         }
                     }
@@ -147,11 +159,22 @@ unsafe impl<'vtab> VTab<'vtab> for SpartialIndex {
             .ok_or_else(|| Error::ModuleError("Column not set".to_string()))?
             .to_string();
 
-        let mut tree = RStarTree::new(ty);
+        let conn = unsafe { Connection::from_handle(db.handle())? };
 
-        let schema = ty.schema().to_string();
+        let tree = if opts.index == IndexKind::GeoHash {
+            if ty != GeometryType::Point || srid != u32::from(SRID::WGS84) {
+                return Err(Error::ModuleError(
+                    "geohash index requires type = point and srid = 4326".to_string(),
+                ));
+            }
 
-        let conn = unsafe { Connection::from_handle(db.handle())? };
+            let conn = unsafe { Connection::from_handle(db.handle())? };
+            RStarTree::geohash(GeoHashTree::open(conn, &name)?)
+        } else {
+            RStarTree::new(ty)
+        };
+
+        let schema = ty.schema().to_string();
 
         let sql = replace(
             COLUMN_TRIGGER,
@@ -165,30 +188,22 @@ unsafe impl<'vtab> VTab<'vtab> for SpartialIndex {
 
         conn.execute_batch(&sql)?;
 
-        let mut stmt = conn.prepare(&format!("SELECT rowid, {column} FROM {table}"))?;
-        let mut rows = stmt.query([])?;
-
-        let mut items = Vec::new();
-        while let Some(row) = rows.next()? {
-            let id: u64 = row.get(0)?;
-            let geo: Geob = row.get(1)?;
-            items.push((id, geo));
+        let mut index = SpartialIndex {
+            base: sqlite3_vtab::default(),
+            tree,
+            name,
+            srid: srid.into(),
+            table,
+            column,
+            ty,
+        };
+
+        // The geohash column persists, it is only filled on create
+        if opts.index != IndexKind::GeoHash {
+            index.reload(&conn)?;
         }
 
-        tree.reload_batch(items)?;
-
-        Ok((
-            schema,
-            SpartialIndex {
-                base: sqlite3_vtab::default(),
-                tree,
-                name,
-                srid: srid.into(),
-                table,
-                column,
-                ty,
-            },
-        ))
+        Ok((schema, index))
     }
 
     fn best_index(&self, info: &mut rusqlite::vtab::IndexInfo) -> rusqlite::Result<()> {
@@ -197,7 +212,7 @@ unsafe impl<'vtab> VTab<'vtab> for SpartialIndex {
 
         let mut a_idx: [Option<usize>; 5] = [None, None, None, None, None];
 
-        let num_rows = self.tree.len();
+        let num_rows = self.tree.len()?;
         let mut est_cost = 0.;
 
         for (i, c) in info.constraints().enumerate() {
@@ -317,13 +332,35 @@ unsafe impl<'vtab> VTab<'vtab> for SpartialIndex {
 
 impl<'vtab> CreateVTab<'vtab> for SpartialIndex {
     const KIND: rusqlite::vtab::VTabKind = VTabKind::Default;
+
+    fn create(
+        db: &mut rusqlite::vtab::VTabConnection,
+        aux: Option<&Self::Aux>,
+        args: &[&[u8]],
+    ) -> rusqlite::Result<(String, Self)> {
+        let (schema, mut index) = Self::connect(db, aux, args)?;
+
+        if let RStarTree::GeoHash(_) = index.tree {
+            let conn = unsafe { Connection::from_handle(db.handle())? };
+            index.reload(&conn)?;
+        }
+
+        Ok((schema, index))
+    }
+
+    fn destroy(&self) -> rusqlite::Result<()> {
+        match &self.tree {
+            RStarTree::GeoHash(tree) => tree.destroy(),
+            _ => Ok(()),
+        }
+    }
 }
 
 impl<'vtab> UpdateVTab<'vtab> for SpartialIndex {
     fn delete(&mut self, arg: rusqlite::types::ValueRef<'_>) -> Result<()> {
         let id = arg.as_i64()?;
 
-        let _ = self.tree.remove(id as _);
+        self.tree.remove(id as _)?;
 
         Ok(())
     }
@@ -334,6 +371,7 @@ impl<'vtab> UpdateVTab<'vtab> for SpartialIndex {
 
         let Some(geo) = geo else { return Ok(0) };
 
+        self.validate(&geo)?;
         self.tree.insert(id, geo)?;
 
         Ok(0)
@@ -343,13 +381,47 @@ impl<'vtab> UpdateVTab<'vtab> for SpartialIndex {
         let rowid: u64 = args.get(0)?;
         let geob: Geob = args.get(3)?;
 
-        let _ = self.tree.remove(rowid as _);
+        self.validate(&geob)?;
+
+        self.tree.remove(rowid as _)?;
         self.tree.insert(rowid, geob)?;
 
         Ok(())
     }
 }
 
+impl SpartialIndex {
+    /// Load the geometries of the indexed column
+    fn reload(&mut self, conn: &Connection) -> Result<()> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT rowid, {} FROM {}",
+            self.column, self.table
+        ))?;
+        let mut rows = stmt.query([])?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next()? {
+            let id: u64 = row.get(0)?;
+            let geo: Geob = row.get(1)?;
+            items.push((id, geo));
+        }
+
+        self.tree.reload_batch(items)
+    }
+
+    fn validate(&self, geo: &Geob) -> Result<()> {
+        if !self.ty.is_valid(geo.kind().into()) {
+            return Err(Error::ModuleError(format!(
+                "geometry of type {} not allowed in index '{}'",
+                geo.kind(),
+                self.name
+            )));
+        }
+
+        Ok(())
+    }
+}
+
 struct CreateIndex<'a> {
     table: &'a str,
     column: &'a str,
@@ -377,3 +449,123 @@ impl<'a> Lookup for CreateIndex<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use geob::{Geob, SRID};
+    use rusqlite::Connection;
+
+    fn ids(conn: &Connection, sql: &str, geo: &Geob) -> Vec<i64> {
+        let mut stmt = conn.prepare(sql).unwrap();
+        let mut ids: Vec<i64> = stmt
+            .query_map([geo], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn geohash_index() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::register(&conn).unwrap();
+
+        conn.execute_batch(
+            "CREATE TABLE places(id INTEGER PRIMARY KEY, point);
+             INSERT INTO places VALUES (1, ST_FromText('SRID=4326;POINT(12.597135 55.673891)'));
+             INSERT INTO places VALUES (2, ST_FromText('SRID=4326;POINT(12.559285 55.691249)'));",
+        )
+        .unwrap();
+
+        conn.execute(
+            "CREATE VIRTUAL TABLE places_index USING SpartialIndex(table = 'places', \
+             column = 'point', srid = 4326, type = 'point', index = 'geohash')",
+            [],
+        )
+        .unwrap();
+
+        // The geohash lives in an ordinary indexed column
+        let index: String = conn
+            .query_row(
+                "SELECT sql FROM sqlite_master WHERE name = 'places_index_geohash_hash'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(index.contains("(hash)"));
+        let rows: i64 = conn
+            .query_row("SELECT count(*) FROM places_index_geohash", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(rows, 2);
+
+        conn.execute(
+            "INSERT INTO places VALUES (3, ST_FromText('SRID=4326;POINT(12.5378308 55.7036352)'))",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO places VALUES (4, ST_FromText('SRID=4326;POINT(-73.98 40.75)'))",
+            [],
+        )
+        .unwrap();
+
+        let lygten = Geob::new_point(SRID::WGS84, 12.5378308, 55.7036352).unwrap();
+        let near = "SELECT id FROM places_index WHERE distance < 3000 AND geometry = ?1";
+        assert_eq!(ids(&conn, near, &lygten), [2, 3]);
+
+        let plan: String = conn
+            .query_row(
+                "EXPLAIN QUERY PLAN SELECT id FROM places_index_geohash \
+                 WHERE hash >= 'u3b' AND hash < 'u3b~'",
+                [],
+                |row| row.get(3),
+            )
+            .unwrap();
+        assert!(plan.contains("places_index_geohash_hash"), "{plan}");
+
+        conn.execute("DELETE FROM places WHERE id = 2", []).unwrap();
+        conn.execute(
+            "UPDATE places SET point = ST_FromText('SRID=4326;POINT(12.54 55.70)') WHERE id = 4",
+            [],
+        )
+        .unwrap();
+        assert_eq!(ids(&conn, near, &lygten), [3, 4]);
+
+        let window =
+            Geob::from_text("SRID=4326;POLYGON((12 55, 13 55, 13 56, 12 56, 12 55))").unwrap();
+        assert_eq!(
+            ids(
+                &conn,
+                "SELECT id FROM places_index WHERE geometry MATCH ?1",
+                &window
+            ),
+            [1, 3, 4]
+        );
+
+        let err = conn
+            .execute(
+                "CREATE VIRTUAL TABLE bad USING SpartialIndex(table = 'places', \
+                 column = 'point', srid = 4326, type = 'point', index = 'quadtree')",
+                [],
+            )
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("unrecognized index kind 'quadtree'"),
+            "{err}"
+        );
+
+        conn.execute("DROP TABLE places_index", []).unwrap();
+        let shadow: i64 = conn
+            .query_row(
+                "SELECT count(*) FROM sqlite_master WHERE name = 'places_index_geohash'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(shadow, 0);
+    }
+}
@@ -3,7 +3,7 @@ use geob::{Geob, SRID, rstar::RStarPoint, types::GeometryRef};
 use rstar::{RTreeObject, SelectionFunction};
 use rusqlite::Error;
 
-use crate::index::types::GeometryType;
+use crate::index::{geohash::GeoHashTree, types::GeometryType};
 
 #[derive(Debug, PartialEq)]
 pub struct PointEntry {
@@ -30,11 +30,10 @@ impl rstar::PointDistance for PointEntry {
         &self,
         point: &<Self::Envelope as rstar::Envelope>::Point,
     ) -> <<Self::Envelope as rstar::Envelope>::Point as rstar::Point>::Scalar {
-        let distance = Haversine.distance(
+        Haversine.distance(
             geo::Point::new(self.point.x(), self.point.y()),
             geo::Point::new(point.x(), point.y()),
-        );
-        distance
+        )
     }
 }
 
@@ -42,7 +41,7 @@ trait WithId {
     fn id(&self) -> u64;
 }
 
-impl<'a, T: WithId> WithId for &'a T {
+impl<T: WithId> WithId for &T {
     fn id(&self) -> u64 {
         (**self).id()
     }
@@ -64,13 +63,14 @@ impl rstar::RTreeObject for GeometryEntry {
     type Envelope = rstar::AABB<RStarPoint>;
 
     fn envelope(&self) -> Self::Envelope {
-        self.point.envelope()
+        RTreeObject::envelope(&self.point)
     }
 }
 
 pub enum RStarTree {
     Point(rstar::RTree<PointEntry>),
     Any(rstar::RTree<GeometryEntry>),
+    GeoHash(GeoHashTree),
 }
 
 #[derive(Debug, Default)]
@@ -90,6 +90,11 @@ impl RStarTree {
         }
     }
 
+    /// Point index on a geohash column instead of an R-tree
+    pub fn geohash(tree: GeoHashTree) -> RStarTree {
+        RStarTree::GeoHash(tree)
+    }
+
     pub fn reload_batch<I: IntoIterator<Item = (u64, Geob)>>(
         &mut self,
         iter: I,
@@ -99,7 +104,10 @@ impl RStarTree {
                 let items = iter
                     .into_iter()
                     .map(|(id, geo)| {
-                        rusqlite::Result::<_, Error>::Ok(GeometryEntry { id, point: geo })
+                        rusqlite::Result::<_, Error>::Ok(GeometryEntry {
+                            id,
+                            point: geo.with_envelope(),
+                        })
                     })
                     .collect::<Result<Vec<_>, _>>()?;
 
@@ -129,6 +137,12 @@ impl RStarTree {
 
                 *tree = rstar::RTree::bulk_load(items);
             }
+            Self::GeoHash(tree) => {
+                tree.clear()?;
+                for (id, geo) in iter {
+                    tree.insert(id, &geo)?;
+                }
+            }
         }
 
         Ok(())
@@ -154,8 +168,12 @@ impl RStarTree {
                 });
             }
             Self::Any(tree) => {
-                tree.insert(GeometryEntry { id, point: geo });
+                tree.insert(GeometryEntry {
+                    id,
+                    point: geo.with_envelope(),
+                });
             }
+            Self::GeoHash(tree) => tree.insert(id, &geo)?,
         }
 
         Ok(())
@@ -188,6 +206,14 @@ impl RStarTree {
 
                     Box::new(iter) as Box<dyn Iterator<Item = (u64, Geob)> + 'a>
                 }
+                (Self::GeoHash(tree), GeometryRef::Point(point)) => {
+                    let iter = tree
+                        .within_distance(point.x(), point.y(), distance)?
+                        .into_iter()
+                        .map(move |(id, (x, y))| (id, Geob::new_point(srid, x, y).unwrap()));
+
+                    Box::new(iter) as Box<dyn Iterator<Item = (u64, Geob)> + 'a>
+                }
                 _ => {
                     return Err(rusqlite::Error::ModuleError(
                         "Index require as Point type".to_string(),
@@ -197,37 +223,43 @@ impl RStarTree {
         } else if let Some(geo) = geometry_match {
             match self {
                 Self::Any(tree) => Box::new(Box::new(
-                    tree.locate_in_envelope(&geo.envelope())
+                    tree.locate_in_envelope(&RTreeObject::envelope(&geo))
                         .map(|m| (m.id, m.point.clone())),
                 )
                     as Box<dyn Iterator<Item = (u64, Geob)> + 'a>),
-                Self::Point(tree) => {
-                    Box::new(tree.locate_in_envelope(&geo.envelope()).map(move |m| {
-                        (
-                            m.id,
-                            Geob::new_point(srid, m.point.x(), m.point.y()).unwrap(),
-                        )
-                    })) as Box<dyn Iterator<Item = (u64, Geob)> + 'a>
-                }
+                Self::Point(tree) => Box::new(
+                    tree.locate_in_envelope(&RTreeObject::envelope(&geo))
+                        .map(move |m| {
+                            (
+                                m.id,
+                                Geob::new_point(srid, m.point.x(), m.point.y()).unwrap(),
+                            )
+                        }),
+                )
+                    as Box<dyn Iterator<Item = (u64, Geob)> + 'a>,
+                Self::GeoHash(tree) => Box::new(tree.select_envelope(srid, &geo)?.into_iter()),
             }
         } else if let Some(geo) = geometry_eq {
             match self {
                 Self::Any(tree) => Box::new(Box::new(
-                    tree.locate_in_envelope(&geo.envelope())
+                    tree.locate_in_envelope(&RTreeObject::envelope(&geo))
                         .map(|m| (m.id, m.point.clone())),
                 )
                     as Box<dyn Iterator<Item = (u64, Geob)> + 'a>),
-                Self::Point(tree) => {
-                    Box::new(tree.locate_in_envelope(&geo.envelope()).map(move |m| {
-                        (
-                            m.id,
-                            Geob::new_point(srid, m.point.x(), m.point.y()).unwrap(),
-                        )
-                    })) as Box<dyn Iterator<Item = (u64, Geob)> + 'a>
-                }
+                Self::Point(tree) => Box::new(
+                    tree.locate_in_envelope(&RTreeObject::envelope(&geo))
+                        .map(move |m| {
+                            (
+                                m.id,
+                                Geob::new_point(srid, m.point.x(), m.point.y()).unwrap(),
+                            )
+                        }),
+                )
+                    as Box<dyn Iterator<Item = (u64, Geob)> + 'a>,
+                Self::GeoHash(tree) => Box::new(tree.select_envelope(srid, &geo)?.into_iter()),
             }
         } else {
-            self.iter(srid)
+            self.iter(srid)?
         };
 
         if let Some(id) = id_eq {
@@ -237,26 +269,31 @@ impl RStarTree {
         Ok(iter)
     }
 
-    pub fn remove(&mut self, id: u64) -> Option<u64> {
-        match self {
+    pub fn remove(&mut self, id: u64) -> rusqlite::Result<Option<u64>> {
+        Ok(match self {
             RStarTree::Point(rtree) => rtree
                 .remove_with_selection_function(RemoveAtId(id))
                 .map(|m| m.id),
             RStarTree::Any(rtree) => rtree
                 .remove_with_selection_function(RemoveAtId(id))
                 .map(|m| m.id),
-        }
+            RStarTree::GeoHash(tree) => tree.remove(id)?,
+        })
     }
 
-    pub fn len(&self) -> usize {
+    pub fn len(&self) -> rusqlite::Result<usize> {
         match self {
-            RStarTree::Point(rtree) => rtree.size(),
-            RStarTree::Any(rtree) => rtree.size(),
+            RStarTree::Point(rtree) => Ok(rtree.size()),
+            RStarTree::Any(rtree) => Ok(rtree.size()),
+            RStarTree::GeoHash(tree) => tree.len(),
         }
     }
 
-    pub fn iter<'a>(&'a self, srid: SRID) -> Box<dyn Iterator<Item = (u64, Geob)> + 'a> {
-        match self {
+    pub fn iter<'a>(
+        &'a self,
+        srid: SRID,
+    ) -> rusqlite::Result<Box<dyn Iterator<Item = (u64, Geob)> + 'a>> {
+        Ok(match self {
             RStarTree::Point(rtree) => {
                 let iter = rtree.iter().map(move |m| {
                     (
@@ -271,7 +308,14 @@ impl RStarTree {
                 let iter = rtree.iter().map(move |m| (m.id, m.point.clone()));
                 Box::new(iter)
             }
-        }
+            RStarTree::GeoHash(tree) => {
+                let iter = tree
+                    .iter()?
+                    .into_iter()
+                    .map(move |(id, (x, y))| (id, Geob::new_point(srid, x, y).unwrap()));
+                Box::new(iter)
+            }
+        })
     }
 }
 
@@ -1,12 +1,22 @@
 use rusqlite::{Connection, Result};
 
+mod affine;
+#[cfg(feature = "index")]
+mod cluster;
 mod functions;
+mod grid;
 #[cfg(feature = "index")]
 mod index;
+mod mvt;
 mod template;
 
 pub fn register(conn: &Connection) -> Result<bool> {
     functions::register_functions(conn)?;
+    mvt::register_functions(conn)?;
+    grid::register_functions(conn)?;
+    affine::register_functions(conn)?;
+    #[cfg(feature = "index")]
+    cluster::register_functions(conn)?;
     #[cfg(feature = "index")]
     index::register_module(conn)?;
 
@@ -0,0 +1,277 @@
+use geob::{
+    Geob, SRID,
+    mvt::{DEFAULT_BUFFER, DEFAULT_EXTENT, Layer, Value, tile_envelope},
+};
+use rusqlite::{
+    Connection, Error, Result,
+    functions::{Aggregate, Context, FunctionFlags},
+    types::ValueRef,
+};
+
+use crate::functions::ensure_same_srid;
+
+pub fn register_functions(conn: &Connection) -> Result<()> {
+    conn.create_scalar_function(
+        "ST_TileEnvelope",
+        3,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let (z, x, y) = (ctx.get(0)?, ctx.get(1)?, ctx.get(2)?);
+
+            let Some(envelope) = tile_envelope(z, x, y) else {
+                return Err(Error::UserFunctionError(
+                    format!("invalid tile: {z}/{x}/{y}").into(),
+                ));
+            };
+
+            Ok(envelope.to_polygon(SRID::WEB_MERCATOR))
+        },
+    )?;
+
+    conn.create_scalar_function(
+        "ST_AsMVTGeom",
+        -1,
+        FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            if !(2..=5).contains(&ctx.len()) {
+                return Err(Error::UserFunctionError(
+                    "ST_AsMVTGeom expects 2 to 5 arguments".into(),
+                ));
+            }
+
+            let geo: Geob = ctx.get(0)?;
+            let bounds: Geob = ctx.get(1)?;
+            ensure_same_srid(&geo, &bounds)?;
+
+            let extent = optional(ctx, 2)?.unwrap_or(DEFAULT_EXTENT);
+            let buffer = optional(ctx, 3)?.unwrap_or(DEFAULT_BUFFER);
+            let clip = optional(ctx, 4)?.unwrap_or(true);
+
+            let Some(bounds) = bounds.envelope() else {
+                return Err(Error::UserFunctionError("empty tile bounds".into()));
+            };
+
+            if !(bounds.width() > 0.0 && bounds.height() > 0.0) {
+                return Err(Error::UserFunctionError(
+                    format!(
+                        "tile bounds must have a positive width and height: {} x {}",
+                        bounds.width(),
+                        bounds.height()
+                    )
+                    .into(),
+                ));
+            }
+
+            Ok(geo.to_tile(&bounds, extent, buffer, clip))
+        },
+    )?;
+
+    conn.create_aggregate_function("ST_AsMVT", -1, FunctionFlags::SQLITE_DETERMINISTIC, AsMvt)?;
+
+    Ok(())
+}
+
+fn optional<T: rusqlite::types::FromSql>(ctx: &Context<'_>, idx: usize) -> Result<Option<T>> {
+    if idx < ctx.len() {
+        ctx.get(idx)
+    } else {
+        Ok(None)
+    }
+}
+
+/// `ST_AsMVT(name, [extent], geom, key, value, ...)` encodes the rows as a
+/// single layer tile. Geometries must already be in tile coordinates, see
+/// `ST_AsMVTGeom`. Rows with a NULL geometry are skipped and so are NULL or
+/// blob property values.
+struct AsMvt;
+
+impl Aggregate<Option<Layer>, Vec<u8>> for AsMvt {
+    fn init(&self, _ctx: &mut Context<'_>) -> Result<Option<Layer>> {
+        Ok(None)
+    }
+
+    fn step(&self, ctx: &mut Context<'_>, layer: &mut Option<Layer>) -> Result<()> {
+        // An odd number of arguments carries the extent
+        let (extent, geom) = match ctx.len() {
+            n if n >= 2 && n % 2 == 0 => (DEFAULT_EXTENT, 1),
+            n if n >= 3 => (ctx.get(1)?, 2),
+            _ => {
+                return Err(Error::UserFunctionError(
+                    "ST_AsMVT expects a layer name and a geometry".into(),
+                ));
+            }
+        };
+
+        let layer = match layer {
+            Some(layer) => layer,
+            None => layer.insert(Layer::new(ctx.get::<String>(0)?, extent)),
+        };
+
+        let Some(geo) = ctx.get::<Option<Geob>>(geom)? else {
+            return Ok(());
+        };
+
+        let mut properties = Vec::new();
+        for idx in (geom + 1..ctx.len()).step_by(2) {
+            let key: String = ctx.get(idx)?;
+            let value = match ctx.get_raw(idx + 1) {
+                ValueRef::Integer(n) => Value::Int(n),
+                ValueRef::Real(n) => Value::Double(n),
+                ValueRef::Text(text) => Value::String(String::from_utf8_lossy(text).into_owned()),
+                ValueRef::Null | ValueRef::Blob(_) => continue,
+            };
+            properties.push((key, value));
+        }
+
+        layer.add_feature(
+            &geo.geometry(),
+            None,
+            properties.iter().map(|(k, v)| (k.as_str(), v.clone())),
+        );
+
+        Ok(())
+    }
+
+    fn finalize(&self, _ctx: &mut Context<'_>, layer: Option<Option<Layer>>) -> Result<Vec<u8>> {
+        Ok(layer
+            .flatten()
+            .map(|layer| layer.encode())
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use geob::{
+        Geob,
+        mvt::{DEFAULT_EXTENT, Layer, Value},
+    };
+    use rusqlite::Connection;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::register(&conn).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE features(id INTEGER PRIMARY KEY, geom, name, rank);
+             INSERT INTO features VALUES
+                (1, ST_FromText('SRID=3857;POINT(25 17)'), 'a', 1),
+                (2, NULL, 'b', 2),
+                (3, ST_FromText('SRID=3857;LINESTRING(1 1, 4 5)'), NULL, 2.5);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn expected(extent: u32, properties: bool) -> Vec<u8> {
+        let point = Geob::from_text("SRID=3857;POINT(25 17)").unwrap();
+        let line = Geob::from_text("SRID=3857;LINESTRING(1 1, 4 5)").unwrap();
+
+        let mut layer = Layer::new("layer", extent);
+        if properties {
+            layer.add_feature(
+                &point.geometry(),
+                None,
+                [("name", Value::String("a".into())), ("rank", Value::Int(1))],
+            );
+            layer.add_feature(&line.geometry(), None, [("rank", Value::Double(2.5))]);
+        } else {
+            layer.add_feature(&point.geometry(), None, []);
+            layer.add_feature(&line.geometry(), None, []);
+        }
+        layer.encode()
+    }
+
+    fn tile(conn: &Connection, args: &str) -> rusqlite::Result<Vec<u8>> {
+        conn.query_row(
+            &format!("SELECT ST_AsMVT({args}) FROM (SELECT * FROM features ORDER BY id)"),
+            [],
+            |row| row.get(0),
+        )
+    }
+
+    #[test]
+    fn as_mvt_arguments() {
+        let conn = setup();
+
+        assert_eq!(
+            tile(&conn, "'layer', geom").unwrap(),
+            expected(DEFAULT_EXTENT, false)
+        );
+        assert_eq!(
+            tile(&conn, "'layer', 512, geom").unwrap(),
+            expected(512, false)
+        );
+        assert_eq!(
+            tile(&conn, "'layer', geom, 'name', name, 'rank', rank").unwrap(),
+            expected(DEFAULT_EXTENT, true)
+        );
+        assert_eq!(
+            tile(&conn, "'layer', 512, geom, 'name', name, 'rank', rank").unwrap(),
+            expected(512, true)
+        );
+
+        assert!(tile(&conn, "'layer'").is_err());
+
+        let empty: Vec<u8> = conn
+            .query_row(
+                "SELECT ST_AsMVT('layer', geom) FROM features WHERE 0",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn as_mvt_geom() {
+        let conn = setup();
+
+        let text: String = conn
+            .query_row(
+                "SELECT ST_ToText(ST_AsMVTGeom(
+                    ST_FromText('SRID=3857;LINESTRING(-30000000 0, 0 0, 0 10018754.171394622)'),
+                    ST_TileEnvelope(0, 0, 0)))",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(text, "SRID=0;LINESTRING(-256 2048, 2048 2048, 2048 1024)");
+
+        let outside: Option<Vec<u8>> = conn
+            .query_row(
+                "SELECT ST_AsMVTGeom(ST_FromText('SRID=3857;POINT(3e7 0)'), ST_TileEnvelope(0, 0, 0), 4096, 0)",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(outside, None);
+
+        assert!(
+            conn.query_row("SELECT ST_TileEnvelope(1, 2, 0)", [], |row| row
+                .get::<_, Vec<u8>>(0))
+                .is_err()
+        );
+
+        let geom = |geom: &str, bounds: &str| {
+            conn.query_row(
+                &format!("SELECT ST_AsMVTGeom(ST_FromText('{geom}'), {bounds})"),
+                [],
+                |row| row.get::<_, Option<Vec<u8>>>(0),
+            )
+        };
+
+        // The bounds are in web mercator
+        assert!(geom("SRID=4326;POINT(10 59)", "ST_TileEnvelope(0, 0, 0)").is_err());
+
+        for bounds in [
+            "SRID=3857;LINESTRING(0 0, 0 10)",
+            "SRID=3857;LINESTRING(0 0, 10 0)",
+            "SRID=3857;POINT(0 0)",
+        ] {
+            assert!(
+                geom("SRID=3857;POINT(0 0)", &format!("ST_FromText('{bounds}')")).is_err(),
+                "{bounds}"
+            );
+        }
+    }
+}
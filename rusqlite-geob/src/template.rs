@@ -86,7 +86,7 @@ where
 
                 let ident = reader.parse(Ident)?;
 
-                if let Err(_) = self.0.replace(ident.value.as_str(), &mut output) {
+                if self.0.replace(ident.value.as_str(), &mut output).is_err() {
                     return Err(
                         reader.error(format!("Lookup '{}' not found", ident.value.as_str()))
                     );
@@ -100,12 +100,11 @@ where
             }
         }
 
-        if current != reader.position() {
-            if let Some(slice) =
+        if current != reader.position()
+            && let Some(slice) =
                 Span::new(current, reader.position()).slice(reader.buffer().source().as_str())
-            {
-                output.push_str(slice);
-            }
+        {
+            output.push_str(slice);
         }
 
         Ok(output)